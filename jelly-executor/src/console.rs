@@ -0,0 +1,75 @@
+use std::cell::RefCell;
+
+use boa_engine::{
+    Context, JsResult, JsValue, NativeFunction, js_string, object::ObjectInitializer, property::Attribute,
+};
+use serde::{Deserialize, Serialize};
+
+/// The level of log
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+pub enum LogLevel {
+    /// console.log
+    #[serde(rename = "log")]
+    Log,
+    /// console.warn
+    #[serde(rename = "warn")]
+    Warn,
+    /// console.error
+    #[serde(rename = "error")]
+    Error,
+}
+
+/// The log printed by code
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct LogEntry {
+    /// level
+    pub level: LogLevel,
+    /// The arguments joined by space
+    pub message: String,
+}
+
+// The logs of current execution
+// ! The code is executed synchronously in one thread, the logs are taken after each execution
+thread_local! {
+    static LOGS: RefCell<Vec<LogEntry>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Take the captured logs
+pub(crate) fn take_logs() -> Vec<LogEntry> {
+    LOGS.with_borrow_mut(std::mem::take)
+}
+
+/// Register the console object
+pub(crate) fn register_console(context: &mut Context) -> JsResult<()> {
+    let console = ObjectInitializer::new(context)
+        .function(NativeFunction::from_fn_ptr(log), js_string!("log"), 0)
+        .function(NativeFunction::from_fn_ptr(warn), js_string!("warn"), 0)
+        .function(NativeFunction::from_fn_ptr(error), js_string!("error"), 0)
+        .build();
+    context.register_global_property(js_string!("console"), console, Attribute::all())
+}
+
+fn log(_: &JsValue, args: &[JsValue], _: &mut Context) -> JsResult<JsValue> {
+    capture(LogLevel::Log, args)
+}
+
+fn warn(_: &JsValue, args: &[JsValue], _: &mut Context) -> JsResult<JsValue> {
+    capture(LogLevel::Warn, args)
+}
+
+fn error(_: &JsValue, args: &[JsValue], _: &mut Context) -> JsResult<JsValue> {
+    capture(LogLevel::Error, args)
+}
+
+fn capture(level: LogLevel, args: &[JsValue]) -> JsResult<JsValue> {
+    let message = args
+        .iter()
+        .map(|arg| match arg.as_string() {
+            Some(text) => text.to_std_string_escaped(), // ? The string is printed without quotes
+            None => arg.display().to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    LOGS.with_borrow_mut(|logs| logs.push(LogEntry { level, message }));
+    Ok(JsValue::undefined())
+}
@@ -1,19 +1,51 @@
-use boa_engine::{Context, Source};
+use std::{
+    pin::pin,
+    task::{self, Poll, Waker},
+};
 
-use crate::error::ExecuteCodeError;
+use boa_engine::{
+    Context, JsError, JsResult, JsString, JsValue, Script, Source, object::builtins::JsArray, property::Attribute,
+    vm::RuntimeLimits,
+};
+use serde_json::Value;
+
+use crate::{
+    console::{register_console, take_logs},
+    error::{ExecuteCodeError, ExecuteErrorDetail},
+    options::ExecuteOptions,
+    report::ExecutionReport,
+    snapshot::Snapshot,
+    values::json_to_js_value,
+};
 
 /// Introduce JS code
 const BUNDLE_JS: &str = include_str!("../../jelly-types/dist/bundle.js");
 
+/// The cycles between the checks of deadline
+const BUDGET: u32 = 100_000;
+
+#[cfg(test)]
+thread_local! {
+    /// The count of loading bundle in this thread
+    pub(crate) static BUNDLE_LOADED: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
 /// Customized context
 pub struct CustomContext {
     context: Context,
+    /// The pristine state after bundle
+    snapshot: Snapshot,
+    /// The execution is aborted in the middle, the state of engine is broken
+    aborted: bool,
 }
 
 impl Default for CustomContext {
     fn default() -> Self {
         let mut context = Context::default();
 
+        #[allow(clippy::unwrap_used)] // ? SAFETY
+        register_console(&mut context).unwrap();
+
         let code = r##"
             const {
                 OpenJSON,
@@ -38,16 +70,57 @@ impl Default for CustomContext {
 
         #[allow(clippy::unwrap_used)] // ? SAFETY
         context.eval(Source::from_bytes(&code)).unwrap();
+        #[cfg(test)]
+        BUNDLE_LOADED.set(BUNDLE_LOADED.get() + 1);
 
-        Self { context }
+        #[allow(clippy::unwrap_used)] // ? SAFETY
+        let snapshot = Snapshot::record(&mut context).unwrap();
+
+        Self {
+            context,
+            snapshot,
+            aborted: false,
+        }
     }
 }
 
 impl CustomContext {
-    /// Execute code
+    /// Execute code, nothing is limited
     pub fn eval(&mut self, code: &str) -> Result<String, ExecuteCodeError> {
-        let source = Source::from_bytes(code);
-        let value = self.context.eval(source);
+        self.eval_with_options(code, &ExecuteOptions::unlimited())
+    }
+
+    /// Execute code with limits
+    pub fn eval_with_options(&mut self, code: &str, options: &ExecuteOptions) -> Result<String, ExecuteCodeError> {
+        self.eval_with_report(code, options).result
+    }
+
+    /// Execute code with limits, the logs and time are reported
+    pub fn eval_with_report(&mut self, code: &str, options: &ExecuteOptions) -> ExecutionReport {
+        take_logs(); // ? Clear the logs left by others
+
+        let start = self.context.host_hooks().utc_now();
+        let result = self.eval_limited(code, options, start);
+        let end = self.context.host_hooks().utc_now();
+
+        ExecutionReport {
+            result,
+            logs: take_logs(),
+            duration: end.saturating_sub(start).max(0) as u64,
+        }
+    }
+
+    fn eval_limited(&mut self, code: &str, options: &ExecuteOptions, now: i64) -> Result<String, ExecuteCodeError> {
+        options.check_deadline(now)?;
+
+        let limits = self.context.runtime_limits_mut();
+        limits.set_loop_iteration_limit(options.max_loop_iterations);
+        limits.set_recursion_limit(options.max_stack_depth);
+
+        let (value, parsed) = match Script::parse(Source::from_bytes(code), None, &mut self.context) {
+            Ok(script) => (self.evaluate(&script, options)?, true),
+            Err(e) => (Err(e), false),
+        };
 
         // Uniformly process the return data, the execution result itself must be a string
         let result = match value {
@@ -57,13 +130,98 @@ impl CustomContext {
             },
             Ok(boa_engine::value::JsValue::Undefined) => Err(ExecuteCodeError::Undefined),
             Ok(v) => Err(ExecuteCodeError::WrongOutput(format!("Not JsString Error: {:?}", v))),
-            Err(e) => Err(ExecuteCodeError::ExecuteError(format!("{}", e))),
+            Err(e) if e.as_native().is_some_and(|e| e.is_runtime_limit()) => {
+                Err(ExecuteCodeError::LimitExceeded(format!("{}", e)))
+            }
+            Err(e) => Err(ExecuteCodeError::ExecuteError(self.error_detail(e, !parsed))),
         };
-        if result.is_err() {
-            println!("code:");
-            println!("{}", code);
+
+        options.check_deadline(self.context.host_hooks().utc_now())?;
+        if let Ok(result) = &result {
+            options.check_json(result)?;
         }
 
         result
     }
+
+    /// Run the script, the deadline is checked each time the budget of cycles is spent
+    /// ! The aborted context is broken, it must be dropped
+    fn evaluate(&mut self, script: &Script, options: &ExecuteOptions) -> Result<JsResult<JsValue>, ExecuteCodeError> {
+        let hooks = self.context.host_hooks();
+        let mut future = pin!(script.evaluate_async_with_budget(&mut self.context, BUDGET));
+        let mut waker = task::Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(value) = future.as_mut().poll(&mut waker) {
+                return Ok(value);
+            }
+            if let Err(e) = options.check_deadline(hooks.utc_now()) {
+                self.aborted = true;
+                return Err(e);
+            }
+        }
+    }
+
+    /// The detail of error, the position of syntax error is relative to the whole code
+    /// ! The engine does not record the position of runtime error, it has no position
+    fn error_detail(&mut self, error: JsError, syntax: bool) -> ExecuteErrorDetail {
+        // 1. The thrown value which is not error
+        let opaque = error.as_opaque().cloned();
+        let Ok(native) = error.try_native(&mut self.context) else {
+            let value = opaque.unwrap_or_default();
+            return ExecuteErrorDetail::new("Uncaught", value.display().to_string());
+        };
+
+        let mut detail = ExecuteErrorDetail::new(native.kind.to_string(), native.message());
+
+        // 2. The name of thrown error
+        if let Some(object) = opaque.as_ref().and_then(JsValue::as_object) {
+            if let Some(name) = object
+                .get(JsString::from("name"), &mut self.context)
+                .ok()
+                .and_then(|v| v.as_string().and_then(|s| s.to_std_string().ok()))
+            {
+                detail.name = name;
+            }
+        }
+
+        // 3. The position of syntax error, the message of runtime error is kept as it is
+        if !syntax {
+            return detail;
+        }
+        if let Some((message, position)) = detail.message.rsplit_once(" at line ") {
+            if let Some((line, column)) = position.split_once(", col ") {
+                if let (Ok(line), Ok(column)) = (line.parse(), column.parse()) {
+                    detail.line = Some(line);
+                    detail.column = Some(column);
+                    detail.message = message.to_string();
+                }
+            }
+        }
+
+        detail
+    }
+
+    /// Set the values of arguments as a global array, it is removed when reset
+    pub(crate) fn set_args(&mut self, name: &str, values: &[Option<&Value>]) -> Result<(), ExecuteCodeError> {
+        let mut args = Vec::with_capacity(values.len());
+        for value in values {
+            args.push(match value {
+                Some(value) => json_to_js_value(value, &mut self.context)
+                    .map_err(|e| ExecuteCodeError::InvalidArgs(format!("{}", e)))?,
+                None => JsValue::undefined(),
+            });
+        }
+        let args = JsArray::from_iter(args, &mut self.context);
+        self.context
+            .register_global_property(JsString::from(name), args, Attribute::CONFIGURABLE)
+            .map_err(|e| ExecuteCodeError::InvalidArgs(format!("{}", e)))
+    }
+
+    /// Restore the pristine state after bundle
+    /// Return false if any state can not be restored, the context should be dropped
+    pub fn reset(&mut self) -> bool {
+        self.context.set_runtime_limits(RuntimeLimits::default()); // ? The restore is not limited
+
+        !self.aborted && self.snapshot.restore(&mut self.context)
+    }
 }
@@ -12,5 +12,40 @@ pub enum ExecuteCodeError {
     /// The execution results output type is wrong
     WrongOutput(String),
     /// Execute error
-    ExecuteError(String),
+    ExecuteError(ExecuteErrorDetail),
+    /// The limits of execute options are exceeded
+    LimitExceeded(String),
+}
+
+/// The detail of execute error
+/// The editor can underline the line of syntax error, the runtime error has only name and message
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ExecuteErrorDetail {
+    /// The name of js error, such as TypeError
+    pub name: String,
+    /// Error message
+    pub message: String,
+    /// The line of code, starts from 1
+    /// ! Only syntax error has position, the engine does not record the position of runtime error
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+    /// The column of line, starts from 1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<u32>,
+    /// The line of code which is failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+}
+
+impl ExecuteErrorDetail {
+    /// Error without position
+    pub fn new(name: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            message: message.into(),
+            line: None,
+            column: None,
+            snippet: None,
+        }
+    }
 }
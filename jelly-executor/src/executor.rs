@@ -0,0 +1,259 @@
+use serde_json::Value;
+
+use crate::{
+    context::CustomContext,
+    error::{ExecuteCodeError, ExecuteErrorDetail},
+    options::ExecuteOptions,
+    report::ExecutionReport,
+};
+
+/// The wrapper before code, the code starts from a new line
+const CODE_PREFIX: &str = r##"
+inner = (#args#) => {
+    let result = undefined;
+"##;
+
+/// The wrapper after code
+const CODE_SUFFIX: &str = r##"
+    return result;
+}
+
+result = inner(#values#);
+
+OpenJSON.stringify(result)
+"##;
+
+/// The global array of argument values, it is removed when reset
+const ARGS: &str = "__jelly_args__";
+
+/// The wrapper before validate code, the code starts from a new line
+const VALIDATE_CODE_PREFIX: &str = r##"
+inner = (data) => {
+    let result = undefined;
+"##;
+
+/// The wrapper after validate code
+const VALIDATE_CODE_SUFFIX: &str = r##"
+    return result;
+}
+
+result = inner(#value#);
+
+OpenJSON.stringify(result)
+"##;
+
+/// Executor owns a context, each execution starts from the pristine state after bundle
+/// The context is reset after execution, it is recreated only if the state can not be restored
+#[derive(Default)]
+pub struct Executor {
+    /// The pristine context for the next execution
+    context: Option<CustomContext>,
+}
+
+impl Executor {
+    /// new, the context of first execution is created in advance
+    pub fn new() -> Self {
+        Self {
+            context: Some(CustomContext::default()),
+        }
+    }
+
+    /// Whether the pristine context is ready
+    pub fn is_prepared(&self) -> bool {
+        self.context.is_some()
+    }
+
+    /// execute code
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - Code
+    /// * `args` - The string of parameter name and parameter. For example "[]" or "[[\"data\",\"{}\"]]"
+    /// * `options` - The limits of running
+    pub fn execute_code(
+        &mut self,
+        code: &str,
+        args: &str,
+        options: &ExecuteOptions,
+    ) -> Result<String, ExecuteCodeError> {
+        self.execute_code_with_report(code, args, options).result
+    }
+
+    /// execute code, the logs and time are reported
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - Code
+    /// * `args` - The string of parameter name and parameter. For example "[]" or "[[\"data\",\"{}\"]]"
+    /// * `options` - The limits of running
+    pub fn execute_code_with_report(&mut self, code: &str, args: &str, options: &ExecuteOptions) -> ExecutionReport {
+        let (prefix, suffix) = match wrap_code(args) {
+            Ok(wrapper) => wrapper,
+            Err(e) => return Err(e).into(),
+        };
+
+        self.eval(&prefix, code, &suffix, options)
+    }
+
+    /// execute code with values, the values are converted to js values directly
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - Code
+    /// * `args` - The name and value of parameters, None means undefined
+    /// * `options` - The limits of running
+    pub fn execute_code_with_values(
+        &mut self,
+        code: &str,
+        args: &[(&str, Option<&Value>)],
+        options: &ExecuteOptions,
+    ) -> Result<Value, ExecuteCodeError> {
+        let names = args.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+        let prefix = wrap_names(&names)?;
+        let values = args.iter().map(|(_, value)| *value).collect::<Vec<_>>();
+        let suffix = CODE_SUFFIX.replace("#values#", &format!("...{ARGS}"));
+
+        if let Err(e) = self
+            .context
+            .get_or_insert_with(CustomContext::default)
+            .set_args(ARGS, &values)
+        {
+            self.reset();
+            return Err(e);
+        }
+        let result = self.eval(&prefix, code, &suffix, options).result?;
+
+        serde_json::from_str(&result).map_err(|e| ExecuteCodeError::InvalidOutput(format!("{}: {}", e, result)))
+    }
+
+    /// execute validate code
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - Code
+    /// * `value` - Verified value. For example "{\"text\":\"text\""}"
+    /// * `options` - The limits of running
+    pub fn execute_validate_code(
+        &mut self,
+        code: &str,
+        value: &str,
+        options: &ExecuteOptions,
+    ) -> Result<String, ExecuteCodeError> {
+        self.execute_validate_code_with_report(code, value, options).result
+    }
+
+    /// execute validate code, the logs and time are reported
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - Code
+    /// * `value` - Verified value. For example "{\"text\":\"text\""}"
+    /// * `options` - The limits of running
+    pub fn execute_validate_code_with_report(
+        &mut self,
+        code: &str,
+        value: &str,
+        options: &ExecuteOptions,
+    ) -> ExecutionReport {
+        let suffix = VALIDATE_CODE_SUFFIX.replace(
+            "#value#",
+            &format!("OpenType.link_value_to_js_value(OpenJSON.parse({:?}))", value),
+        );
+
+        self.eval(VALIDATE_CODE_PREFIX, code, &suffix, options)
+    }
+
+    /// Create the pristine context for the next execution in advance
+    pub fn prepare(&mut self) {
+        self.context.get_or_insert_with(CustomContext::default);
+    }
+
+    /// Restore the pristine state, the context is dropped if it can not be restored
+    pub fn reset(&mut self) {
+        if self.context.as_mut().is_some_and(|context| !context.reset()) {
+            self.context = None;
+        }
+    }
+
+    fn eval(&mut self, prefix: &str, code: &str, suffix: &str, options: &ExecuteOptions) -> ExecutionReport {
+        let mut report = self
+            .context
+            .get_or_insert_with(CustomContext::default)
+            .eval_with_report(&format!("{prefix}{code}{suffix}"), options);
+        report.result = report.result.map_err(|e| match e {
+            ExecuteCodeError::ExecuteError(detail) => {
+                ExecuteCodeError::ExecuteError(locate(detail, prefix.matches('\n').count(), code))
+            }
+            e => e,
+        });
+        self.reset(); // ? The next execution must not see anything of this one
+        report
+    }
+}
+
+/// The wrapper of code with arguments
+fn wrap_code(args: &str) -> Result<(String, String), ExecuteCodeError> {
+    let args: Vec<(String, String)> =
+        serde_json::from_str(args).map_err(|e| ExecuteCodeError::InvalidArgs(format!("{}", e)))?;
+    let prefix = wrap_names(&args.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>())?;
+    let suffix = CODE_SUFFIX.replace(
+        "#values#",
+        &args
+            .iter()
+            .map(|(_, value)| {
+                if value.is_empty() {
+                    "undefined".to_string()
+                } else {
+                    format!("OpenJSON.parse({:?})", value)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+    );
+    Ok((prefix, suffix))
+}
+
+/// The wrapper before code with names of arguments
+/// ! The names are put into code, only identifiers are allowed
+fn wrap_names(names: &[&str]) -> Result<String, ExecuteCodeError> {
+    if let Some(name) = names.iter().find(|name| !is_identifier(name)) {
+        return Err(ExecuteCodeError::InvalidArgs(format!("invalid arg name: {name}")));
+    }
+    Ok(CODE_PREFIX.replace("#args#", &names.join(", ")))
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+/// Make the position relative to the code, the error out of code has no position
+fn locate(mut detail: ExecuteErrorDetail, offset: usize, code: &str) -> ExecuteErrorDetail {
+    let lines = code.lines().collect::<Vec<_>>();
+
+    // ? The engine has no position of runtime error, it is not guessed
+    if let Some(line) = detail.line {
+        match (line as usize).checked_sub(offset).filter(|line| 0 < *line) {
+            Some(line) if line <= lines.len() => detail.line = Some(line as u32),
+            Some(_) if !lines.is_empty() => {
+                // ? The code is not finished, the error is found in suffix
+                detail.line = Some(lines.len() as u32);
+                detail.column = None;
+            }
+            _ => {
+                detail.line = None;
+                detail.column = None;
+            }
+        }
+    }
+
+    detail.snippet = detail
+        .line
+        .and_then(|line| lines.get((line as usize).checked_sub(1)?))
+        .map(|line| line.to_string());
+
+    detail
+}
@@ -10,8 +10,11 @@
 
 use std::cell::RefCell;
 
-use context::CustomContext;
 use error::ExecuteCodeError;
+use executor::Executor;
+use options::ExecuteOptions;
+use report::ExecutionReport;
+use serde_json::Value;
 
 /// error
 pub mod error;
@@ -19,97 +22,116 @@ pub mod error;
 /// context
 pub mod context;
 
+/// options
+pub mod options;
+
+/// console
+pub mod console;
+
+/// report
+pub mod report;
+
+/// executor
+pub mod executor;
+
+/// pool
+pub mod pool;
+
+/// snapshot
+mod snapshot;
+
+/// values
+mod values;
+
 /// test
 #[cfg(test)]
 mod test;
 
-// Common context
+// The executor of thread, it is reset after each execution
+// ! The context is created in advance, so the executor is dropped before the gc of thread
 thread_local! {
-    static CONTENT: RefCell<CustomContext> = RefCell::default();
+    static EXECUTOR: RefCell<Executor> = RefCell::new(Executor::new());
 }
 
-/// execute code
+/// execute code, nothing is limited
 ///
 /// # Arguments
 ///
 /// * `code` - Code
 /// * `args` - The string of parameter name and parameter. For example "[]" or "[[\"data\",\"{}\"]]"
 pub fn execute_code(code: &str, args: &str) -> Result<String, ExecuteCodeError> {
-    let args: Vec<(String, String)> =
-        serde_json::from_str(args).map_err(|e| ExecuteCodeError::InvalidArgs(format!("{}", e)))?;
-    let code = r##"
-        inner = (#args#) => {
-            let result = undefined;
-
-            #code#
-
-            return result;
-        }
-
-        result = inner(#values#);
-
-        OpenJSON.stringify(result)
-    "##
-    .replace("#code#", code)
-    .replace(
-        "#args#",
-        &args
-            .iter()
-            .map(|(name, _)| name.to_owned())
-            .collect::<Vec<_>>()
-            .join(", "),
-    )
-    .replace(
-        "#values#",
-        &args
-            .iter()
-            .map(|(_, value)| {
-                if value.is_empty() {
-                    "undefined".to_string()
-                } else {
-                    format!("OpenJSON.parse({:?})", value)
-                }
-            })
-            .collect::<Vec<_>>()
-            .join(", "),
-    );
-
-    // let mut context = CustomContext::default();
-    // context.eval(&code)
-
-    // Use thread itself context
-    CONTENT.with_borrow_mut(|context| context.eval(&code))
+    execute_code_with_options(code, args, &ExecuteOptions::unlimited())
 }
 
-/// execute validate code
+/// execute code with limits
 ///
 /// # Arguments
 ///
 /// * `code` - Code
-/// * `value` - Verified value. For example "{\"text\":\"text\""}"
-pub fn execute_validate_code(code: &str, value: &str) -> Result<String, ExecuteCodeError> {
-    let code = r##"
-        inner = (data) => {
-            let result = undefined;
-
-            #code#
+/// * `args` - The string of parameter name and parameter. For example "[]" or "[[\"data\",\"{}\"]]"
+/// * `options` - The limits of running
+pub fn execute_code_with_options(code: &str, args: &str, options: &ExecuteOptions) -> Result<String, ExecuteCodeError> {
+    EXECUTOR.with_borrow_mut(|executor| executor.execute_code(code, args, options))
+}
 
-            return result;
-        }
+/// execute code, the logs of console and time are reported
+///
+/// # Arguments
+///
+/// * `code` - Code
+/// * `args` - The string of parameter name and parameter. For example "[]" or "[[\"data\",\"{}\"]]"
+/// * `options` - The limits of running
+pub fn execute_code_with_report(code: &str, args: &str, options: &ExecuteOptions) -> ExecutionReport {
+    EXECUTOR.with_borrow_mut(|executor| executor.execute_code_with_report(code, args, options))
+}
 
-        result = inner(#value#);
+/// execute code with values, the values are converted to js values directly
+///
+/// # Arguments
+///
+/// * `code` - Code
+/// * `args` - The name and value of parameters, None means undefined
+/// * `options` - The limits of running
+pub fn execute_code_with_values(
+    code: &str,
+    args: &[(&str, Option<&Value>)],
+    options: &ExecuteOptions,
+) -> Result<Value, ExecuteCodeError> {
+    EXECUTOR.with_borrow_mut(|executor| executor.execute_code_with_values(code, args, options))
+}
 
-        OpenJSON.stringify(result)
-    "##
-    .replace("#code#", code)
-    .replace(
-        "#value#",
-        &format!("OpenType.link_value_to_js_value(OpenJSON.parse({:?}))", value),
-    );
+/// execute validate code, nothing is limited
+///
+/// # Arguments
+///
+/// * `code` - Code
+/// * `value` - Verified value. For example "{\"text\":\"text\""}"
+pub fn execute_validate_code(code: &str, value: &str) -> Result<String, ExecuteCodeError> {
+    execute_validate_code_with_options(code, value, &ExecuteOptions::unlimited())
+}
 
-    // let mut context = CustomContext::default();
-    // context.eval(&code)
+/// execute validate code with limits
+///
+/// # Arguments
+///
+/// * `code` - Code
+/// * `value` - Verified value. For example "{\"text\":\"text\""}"
+/// * `options` - The limits of running
+pub fn execute_validate_code_with_options(
+    code: &str,
+    value: &str,
+    options: &ExecuteOptions,
+) -> Result<String, ExecuteCodeError> {
+    EXECUTOR.with_borrow_mut(|executor| executor.execute_validate_code(code, value, options))
+}
 
-    // Use thread itself context
-    CONTENT.with_borrow_mut(|context| context.eval(&code))
+/// execute validate code, the logs of console and time are reported
+///
+/// # Arguments
+///
+/// * `code` - Code
+/// * `value` - Verified value. For example "{\"text\":\"text\""}"
+/// * `options` - The limits of running
+pub fn execute_validate_code_with_report(code: &str, value: &str, options: &ExecuteOptions) -> ExecutionReport {
+    EXECUTOR.with_borrow_mut(|executor| executor.execute_validate_code_with_report(code, value, options))
 }
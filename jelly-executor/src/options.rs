@@ -0,0 +1,102 @@
+use boa_engine::vm::RuntimeLimits;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::ExecuteCodeError;
+
+/// Execute options, the limits of running code
+/// ! It is not a sandbox, the memory of execution is not limited, only the size of output is checked
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct ExecuteOptions {
+    /// Max iterations of each loop
+    pub max_loop_iterations: u64,
+    /// Max depth of function calls
+    pub max_stack_depth: usize,
+    /// Max length of each string in result, the strings made in execution are not limited
+    #[serde(alias = "max_string_length")]
+    pub max_output_string_length: usize,
+    /// Max length of each array in result, the arrays made in execution are not limited
+    #[serde(alias = "max_array_length")]
+    pub max_output_array_length: usize,
+    /// Timestamp in milliseconds, the execution is aborted if it is not finished before it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<u64>,
+}
+
+impl Default for ExecuteOptions {
+    fn default() -> Self {
+        Self {
+            max_loop_iterations: 1_000_000,
+            max_stack_depth: 512,
+            max_output_string_length: 10 * 1024 * 1024,
+            max_output_array_length: 1_000_000,
+            deadline: None,
+        }
+    }
+}
+
+impl ExecuteOptions {
+    /// Nothing is limited, the engine runs with its own default limits
+    pub fn unlimited() -> Self {
+        let limits = RuntimeLimits::default();
+        Self {
+            max_loop_iterations: limits.loop_iteration_limit(),
+            max_stack_depth: limits.recursion_limit(),
+            max_output_string_length: usize::MAX,
+            max_output_array_length: usize::MAX,
+            deadline: None,
+        }
+    }
+
+    /// Check whether the deadline is passed
+    pub(crate) fn check_deadline(&self, now: i64) -> Result<(), ExecuteCodeError> {
+        if let Some(deadline) = self.deadline {
+            if deadline as i64 <= now {
+                return Err(ExecuteCodeError::LimitExceeded(format!("deadline {deadline} exceeded")));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the size of output json, the text which is not json is checked as string
+    pub(crate) fn check_json(&self, json: &str) -> Result<(), ExecuteCodeError> {
+        if self.max_output_string_length == usize::MAX && self.max_output_array_length == usize::MAX {
+            return Ok(()); // ? Not limited
+        }
+        match serde_json::from_str::<Value>(json) {
+            Ok(value) => self.check_value(&value),
+            Err(_) => self.check_string(json),
+        }
+    }
+
+    /// Check the size of output value
+    fn check_value(&self, value: &Value) -> Result<(), ExecuteCodeError> {
+        match value {
+            Value::String(text) => self.check_string(text),
+            Value::Array(values) => {
+                if self.max_output_array_length < values.len() {
+                    return Err(ExecuteCodeError::LimitExceeded(format!(
+                        "array length {} exceeded the limit {}",
+                        values.len(),
+                        self.max_output_array_length
+                    )));
+                }
+                values.iter().try_for_each(|value| self.check_value(value))
+            }
+            Value::Object(values) => values.values().try_for_each(|value| self.check_value(value)),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_string(&self, text: &str) -> Result<(), ExecuteCodeError> {
+        if self.max_output_string_length < text.len() {
+            return Err(ExecuteCodeError::LimitExceeded(format!(
+                "string length {} exceeded the limit {}",
+                text.len(),
+                self.max_output_string_length
+            )));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+use std::{
+    cell::RefCell,
+    ops::{Deref, DerefMut},
+};
+
+use crate::executor::Executor;
+
+/// Executor pool, the prepared executors are reused to avoid loading bundle again
+/// ! The context can not be sent to other thread, the pool is used in one thread
+pub struct ExecutorPool {
+    executors: RefCell<Vec<Executor>>,
+    capacity: usize,
+}
+
+impl ExecutorPool {
+    /// new, the prepared executors are kept at most capacity
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            executors: RefCell::new(Vec::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    /// Create the executors in advance
+    pub fn warm_up(&self, count: usize) {
+        let mut executors = self.executors.borrow_mut();
+        while executors.len() < count.min(self.capacity) {
+            executors.push(Executor::new());
+        }
+    }
+
+    /// Take a prepared executor or create new one, it is returned when dropped if it is still prepared
+    pub fn acquire(&self) -> PooledExecutor<'_> {
+        let executor = self.executors.borrow_mut().pop().unwrap_or_default();
+        PooledExecutor {
+            pool: self,
+            executor: Some(executor),
+        }
+    }
+
+    /// The count of prepared executors
+    pub fn idle(&self) -> usize {
+        self.executors.borrow().len()
+    }
+}
+
+/// The executor taken from pool
+pub struct PooledExecutor<'a> {
+    pool: &'a ExecutorPool,
+    executor: Option<Executor>, // ! Always some before dropped
+}
+
+impl Deref for PooledExecutor<'_> {
+    type Target = Executor;
+
+    fn deref(&self) -> &Self::Target {
+        #[allow(clippy::unwrap_used)] // ? SAFETY
+        self.executor.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PooledExecutor<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        #[allow(clippy::unwrap_used)] // ? SAFETY
+        self.executor.as_mut().unwrap()
+    }
+}
+
+impl Drop for PooledExecutor<'_> {
+    fn drop(&mut self) {
+        if let Some(executor) = self.executor.take() {
+            let mut executors = self.pool.executors.borrow_mut();
+            if executor.is_prepared() && executors.len() < self.pool.capacity {
+                executors.push(executor);
+            }
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{console::LogEntry, error::ExecuteCodeError};
+
+/// The report of execution
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ExecutionReport {
+    /// The result of execution
+    pub result: Result<String, ExecuteCodeError>,
+    /// The logs printed by console
+    pub logs: Vec<LogEntry>,
+    /// The time of execution, milliseconds
+    pub duration: u64,
+}
+
+impl From<Result<String, ExecuteCodeError>> for ExecutionReport {
+    fn from(result: Result<String, ExecuteCodeError>) -> Self {
+        Self {
+            result,
+            logs: Vec::new(),
+            duration: 0,
+        }
+    }
+}
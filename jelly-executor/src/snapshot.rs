@@ -0,0 +1,164 @@
+use std::collections::HashSet;
+
+use boa_engine::{
+    Context, JsObject, JsValue, Source,
+    object::JsPrototype,
+    property::{PropertyDescriptor, PropertyKey},
+};
+
+/// The objects which are not reachable from global object
+const ROOTS: &str = r##"[
+    OpenJSON, OpenType, OpenNumber, OpenHex, Principal, OpenIc,
+    Object.getPrototypeOf([][Symbol.iterator]()),
+    Object.getPrototypeOf(new Map()[Symbol.iterator]()),
+    Object.getPrototypeOf(new Set()[Symbol.iterator]()),
+    Object.getPrototypeOf(''[Symbol.iterator]()),
+    Object.getPrototypeOf(/a/[Symbol.matchAll]('')),
+    Object.getPrototypeOf(function* () {}),
+    Object.getPrototypeOf(async function () {}),
+    Object.getPrototypeOf(async function* () {}),
+    Object.getPrototypeOf(Uint8Array),
+]"##;
+
+/// The properties of an object
+struct ObjectRecord {
+    target: JsObject,
+    prototype: JsPrototype,
+    extensible: bool,
+    keys: HashSet<PropertyKey>,
+    properties: Vec<(PropertyKey, PropertyDescriptor)>,
+}
+
+/// The pristine state of context
+/// All objects reachable from the global object and the roots are recorded, includes functions and prototypes
+/// ! The internal state such as the elements of map or the global lexical declarations of indirect eval is not recorded
+pub(crate) struct Snapshot {
+    records: Vec<ObjectRecord>,
+}
+
+impl Snapshot {
+    /// Record the state of context
+    pub(crate) fn record(context: &mut Context) -> Option<Self> {
+        let mut pending = vec![context.global_object()];
+        let roots = context.eval(Source::from_bytes(ROOTS)).ok()?;
+        let roots = roots.as_object()?.clone();
+        for key in roots.own_property_keys(context).ok()? {
+            pending.extend(property(&roots, &key).and_then(|p| p.value().and_then(|v| v.as_object().cloned())));
+        }
+
+        let mut visited = HashSet::new();
+        let mut records = Vec::new();
+        while let Some(target) = pending.pop() {
+            if !visited.insert(target.clone()) {
+                continue;
+            }
+            let record = ObjectRecord::record(target, context)?;
+            pending.extend(record.prototype.clone());
+            for (_, p) in &record.properties {
+                pending.extend(
+                    [p.value(), p.get(), p.set()]
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|v| v.as_object().cloned()),
+                );
+            }
+            records.push(record);
+        }
+
+        Some(Self { records })
+    }
+
+    /// Restore the recorded state
+    /// Return false if any state can not be restored
+    pub(crate) fn restore(&self, context: &mut Context) -> bool {
+        let mut restored = true;
+        for record in &self.records {
+            restored &= record.restore(context);
+        }
+
+        // The global variables of wrapper
+        restored
+            && context
+                .eval(Source::from_bytes("inner = undefined; result = undefined;"))
+                .is_ok()
+    }
+}
+
+impl ObjectRecord {
+    fn record(target: JsObject, context: &mut Context) -> Option<Self> {
+        let prototype = target.prototype();
+        let extensible = target.is_extensible(context).ok()?;
+        let keys = target.own_property_keys(context).ok()?;
+        let properties = keys
+            .iter()
+            .filter_map(|key| property(&target, key).map(|p| (key.clone(), p)))
+            .collect::<Vec<_>>();
+        Some(Self {
+            target,
+            prototype,
+            extensible,
+            keys: keys.into_iter().collect(),
+            properties,
+        })
+    }
+
+    fn restore(&self, context: &mut Context) -> bool {
+        let target = &self.target;
+
+        // ? The object can not be extensible again
+        if self.extensible && !target.is_extensible(context).unwrap_or_default() {
+            return false;
+        }
+
+        let mut restored = true;
+        if target.prototype() != self.prototype && !target.set_prototype(self.prototype.clone()) {
+            restored = false;
+        }
+
+        // 1. remove the added properties
+        let Ok(current) = target.own_property_keys(context) else {
+            return false;
+        };
+        for key in current {
+            if !self.keys.contains(&key) && !target.delete_property_or_throw(key, context).unwrap_or_default() {
+                restored = false;
+            }
+        }
+
+        // 2. restore the changed properties
+        for (key, origin) in &self.properties {
+            if property(target, key).is_some_and(|p| is_same(&p, origin)) {
+                continue;
+            }
+            if target
+                .define_property_or_throw(key.clone(), origin.clone(), context)
+                .is_err()
+            {
+                restored = false;
+            }
+        }
+
+        restored
+    }
+}
+
+/// The own property, the getter is not called
+fn property(target: &JsObject, key: &PropertyKey) -> Option<PropertyDescriptor> {
+    target.borrow().properties().get(key)
+}
+
+fn is_same(a: &PropertyDescriptor, b: &PropertyDescriptor) -> bool {
+    fn same(a: Option<&JsValue>, b: Option<&JsValue>) -> bool {
+        match (a, b) {
+            (Some(a), Some(b)) => JsValue::same_value(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+    same(a.value(), b.value())
+        && same(a.get(), b.get())
+        && same(a.set(), b.set())
+        && a.writable() == b.writable()
+        && a.enumerable() == b.enumerable()
+        && a.configurable() == b.configurable()
+}
@@ -1,8 +1,15 @@
-use crate::{execute_code, execute_validate_code};
+use crate::{
+    console::{LogEntry, LogLevel},
+    execute_code, execute_code_with_options, execute_code_with_report, execute_code_with_values, execute_validate_code,
+    execute_validate_code_with_options, execute_validate_code_with_report,
+    executor::Executor,
+    options::ExecuteOptions,
+    pool::ExecutorPool,
+};
 
 #[cfg(test)]
 mod tests {
-    use crate::error::ExecuteCodeError;
+    use crate::error::{ExecuteCodeError, ExecuteErrorDetail};
 
     use super::*;
 
@@ -63,9 +70,10 @@ mod tests {
         );
         assert_eq!(
             execute_code(r#"result = OpenJSON.stringify((() => {}));"#, "[]"),
-            Err(ExecuteCodeError::ExecuteError(
-                "Error: can not stringify function".into()
-            ))
+            Err(ExecuteCodeError::ExecuteError(ExecuteErrorDetail::new(
+                "Error",
+                "can not stringify function"
+            )))
         );
         assert_eq!(
             execute_code(r#"result = OpenJSON.stringify([1,2,3]);"#, "[]"),
@@ -100,9 +108,10 @@ mod tests {
 
         assert_eq!(
             execute_code(r#"result = OpenJSON.parse(null);"#, "[]"),
-            Err(ExecuteCodeError::ExecuteError(
-                "Error: json must be a string".to_string()
-            ))
+            Err(ExecuteCodeError::ExecuteError(ExecuteErrorDetail::new(
+                "Error",
+                "json must be a string"
+            )))
         );
 
         assert_eq!(
@@ -142,7 +151,10 @@ mod tests {
         );
         assert_eq!(
             execute_code(r#"result = OpenJSON.parse((() => {}));"#, "[]"),
-            Err(ExecuteCodeError::ExecuteError("Error: json must be a string".into()))
+            Err(ExecuteCodeError::ExecuteError(ExecuteErrorDetail::new(
+                "Error",
+                "json must be a string"
+            )))
         );
         assert_eq!(
             execute_code(r#"result = OpenJSON.parse("[1,2,3]");"#, "[]"),
@@ -278,8 +290,10 @@ mod tests {
             ),
             Ok(r#""wrong length""#.into())
         );
-        assert!(execute_validate_code(r#"let a : number = 1;"#, r#"{"text":"xxxx"}"#)
-            .is_err_and(|e| matches!(e, ExecuteCodeError::ExecuteError(_))),);
+        assert!(
+            execute_validate_code(r#"let a : number = 1;"#, r#"{"text":"xxxx"}"#)
+                .is_err_and(|e| matches!(e, ExecuteCodeError::ExecuteError(_))),
+        );
     }
 
     #[test]
@@ -296,4 +310,403 @@ mod tests {
             Ok(r#"1346269"#.into())
         );
     }
+
+    #[test]
+    fn test_limits() {
+        let options = ExecuteOptions {
+            max_loop_iterations: 1_000,
+            max_stack_depth: 64,
+            max_output_string_length: 16,
+            max_output_array_length: 4,
+            deadline: None,
+        };
+
+        // loop
+        assert!(
+            execute_validate_code_with_options(r#"while (true) {}"#, r#"{"text":"x"}"#, &options)
+                .is_err_and(|e| matches!(e, ExecuteCodeError::LimitExceeded(_)))
+        );
+        assert_eq!(
+            execute_code_with_options(r#"let i = 0; while (i < 100) { i++; } result = i;"#, "[]", &options),
+            Ok(r#"100"#.into())
+        );
+
+        // stack
+        assert!(
+            execute_code_with_options(r#"const f = (n) => f(n + 1); result = f(0);"#, "[]", &options)
+                .is_err_and(|e| matches!(e, ExecuteCodeError::LimitExceeded(_)))
+        );
+
+        // string and array of output
+        assert_eq!(
+            execute_code_with_options(r#"result = "x".repeat(17).length;"#, "[]", &options),
+            Ok(r#"17"#.into())
+        );
+        assert!(
+            execute_code_with_options(r#"result = "x".repeat(17);"#, "[]", &options)
+                .is_err_and(|e| matches!(e, ExecuteCodeError::LimitExceeded(_)))
+        );
+        assert!(
+            execute_code_with_options(r#"result = data;"#, r#"[["data","[1,2,3,4,5]"]]"#, &options)
+                .is_err_and(|e| matches!(e, ExecuteCodeError::LimitExceeded(_)))
+        );
+
+        // deadline
+        let options = ExecuteOptions {
+            deadline: Some(1),
+            ..Default::default()
+        };
+        assert!(
+            execute_code_with_options(r#"result = 1;"#, "[]", &options)
+                .is_err_and(|e| matches!(e, ExecuteCodeError::LimitExceeded(_)))
+        );
+        // ? Each loop is under the limit, the execution is aborted in engine
+        #[allow(clippy::unwrap_used)] // ? checked
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        let options = ExecuteOptions {
+            deadline: Some(now + 200),
+            ..Default::default()
+        };
+        assert!(
+            execute_code_with_options(
+                r#"for (let i = 0; i < 100000; i++) { for (let j = 0; j < 100000; j++) {} } result = 1;"#,
+                "[]",
+                &options
+            )
+            .is_err_and(|e| matches!(e, ExecuteCodeError::LimitExceeded(_)))
+        );
+        assert_eq!(
+            serde_json::from_str::<ExecuteOptions>(r#"{"max_string_length":16}"#)
+                .map(|o| o.max_output_string_length)
+                .ok(),
+            Some(16)
+        );
+
+        // The limits are not kept by the next execution
+        assert_eq!(
+            execute_code(r#"let i = 0; while (i < 2000) { i++; } result = i;"#, "[]"),
+            Ok(r#"2000"#.into())
+        );
+
+        // The legacy functions are not limited
+        let code = r#"let i = 0; while (i < 1000001) { i++; } result = "x".repeat(i).length;"#;
+        assert!(
+            execute_code_with_options(code, "[]", &ExecuteOptions::default())
+                .is_err_and(|e| matches!(e, ExecuteCodeError::LimitExceeded(_)))
+        );
+        assert_eq!(execute_code(code, "[]"), Ok(r#"1000001"#.into()));
+        assert_eq!(
+            execute_validate_code(r#"result = "x".repeat(10 * 1024 * 1024 + 1);"#, r#"{"text":"x"}"#).map(|r| r.len()),
+            Ok(10 * 1024 * 1024 + 3)
+        );
+    }
+
+    #[test]
+    fn test_isolation() {
+        let options = ExecuteOptions::default();
+        let mut executor = Executor::new();
+
+        // global
+        assert_eq!(
+            executor.execute_code(r#"polluted = 1; result = typeof polluted;"#, "[]", &options),
+            Ok(r#""number""#.into())
+        );
+        assert_eq!(
+            executor.execute_code(r#"result = typeof polluted;"#, "[]", &options),
+            Ok(r#""undefined""#.into())
+        );
+
+        // prototype and builtin
+        assert_eq!(
+            executor.execute_code(
+                r#"Array.prototype.map = () => 'polluted'; JSON.parse = () => 'polluted'; OpenJSON.stringify = () => '"polluted"'; result = 1;"#,
+                "[]",
+                &options
+            ),
+            Ok(r#""polluted""#.into())
+        );
+        assert_eq!(
+            executor.execute_code(r#"result = [1, 2].map((v) => v * 2);"#, r#"[["data","{}"]]"#, &options),
+            Ok(r#"[2,4]"#.into())
+        );
+
+        // The frozen builtin is not kept
+        assert!(
+            executor
+                .execute_code(
+                    r#"Object.freeze(Array.prototype); Array.prototype.x = 1; result = 1;"#,
+                    "[]",
+                    &options
+                )
+                .is_ok()
+        );
+        assert_eq!(
+            executor.execute_code(r#"Array.prototype.x = 1; result = [].x;"#, "[]", &options),
+            Ok(r#"1"#.into())
+        );
+        assert_eq!(
+            executor.execute_code(r#"result = [].x;"#, "[]", &options),
+            Err(ExecuteCodeError::Undefined)
+        );
+
+        // The intrinsics which are not reachable from global
+        assert_eq!(
+            executor.execute_code(
+                r#"Object.getPrototypeOf([][Symbol.iterator]()).next = () => ({ done: true }); result = 1;"#,
+                "[]",
+                &options
+            ),
+            Ok(r#"1"#.into())
+        );
+        assert_eq!(
+            executor.execute_code(
+                r#"let s = 0; for (const v of [1, 2, 3]) { s += v; } result = [s, new Uint8Array([1, 2]).join()];"#,
+                "[]",
+                &options
+            ),
+            Ok(r#"[6,"1,2"]"#.into())
+        );
+
+        // The properties of builtin functions
+        assert_eq!(
+            executor.execute_code(r#"Math.max.leaked = 1; result = Math.max.leaked;"#, "[]", &options),
+            Ok(r#"1"#.into())
+        );
+        assert_eq!(
+            executor.execute_code(r#"result = typeof Math.max.leaked;"#, "[]", &options),
+            Ok(r#""undefined""#.into())
+        );
+
+        // The free functions are isolated too
+        assert_eq!(execute_code(r#"leaked = 1; result = leaked;"#, "[]"), Ok(r#"1"#.into()));
+        assert_eq!(
+            execute_code(r#"result = typeof leaked;"#, "[]"),
+            Ok(r#""undefined""#.into())
+        );
+    }
+
+    #[test]
+    fn test_pool() {
+        let options = ExecuteOptions::default();
+        let pool = ExecutorPool::new(2);
+        pool.warm_up(1);
+        assert_eq!(pool.idle(), 1);
+        {
+            let mut executor1 = pool.acquire();
+            let mut executor2 = pool.acquire();
+            assert_eq!(pool.idle(), 0);
+            assert_eq!(
+                executor1.execute_code(r#"result = 1;"#, "[]", &options),
+                Ok(r#"1"#.into())
+            );
+            assert_eq!(
+                executor2.execute_validate_code(r#"result = data.length;"#, r#"{"text":"xxx"}"#, &options),
+                Ok(r#"3"#.into())
+            );
+        }
+        assert_eq!(pool.idle(), 2); // ? The used executors are reset and returned
+        {
+            #[allow(clippy::unwrap_used)] // ? checked
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64;
+            let options = ExecuteOptions {
+                deadline: Some(now + 100),
+                ..Default::default()
+            };
+            let mut executor = pool.acquire();
+            assert!(
+                executor
+                    .execute_code(
+                        r#"for (let i = 0; i < 100000; i++) { for (let j = 0; j < 100000; j++) {} } result = 1;"#,
+                        "[]",
+                        &options
+                    )
+                    .is_err()
+            );
+        }
+        assert_eq!(pool.idle(), 1); // ? The aborted context is dropped
+    }
+
+    #[test]
+    fn test_bundle_loaded() {
+        let loaded = std::thread::spawn(|| {
+            for i in 0..20 {
+                assert_eq!(execute_code(&format!("result = {i};"), "[]"), Ok(format!("{i}")));
+                assert_eq!(
+                    execute_validate_code(r#"result = data.length;"#, r#"{"text":"xxx"}"#),
+                    Ok(r#"3"#.into())
+                );
+            }
+            assert!(execute_code(r#"Object.freeze(Array.prototype); result = 1;"#, "[]").is_ok());
+            assert_eq!(execute_code(r#"result = 1;"#, "[]"), Ok(r#"1"#.into()));
+            crate::context::BUNDLE_LOADED.get()
+        })
+        .join();
+        assert_eq!(loaded.ok(), Some(2)); // ? Only the frozen builtin needs a new context
+    }
+
+    #[test]
+    fn test_error() {
+        let detail = |result: Result<String, ExecuteCodeError>| match result {
+            Err(ExecuteCodeError::ExecuteError(detail)) => Some(detail),
+            _ => None,
+        };
+
+        // syntax error
+        let error = detail(execute_code("const a = 1;\nlet b : number = 2;\nresult = a;", "[]"));
+        println!("{:?}", error);
+        assert!(error.is_some_and(|e| {
+            e.name == "SyntaxError"
+                && e.line == Some(2)
+                && e.column == Some(7)
+                && e.snippet.as_deref() == Some("let b : number = 2;")
+                && !e.message.contains(" at line ")
+        }));
+
+        // The code is not finished
+        let error = detail(execute_code("if (true) {\nresult = 1;", "[]"));
+        println!("{:?}", error);
+        assert!(error.is_some_and(|e| e.name == "SyntaxError" && e.line.is_none_or(|line| line <= 2)));
+
+        // runtime error
+        let error = detail(execute_code(
+            "// missing_value\nconst data = 1;\nresult = data + missing_value;",
+            "[]",
+        ));
+        println!("{:?}", error);
+        assert!(error.is_some_and(|e| {
+            e.name == "ReferenceError"
+                && e.line.is_none()
+                && e.column.is_none()
+                && e.snippet.is_none()
+                && e.message == "missing_value is not defined"
+        }));
+        let error = detail(execute_validate_code("throw new RangeError('bad');", r#"{"text":"x"}"#));
+        assert!(error.is_some_and(|e| e.name == "RangeError" && e.message == "bad" && e.line.is_none()));
+        let error = detail(execute_code(
+            "class MyError extends Error { name = 'MyError' }\nthrow new MyError('bad');",
+            "[]",
+        ));
+        assert!(error.is_some_and(|e| e.name == "MyError" && e.message == "bad"));
+        // ? The position in message of runtime error is not parsed
+        let error = detail(execute_code("\nthrow new SyntaxError('bad at line 1, col 2');", "[]"));
+        assert!(error.is_some_and(|e| {
+            e.name == "SyntaxError" && e.message == "bad at line 1, col 2" && e.line.is_none() && e.column.is_none()
+        }));
+        let error = detail(execute_code("const f = () => null.x;\nresult = f();", "[]"));
+        assert!(error.is_some_and(|e| e.name == "TypeError" && e.line.is_none() && e.snippet.is_none()));
+        let error = detail(execute_code("throw 'bad';", "[]"));
+        assert!(error.is_some_and(|e| e.name == "Uncaught" && e.message == r#""bad""#));
+    }
+
+    #[test]
+    fn test_report() {
+        let options = ExecuteOptions::default();
+        let report = execute_code_with_report(
+            r#"console.log("data", data, [1, 2]); console.warn(1n); console.error({ a: true }); result = data;"#,
+            r#"[["data","\"text\""]]"#,
+            &options,
+        );
+        println!("{:?}", report);
+        assert_eq!(report.result, Ok(r#""text""#.to_string()));
+        assert_eq!(
+            report.logs,
+            vec![
+                LogEntry {
+                    level: LogLevel::Log,
+                    message: "data text [ 1, 2 ]".into()
+                },
+                LogEntry {
+                    level: LogLevel::Warn,
+                    message: "1n".into()
+                },
+                LogEntry {
+                    level: LogLevel::Error,
+                    message: "{\n   a: true\n}".into()
+                },
+            ]
+        );
+
+        // The logs before error are kept
+        let report = execute_validate_code_with_report(
+            r#"console.log(data); throw new Error("bad");"#,
+            r#"{"text":"xxxx"}"#,
+            &options,
+        );
+        assert!(report.result.is_err());
+        assert_eq!(
+            report.logs.iter().map(|l| l.message.as_str()).collect::<Vec<_>>(),
+            vec!["xxxx"]
+        );
+
+        // The old api still works and logs are not left to next execution
+        assert_eq!(execute_code(r#"console.log(1); result = 1;"#, "[]"), Ok("1".into()));
+        let report = execute_code_with_report(r#"result = 2;"#, "[]", &options);
+        assert_eq!(report.result, Ok("2".into()));
+        assert!(report.logs.is_empty());
+
+        // console is restored after changed
+        assert_eq!(
+            execute_code(r#"console.log = undefined; result = 1;"#, "[]"),
+            Ok("1".into())
+        );
+        let report = execute_code_with_report(r#"console.log(3); result = 3;"#, "[]", &options);
+        assert_eq!(report.logs.len(), 1);
+    }
+
+    #[test]
+    fn test_values() {
+        let options = ExecuteOptions::default();
+        let data = serde_json::json!({
+            "text": "a\"b",
+            "list": [1, 2.5, true, null],
+            "big": { "__open_type__": "bigint", "value": "12345678901234567890" },
+            "bytes": { "__open_type__": "Uint8Array", "value": [1, 2, 3] },
+            "principal": { "__open_type__": "Principal", "value": "aaaaa-aa" },
+        });
+        assert_eq!(
+            execute_code_with_values(
+                r#"result = [data.text, data.list, typeof data.big, data.big + 1n, data.bytes instanceof Uint8Array, data.principal.toText(), typeof empty];"#,
+                &[("data", Some(&data)), ("empty", None)],
+                &options,
+            ),
+            Ok(serde_json::json!([
+                "a\"b",
+                [1, 2.5, true, null],
+                "bigint",
+                { "__open_type__": "bigint", "value": "12345678901234567891" },
+                true,
+                "aaaaa-aa",
+                "undefined"
+            ]))
+        );
+
+        // The name can not be injected
+        assert!(
+            execute_code_with_values(r#"result = 1;"#, &[("a) => {}; (b", None)], &options)
+                .is_err_and(|e| matches!(e, ExecuteCodeError::InvalidArgs(_)))
+        );
+        assert!(
+            execute_code(r#"result = 1;"#, r#"[["a) => {}; (b", ""]]"#)
+                .is_err_and(|e| matches!(e, ExecuteCodeError::InvalidArgs(_)))
+        );
+
+        // The wrong open type value
+        let wrong = serde_json::json!({ "__open_type__": "bigint", "value": "x" });
+        assert!(
+            execute_code_with_values(r#"result = 1;"#, &[("data", Some(&wrong))], &options)
+                .is_err_and(|e| matches!(e, ExecuteCodeError::InvalidArgs(_)))
+        );
+
+        // The arguments are not left
+        assert_eq!(
+            execute_code(r#"result = typeof __jelly_args__;"#, "[]"),
+            Ok(r#""undefined""#.into())
+        );
+    }
 }
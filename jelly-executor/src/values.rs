@@ -0,0 +1,64 @@
+use boa_engine::{
+    Context, JsBigInt, JsNativeError, JsObject, JsResult, JsString, JsValue, Source,
+    object::builtins::{JsArray, JsUint8Array},
+};
+use serde_json::Value;
+
+/// Open type mark of js value
+const OPEN_TYPE: &str = "__open_type__";
+
+/// Convert json value to js value, the open types are restored
+pub(crate) fn json_to_js_value(value: &Value, context: &mut Context) -> JsResult<JsValue> {
+    match value {
+        Value::Array(values) => {
+            let values = values
+                .iter()
+                .map(|value| json_to_js_value(value, context))
+                .collect::<JsResult<Vec<_>>>()?;
+            Ok(JsArray::from_iter(values, context).into())
+        }
+        Value::Object(values) => match values.get(OPEN_TYPE).and_then(Value::as_str) {
+            Some(open_type) => open_type_to_js_value(open_type, value, context),
+            None => {
+                let object = JsObject::with_object_proto(context.intrinsics());
+                for (key, value) in values {
+                    let value = json_to_js_value(value, context)?;
+                    object.create_data_property_or_throw(JsString::from(key.as_str()), value, context)?;
+                }
+                Ok(object.into())
+            }
+        },
+        _ => JsValue::from_json(value, context),
+    }
+}
+
+fn open_type_to_js_value(open_type: &str, value: &Value, context: &mut Context) -> JsResult<JsValue> {
+    let wrong = || JsNativeError::typ().with_message(format!("wrong open type value: {value}"));
+    match open_type {
+        "bigint" => {
+            let text = value.get("value").and_then(Value::as_str).ok_or_else(wrong)?;
+            Ok(JsBigInt::from_string(text).ok_or_else(wrong)?.into())
+        }
+        "Uint8Array" => {
+            let bytes = value
+                .get("value")
+                .and_then(Value::as_array)
+                .ok_or_else(wrong)?
+                .iter()
+                .map(|byte| byte.as_u64().and_then(|byte| u8::try_from(byte).ok()))
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(wrong)?;
+            Ok(JsUint8Array::from_iter(bytes, context)?.into())
+        }
+        _ => {
+            // ? The other open types are restored by the bundle
+            let parse = context.eval(Source::from_bytes("OpenJSON.parse"))?;
+            let parse = parse.as_callable().ok_or_else(wrong)?.clone();
+            parse.call(
+                &JsValue::undefined(),
+                &[JsString::from(value.to_string()).into()],
+                context,
+            )
+        }
+    }
+}
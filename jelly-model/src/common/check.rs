@@ -100,6 +100,9 @@ pub fn check_u64_anchor(anchor: &str, prefix: &str) -> Result<(Principal, u64),
     Ok((canister_id, id))
 }
 
+/// The name of variant, such as the name of argument, it must be checked by `is_valid_variant_name`
+pub type VariantName = String;
+
 /// check variant name
 #[inline]
 pub fn is_valid_variant_name(name: &str) -> bool {
@@ -15,6 +15,10 @@ pub mod model;
 /// store
 pub mod store;
 
+/// runtime
+#[cfg(feature = "runtime")]
+pub mod runtime;
+
 /// Determine whether it needs serialization
 #[inline]
 #[allow(unused)]
@@ -12,6 +12,7 @@ use super::common::trigger::ComponentTriggered;
 use super::common::types::LinkType;
 use super::node::TrimmedNode;
 use super::types::check::{CheckFunction, CheckedAnchors, CheckedCodeItem, CheckedCombined};
+use super::types::diagnostic::{Diagnostic, DiagnosticSeverity};
 use super::CombinedMetadata;
 use super::LinkComponent;
 
@@ -33,6 +34,9 @@ mod check_single;
 
 mod checked;
 
+/// incremental check
+pub mod incremental;
+
 /// test
 #[cfg(test)]
 mod test;
@@ -138,48 +142,123 @@ pub fn find_template_origin_codes(nodes: &[TrimmedNode]) -> Result<Vec<CheckedCo
 /// final check
 /// check function must has all data
 pub fn check<F: CheckFunction>(components: &[LinkComponent], fetch: &F) -> Result<CheckedCombined, LinkError> {
-    // 0. Check whether it is empty
-    check_empty::check_empty(components)?;
+    check_stages(components, fetch, &mut Report::FailFast)?
+        .ok_or_else(|| system_error("check stopped without error".to_string()))
+}
 
-    // 1. Check whether the component ID is repeated
-    let all_components = check_id::check_component_id(components)?;
+/// check with diagnostics
+/// All problems are reported instead of the first error, empty if the combined is valid
+/// ! The later stages are skipped after fatal diagnostic or wrong inlets
+pub fn check_diagnostics<F: CheckFunction>(components: &[LinkComponent], fetch: &F) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    if let Err(error) = check_stages(components, fetch, &mut Report::Collect(&mut diagnostics)) {
+        diagnostics.push(Diagnostic::fatal(error_component(&error), error)); // ? Never happens when collecting
+    }
+    diagnostics
+}
 
-    // 2. Is there a cycle reference
-    check_circular::check_circular_reference(components, &all_components)?;
+/// The way of reporting the problems of stages
+enum Report<'a> {
+    /// Stop at the first problem
+    FailFast,
+    /// Collect all problems, stop at fatal one
+    Collect(&'a mut Vec<Diagnostic>),
+}
 
-    // 3. Whether the basic logic between the inspection components is self -consistent
+impl Report<'_> {
+    /// Report a problem, true if the later stages should be skipped
+    fn report(&mut self, diagnostic: Diagnostic) -> Result<bool, LinkError> {
+        match self {
+            Report::FailFast => Err(diagnostic.error),
+            Report::Collect(diagnostics) => {
+                let fatal = diagnostic.severity == DiagnosticSeverity::Fatal;
+                diagnostics.push(diagnostic);
+                Ok(fatal)
+            }
+        }
+    }
+
+    /// Whether any problem is reported
+    fn is_empty(&self) -> bool {
+        match self {
+            Report::FailFast => true,
+            Report::Collect(diagnostics) => diagnostics.is_empty(),
+        }
+    }
+}
+
+/// All stages of check, None if the stages are skipped
+fn check_stages<F: CheckFunction>(
+    components: &[LinkComponent],
+    fetch: &F,
+    report: &mut Report<'_>,
+) -> Result<Option<CheckedCombined>, LinkError> {
+    // 0. Check whether it is empty
+    if let Err(error) = check_empty::check_empty(components) {
+        report.report(Diagnostic::fatal(None, error))?;
+        return Ok(None);
+    }
 
-    // 3.1 params cannot be repeated
-    check_names::check_param_names(components)?;
-    // 3.2 form cannot be repeated
-    check_names::check_form_names(components)?;
-    // 3.3 identity cannot be repeated
-    check_names::check_identity_names(components)?;
-    // 3.4 interaction cannot be repeated
-    check_names::check_interaction_names(components)?;
+    // 1. Check whether the component ID is repeated
+    let all_components = match check_id::check_component_id(components) {
+        Ok(all_components) => all_components,
+        Err(error) => {
+            report.report(Diagnostic::fatal(error_component(&error), error))?;
+            return Ok(None);
+        }
+    };
 
-    // 4. The output type can only have only one at most
-    let output = check_output::check_output_number(components)?; // Record output type
+    // 2. params, form, identity, interaction and variable cannot be repeated
+    for diagnostic in check_names::find_duplicate_names(components) {
+        report.report(diagnostic)?;
+    }
 
-    // 5. Check whether the Inlets of each component is wrong
-    {
-        let mut checked = HashSet::new();
-        for component in components {
-            check_inlets::check_single_component_inlets(component, &all_components, &mut checked)?;
+    // 3. Check whether the Inlets of each component is wrong, the later stages need all inlets
+    let unknown = check_inlets::find_unknown_inlets(components, &all_components);
+    if !unknown.is_empty() {
+        for diagnostic in unknown {
+            report.report(diagnostic)?;
         }
+        return Ok(None);
     }
 
+    // 4. Is there a cycle reference
+    if let Err(error) = check_circular::check_circular_reference(components, &all_components) {
+        report.report(Diagnostic::fatal(error_component(&error), error))?;
+        return Ok(None);
+    }
+
+    // 5. The output type can only have only one at most
+    let output = match check_output::check_output_number(components) {
+        Ok(output) => output, // Record output type
+        Err(error) => {
+            let outputs = components
+                .iter()
+                .filter(|c| matches!(c, LinkComponent::Output(_)))
+                .map(|c| c.id())
+                .collect();
+            report.report(Diagnostic::error(None, error).with_related(outputs))?;
+            None
+        }
+    };
+
     // 6. The cither error that may exist after the inspection component is collected
-    let colors = check_afflux::check_afflux(&all_components)?;
+    let colors = match check_afflux::check_afflux(&all_components) {
+        Ok(colors) => colors,
+        Err(error) => {
+            report.report(Diagnostic::fatal(error_component(&error), error))?;
+            return Ok(None);
+        }
+    };
 
-    // 7. Whether each component consistent
+    // 7. Whether each component consistent, the wrong component does not stop others
     let mut checked = HashMap::new();
     let mut codes = HashMap::new(); // Records that need to be stored separately
     let mut apis = HashMap::new(); // Records need to be stored separately
     {
         let mut triggers = HashMap::new(); // Record component's own trigger information
         for component in components {
-            check_single::check_single_component(
+            if let Err(error) = check_single::check_single_component(
                 component,
                 &all_components,
                 &colors,
@@ -188,18 +267,47 @@ pub fn check<F: CheckFunction>(components: &[LinkComponent], fetch: &F) -> Resul
                 &mut codes,
                 &mut apis,
                 &mut checked,
-            )?;
+            ) {
+                report.report(Diagnostic::error(Some(component.id()), error))?;
+            }
         }
-        for trigger in triggers.values() {
-            trigger.check(&triggers, &all_components, &colors)?;
+        let mut ids = triggers.keys().copied().collect::<Vec<_>>();
+        ids.sort();
+        for id in ids {
+            if let Some(Err(error)) = triggers.get(&id).map(|t| t.check(&triggers, &all_components, &colors)) {
+                report.report(Diagnostic::error(Some(id), error))?;
+            }
         }
     }
+    if !report.is_empty() {
+        return Ok(None);
+    }
 
-    // 6. Calculate the return result
-    let canister_id = fetch.canister_id().map_err(system_error)?;
-    let result = checked::parse_checked(canister_id, components, codes, apis, checked, output)?;
+    // 8. Calculate the return result
+    let result = fetch
+        .canister_id()
+        .map_err(system_error)
+        .and_then(|canister_id| checked::parse_checked(canister_id, components, codes, apis, checked, output));
+    match result {
+        Ok(result) => Ok(Some(result)),
+        Err(error) => {
+            report.report(Diagnostic::fatal(None, error))?;
+            Ok(None)
+        }
+    }
+}
 
-    Ok(result)
+/// The component of error between components
+fn error_component(error: &LinkError) -> Option<ComponentId> {
+    match error {
+        LinkError::InvalidComponentId { id }
+        | LinkError::DuplicateComponentId { id }
+        | LinkError::CircularReference { id } => Some(*id),
+        LinkError::AffluxComponentId { from, .. }
+        | LinkError::InvalidEndpoint { from, .. }
+        | LinkError::UnknownComponentOrNotRefer { from: Some(from), .. } => Some(*from),
+        _ => None,
+    }
 }
 
 /// check template
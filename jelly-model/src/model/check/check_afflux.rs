@@ -15,8 +15,16 @@ pub(super) fn check_afflux<'a>(
     components: &HashMap<ComponentId, &'a LinkComponent>,
 ) -> Result<HashMap<ComponentId, ComponentColor<'a>>, LinkError> {
     // See the conflict of inspection, disconnect the empty conditions of the air conditioning method to prevent the introduction of empty data
-    let mut colors: HashMap<ComponentId, ComponentColor<'a>> = HashMap::with_capacity(components.len());
+    check_afflux_with(components, HashMap::with_capacity(components.len()))
+}
 
+/// Continue dyeing from the known colors, only the components without color are processed
+/// ! The known colors must be calculated with the same upstream components
+#[inline]
+pub(super) fn check_afflux_with<'a>(
+    components: &HashMap<ComponentId, &'a LinkComponent>,
+    mut colors: HashMap<ComponentId, ComponentColor<'a>>,
+) -> Result<HashMap<ComponentId, ComponentColor<'a>>, LinkError> {
     let mut changed;
     loop {
         changed = false; // Set it first to false
@@ -30,6 +38,12 @@ pub(super) fn check_afflux<'a>(
 
             let mut endpoints = Vec::with_capacity(inlets.len());
             for inlet in inlets.iter() {
+                if components
+                    .get(&inlet.id)
+                    .is_some_and(|c| component.is_loop_back_edge(c))
+                {
+                    continue; // ? The loop body returns to the loop start
+                }
                 let info = match colors.get(&inlet.id) {
                     Some(info) => info,
                     None => continue 'outer,
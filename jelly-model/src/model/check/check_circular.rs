@@ -33,7 +33,7 @@ pub(super) fn check_circular_reference(
             for inlet in inlets {
                 // Travel every dependencies
                 let c = components.get(&inlet.id).ok_or(LinkError::UnknownComponentOrNotRefer {
-                    from: None,
+                    from: Some(id),
                     id: inlet.id,
                 })?;
                 let max_outputs = c.count_outputs(); // How many introduction points
@@ -43,6 +43,9 @@ pub(super) fn check_circular_reference(
                         inlet: *inlet,
                     });
                 }
+                if component.is_loop_back_edge(c) {
+                    continue; // ? The loop body returns to the loop start
+                }
                 check_inlets(c, components, visited, checked)?; // Recursively check the introduction point of this component
             }
         }
@@ -1,6 +1,6 @@
 use std::collections::{HashMap, HashSet};
 
-use super::{ComponentId, LinkComponent, LinkError};
+use super::{ComponentId, Diagnostic, LinkComponent, LinkError};
 
 /// Check whether the reference of a single component is wrong
 #[inline]
@@ -22,6 +22,9 @@ pub(super) fn check_single_component_inlets(
                 from: Some(id),
                 id: inlet.id,
             })?;
+            if component.is_loop_back_edge(c) {
+                continue; // ? The loop body returns to the loop start
+            }
             check_single_component_inlets(c, components, checked)?;
         }
 
@@ -43,3 +46,24 @@ pub(super) fn check_single_component_inlets(
 
     Ok(())
 }
+
+/// Find all unknown inlets of every component
+pub(super) fn find_unknown_inlets(
+    components: &[LinkComponent],
+    all_components: &HashMap<ComponentId, &LinkComponent>,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for component in components {
+        let id = component.id();
+        for inlet in component.get_inlets().into_iter().flatten() {
+            if !all_components.contains_key(&inlet.id) {
+                let error = LinkError::UnknownComponentOrNotRefer {
+                    from: Some(id),
+                    id: inlet.id,
+                };
+                diagnostics.push(Diagnostic::error(Some(id), error).with_related(vec![inlet.id]));
+            }
+        }
+    }
+    diagnostics
+}
@@ -1,6 +1,6 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-use super::{LinkComponent, LinkError};
+use super::{ComponentId, Diagnostic, LinkComponent, LinkError};
 
 /// check param name
 #[inline]
@@ -61,3 +61,71 @@ pub(super) fn check_interaction_names(components: &[LinkComponent]) -> Result<()
     }
     Ok(())
 }
+
+/// check variable name
+#[inline]
+pub(super) fn check_variable_names(components: &[LinkComponent]) -> Result<(), LinkError> {
+    let mut names = HashSet::new();
+    for component in components {
+        if let Some(name) = component.get_variable_name() {
+            if names.contains(&name) {
+                return Err(LinkError::DuplicateVariableName { name: name.to_owned() });
+            }
+            names.insert(name);
+        }
+    }
+    Ok(())
+}
+
+/// Get the name of component
+type GetName = fn(&LinkComponent) -> Option<&String>;
+
+/// The error of repeated name
+type NameError = fn(String) -> LinkError;
+
+/// Find all repeated names, every repeated component is reported with the components of the same name
+pub(super) fn find_duplicate_names(components: &[LinkComponent]) -> Vec<Diagnostic> {
+    let names: [(GetName, NameError); 5] = [
+        (LinkComponent::get_param_name, |name| LinkError::DuplicateParamName {
+            name,
+        }),
+        (LinkComponent::get_form_name, |name| LinkError::DuplicateFormName {
+            name,
+        }),
+        (LinkComponent::get_identity_name, |name| {
+            LinkError::DuplicateIdentityName { name }
+        }),
+        (LinkComponent::get_interaction_name, |name| {
+            LinkError::DuplicateInteractionName { name }
+        }),
+        (LinkComponent::get_variable_name, |name| {
+            LinkError::DuplicateVariableName { name }
+        }),
+    ];
+    names
+        .into_iter()
+        .flat_map(|(get_name, error)| find_duplicates(components, get_name, error))
+        .collect()
+}
+
+fn find_duplicates(components: &[LinkComponent], get_name: GetName, error: NameError) -> Vec<Diagnostic> {
+    let mut names: HashMap<&String, Vec<ComponentId>> = HashMap::new();
+    for component in components {
+        if let Some(name) = get_name(component) {
+            names.entry(name).or_default().push(component.id());
+        }
+    }
+    let mut diagnostics = Vec::new();
+    for component in components {
+        let id = component.id();
+        let Some((name, ids)) = get_name(component).and_then(|name| names.get_key_value(name)) else {
+            continue;
+        };
+        if ids.first().is_some_and(|first| *first != id) {
+            // ? The first one is not repeated
+            let related = ids.iter().filter(|other| **other != id).copied().collect();
+            diagnostics.push(Diagnostic::error(Some(id), error((*name).clone())).with_related(related));
+        }
+    }
+    diagnostics
+}
@@ -0,0 +1,225 @@
+use std::{
+    collections::{HashMap, HashSet, hash_map::Entry},
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use super::{
+    ApiData, ApiDataAnchor, CheckFunction, CheckedCombined, CodeData, CodeDataAnchor, ComponentColor, ComponentId,
+    ComponentTriggered, LinkComponent, LinkError, check_afflux, check_circular, check_empty, check_id, check_inlets,
+    check_names, check_output, check_single, checked, system_error,
+};
+
+/// The checked result of a single component
+struct CachedComponent {
+    /// Content hash of the origin component, always checked again if it can not be hashed
+    hash: Option<u64>,
+    /// Dyeing information
+    color: HashMap<ComponentId, HashSet<u32>>,
+    /// Conflict components
+    conflict: HashSet<ComponentId>,
+    /// The checked component, the output type is resolved
+    checked: LinkComponent,
+    /// Trigger information of component
+    trigger: Option<ComponentTriggered>,
+    /// Records that need to be stored separately
+    codes: HashMap<CodeDataAnchor, CodeData>,
+    /// Records that need to be stored separately
+    apis: HashMap<ApiDataAnchor, ApiData>,
+}
+
+/// Incremental checker for the editor
+/// The result of each component is cached by content hash,
+/// only the edited components and their downstream are checked again
+/// ! The check function must not change between checks, otherwise clear it
+#[derive(Default)]
+pub struct IncrementalChecker {
+    /// cached results
+    cache: HashMap<ComponentId, CachedComponent>,
+    /// The components checked in the last check
+    rechecked: Vec<ComponentId>,
+}
+
+impl IncrementalChecker {
+    /// The components checked in the last check, in order of components
+    pub fn rechecked(&self) -> &[ComponentId] {
+        &self.rechecked
+    }
+
+    /// Drop all cached results
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.rechecked.clear();
+    }
+
+    /// Check again, the result is the same as the full check
+    pub fn check<F: CheckFunction>(
+        &mut self,
+        components: &[LinkComponent],
+        fetch: &F,
+    ) -> Result<CheckedCombined, LinkError> {
+        self.rechecked.clear();
+
+        // ? Invalidate first, the cache is consistent with components even if the check fails
+        let hashes = self.invalidate(components);
+
+        // 0. Check whether it is empty
+        check_empty::check_empty(components)?;
+
+        // 1. Check whether the component ID is repeated
+        let all_components = check_id::check_component_id(components)?;
+
+        // 2. Is there a cycle reference
+        check_circular::check_circular_reference(components, &all_components)?;
+
+        // 3. Names cannot be repeated
+        check_names::check_param_names(components)?;
+        check_names::check_form_names(components)?;
+        check_names::check_identity_names(components)?;
+        check_names::check_interaction_names(components)?;
+        check_names::check_variable_names(components)?;
+
+        // 4. The output type can only have only one at most
+        let output = check_output::check_output_number(components)?;
+
+        // 5. Check whether the Inlets of each component is wrong
+        {
+            let mut checked = HashSet::new();
+            for component in components {
+                check_inlets::check_single_component_inlets(component, &all_components, &mut checked)?;
+            }
+        }
+
+        // 6. Dyeing from the cached colors
+        let mut known = HashMap::with_capacity(all_components.len());
+        for (id, cached) in &self.cache {
+            if let Some(component) = all_components.get(id) {
+                known.insert(
+                    *id,
+                    ComponentColor {
+                        id: *id,
+                        component,
+                        color: cached.color.clone(),
+                        conflict: cached.conflict.clone(),
+                    },
+                );
+            }
+        }
+        let colors = check_afflux::check_afflux_with(&all_components, known)?;
+
+        // 7. Whether each component consistent, only the uncached components are checked
+        let mut checked = HashMap::new();
+        let mut codes = HashMap::new();
+        let mut apis = HashMap::new();
+        let mut triggers = HashMap::new();
+        for component in components {
+            let id = component.id();
+            let cached = match self.cache.entry(id) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let hash = hashes.get(&id).copied();
+                    self.rechecked.push(id);
+                    entry.insert(check_component(component, hash, &all_components, &colors, fetch)?)
+                }
+            };
+            checked.insert(id, cached.checked.clone());
+            codes.extend(cached.codes.iter().map(|(k, v)| (k.clone(), v.clone())));
+            apis.extend(cached.apis.iter().map(|(k, v)| (k.clone(), v.clone())));
+            if let Some(trigger) = &cached.trigger {
+                triggers.insert(id, trigger.renew());
+            }
+        }
+        for trigger in triggers.values() {
+            trigger.check(&triggers, &all_components, &colors)?;
+        }
+
+        // 8. Calculate the return result
+        let canister_id = fetch.canister_id().map_err(system_error)?;
+        checked::parse_checked(canister_id, components, codes, apis, checked, output)
+    }
+
+    /// Drop the cached results of the edited components and their downstream through inlets
+    /// The removed and added components are edited too
+    fn invalidate(&mut self, components: &[LinkComponent]) -> HashMap<ComponentId, u64> {
+        let hashes = components
+            .iter()
+            .filter_map(|c| Some((c.id(), content_hash(c)?)))
+            .collect::<HashMap<_, _>>();
+
+        let mut downstream: HashMap<ComponentId, Vec<ComponentId>> = HashMap::new();
+        for component in components {
+            for inlet in component.get_inlets().into_iter().flatten() {
+                downstream.entry(inlet.id).or_default().push(component.id());
+            }
+        }
+
+        let mut pending = self
+            .cache
+            .iter()
+            .filter(|(id, cached)| cached.hash.is_none() || hashes.get(id) != cached.hash.as_ref())
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        pending.extend(
+            components
+                .iter()
+                .map(|c| c.id())
+                .filter(|id| !self.cache.contains_key(id)),
+        );
+        let mut dirty = HashSet::new();
+        while let Some(id) = pending.pop() {
+            if dirty.insert(id) {
+                pending.extend(downstream.get(&id).into_iter().flatten());
+            }
+        }
+
+        self.cache.retain(|id, _| !dirty.contains(id));
+        hashes
+    }
+}
+
+/// Check a single component with its own records
+fn check_component<F: CheckFunction>(
+    component: &LinkComponent,
+    hash: Option<u64>,
+    components: &HashMap<ComponentId, &LinkComponent>,
+    colors: &HashMap<ComponentId, ComponentColor<'_>>,
+    fetch: &F,
+) -> Result<CachedComponent, LinkError> {
+    let id = component.id();
+    let mut triggers = HashMap::new();
+    let mut codes = HashMap::new();
+    let mut apis = HashMap::new();
+    let mut checked = HashMap::new();
+    check_single::check_single_component(
+        component,
+        components,
+        colors,
+        fetch,
+        &mut triggers,
+        &mut codes,
+        &mut apis,
+        &mut checked,
+    )?;
+
+    let color = colors
+        .get(&id)
+        .ok_or_else(|| system_error("every component should has info".into()))?;
+    Ok(CachedComponent {
+        hash,
+        color: color.color.clone(),
+        conflict: color.conflict.clone(),
+        checked: checked
+            .remove(&id)
+            .ok_or_else(|| system_error("checked component should be recorded".into()))?,
+        trigger: triggers.remove(&id),
+        codes,
+        apis,
+    })
+}
+
+/// Content hash of the component
+fn content_hash(component: &LinkComponent) -> Option<u64> {
+    let json = serde_json::to_string(component).ok()?;
+    let mut hasher = DefaultHasher::new();
+    json.hash(&mut hasher);
+    Some(hasher.finish())
+}
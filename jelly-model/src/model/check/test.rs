@@ -1,6 +1,6 @@
 use crate::{
     model::{
-        check::{check, find_all_anchors, find_origin_codes},
+        check::{check, check_diagnostics, find_all_anchors, find_origin_codes, incremental::IncrementalChecker},
         common::{
             call_trigger::{CallTriggerLoading, ComponentCallTrigger},
             code::{CodeContent, OriginCodeContent},
@@ -8,7 +8,7 @@ use crate::{
             lets::Endpoint,
             refer::{InputValue, ReferValue},
             types::LinkType,
-            values::LinkValue,
+            values::{ArrayLinkValue, LinkValue, ObjectSubitemValue},
         },
         components::{
             call::{
@@ -17,16 +17,25 @@ use crate::{
             },
             code::{CodeMetadata, ComponentCode},
             condition::{
-                bool::ConditionBoolCompare, text::ConditionTextCompare, ComponentCondition, Condition, ConditionItem,
-                ConditionMatches, ConditionMetadata,
+                array::ConditionArrayCompare,
+                bool::ConditionBoolCompare,
+                number::{ConditionNumberCompare, ConditionRange},
+                text::ConditionTextCompare,
+                ComponentCondition, Condition, ConditionItem, ConditionMatches, ConditionMetadata,
             },
             constant::{ComponentConst, ConstMetadata},
             form::ComponentForm,
+            loops::{ComponentLoopEnd, ComponentLoopStart, LoopEndMetadata, LoopStartMetadata, LoopWay},
             param::{ComponentParam, ParamMetadata},
+            switch::{ComponentSwitch, SwitchCase, SwitchMetadata, SwitchPattern, SwitchRange, SwitchTag},
+            variable::{ComponentVariableAssign, ComponentVariableValue, VariableAssignMetadata, VariableValueMetadata},
             view::{text::ViewTextMetadata, ComponentView, ViewMetadata},
             LinkComponent,
         },
-        types::check::{CheckFunction, CheckedCombined},
+        types::{
+            check::{CheckFunction, CheckedCombined},
+            diagnostic::DiagnosticSeverity,
+        },
     },
     store::code::item::CodeItem,
 };
@@ -164,10 +173,10 @@ fn test3() {
     assert!(matches!(checked, Ok(CheckedCombined { .. })));
     let checked = find_origin_codes(&components, &fetch);
     println!("{:#?}", checked);
-    assert!(matches!(checked, Ok(_)));
+    assert!(checked.is_ok());
     let checked = find_all_anchors(&components);
     println!("{:#?}", checked);
-    assert!(matches!(checked, Ok(_)));
+    assert!(checked.is_ok());
 }
 
 #[test]
@@ -315,3 +324,517 @@ fn test4() {
     println!("{:#?}", checked);
     assert!(matches!(checked, Ok(CheckedCombined { .. })));
 }
+
+#[test]
+fn test_condition_operators() {
+    let fetch = MockCallFunction;
+    let constant = |id: u32, value: LinkValue| {
+        LinkComponent::Const(ComponentConst {
+            id: id.into(),
+            metadata: ConstMetadata { value: value.clone() },
+            output: value.link_type(),
+        })
+    };
+    let item = |id: u32, matches: ConditionMatches| {
+        Condition::Required(ConditionItem {
+            value: ReferValue {
+                endpoint: Endpoint {
+                    id: id.into(),
+                    index: None,
+                },
+                refer: None,
+            },
+            matches,
+        })
+    };
+    let check_condition = |condition: Condition| {
+        let components = vec![
+            constant(1, LinkValue::Integer(5)),
+            constant(2, LinkValue::Text("abc".into())),
+            constant(
+                3,
+                LinkValue::Array(ArrayLinkValue {
+                    ty: LinkType::Integer,
+                    values: vec![LinkValue::Integer(1)],
+                }),
+            ),
+            LinkComponent::Condition(ComponentCondition {
+                id: 100.into(),
+                inlets: Some(
+                    [1, 2, 3]
+                        .into_iter()
+                        .map(|id: u32| Endpoint {
+                            id: id.into(),
+                            index: None,
+                        })
+                        .collect(),
+                ),
+                metadata: ConditionMetadata {
+                    conditions: vec![condition],
+                },
+            }),
+        ];
+        check(&components, &fetch)
+    };
+    let integer = |n: i64| InputValue::Const(LinkValue::Integer(n));
+    let range = |min: i64, max: i64| ConditionRange {
+        min: integer(min),
+        max: integer(max),
+    };
+    let regex = |pattern: &str| {
+        item(
+            2,
+            ConditionMatches::Text(ConditionTextCompare::Regex(InputValue::Const(LinkValue::Text(
+                pattern.into(),
+            )))),
+        )
+    };
+
+    // valid
+    assert!(check_condition(regex("^a.c$")).is_ok());
+    let between = ConditionNumberCompare::Between(range(1, 5));
+    assert!(check_condition(item(1, ConditionMatches::Integer(between))).is_ok());
+    let set = ConditionNumberCompare::InSet(vec![integer(1), integer(5)]);
+    assert!(check_condition(item(1, ConditionMatches::Integer(set))).is_ok());
+    let any = ConditionArrayCompare::Any(Box::new(ConditionMatches::Integer(ConditionNumberCompare::Greater(
+        integer(0),
+    ))));
+    assert!(check_condition(item(3, ConditionMatches::Array(any))).is_ok());
+
+    // invalid
+    assert!(matches!(
+        check_condition(regex("(")),
+        Err(LinkError::InvalidCondition(_))
+    ));
+    let reversed = ConditionNumberCompare::NotBetween(range(5, 1));
+    assert!(matches!(
+        check_condition(item(1, ConditionMatches::Integer(reversed))),
+        Err(LinkError::InvalidCondition(_))
+    ));
+    let empty = ConditionNumberCompare::InSet(vec![]);
+    assert!(matches!(
+        check_condition(item(1, ConditionMatches::Integer(empty))),
+        Err(LinkError::InvalidCondition(_))
+    ));
+    let all = ConditionArrayCompare::All(Box::new(ConditionMatches::Text(ConditionTextCompare::NotNull)));
+    assert!(matches!(
+        check_condition(item(3, ConditionMatches::Array(all))),
+        Err(LinkError::InvalidCondition(_))
+    ));
+}
+
+#[test]
+fn test_diagnostics() {
+    let fetch = MockCallFunction;
+    let param = |id: u32, name: &str| {
+        LinkComponent::Param(ComponentParam {
+            id: id.into(),
+            metadata: ParamMetadata {
+                name: name.into(),
+                default: None,
+            },
+        })
+    };
+    let condition = |id: u32, inlet: u32, matches: ConditionMatches| {
+        let endpoint = Endpoint {
+            id: inlet.into(),
+            index: None,
+        };
+        LinkComponent::Condition(ComponentCondition {
+            id: id.into(),
+            inlets: Some(vec![endpoint]),
+            metadata: ConditionMetadata {
+                conditions: vec![Condition::Required(ConditionItem {
+                    value: ReferValue { endpoint, refer: None },
+                    matches,
+                })],
+            },
+        })
+    };
+    let regex = ConditionMatches::Text(ConditionTextCompare::Regex(InputValue::Const(LinkValue::Text(
+        "(".into(),
+    ))));
+    let integer = ConditionMatches::Text(ConditionTextCompare::Equal(InputValue::Const(LinkValue::Integer(1))));
+
+    // every problem is reported
+    let components = vec![
+        param(1, "a"),
+        param(2, "a"),
+        param(3, "a"),
+        condition(10, 1, regex.clone()),
+        condition(11, 1, ConditionMatches::Text(ConditionTextCompare::NotNull)),
+        condition(12, 1, integer),
+    ];
+    let diagnostics = check_diagnostics(&components, &fetch);
+    let found = diagnostics
+        .iter()
+        .map(|d| (d.severity, d.component, d.related.len()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        vec![
+            (DiagnosticSeverity::Error, Some(2.into()), 2),
+            (DiagnosticSeverity::Error, Some(3.into()), 2),
+            (DiagnosticSeverity::Error, Some(10.into()), 0),
+            (DiagnosticSeverity::Error, Some(12.into()), 0),
+        ]
+    );
+    assert!(matches!(diagnostics[0].error, LinkError::DuplicateParamName { .. }));
+    assert!(matches!(diagnostics[2].error, LinkError::InvalidCondition(_)));
+
+    // unknown inlets stop the later stages
+    let components = vec![param(1, "a"), condition(10, 8, regex.clone()), condition(11, 9, regex)];
+    let diagnostics = check_diagnostics(&components, &fetch);
+    assert_eq!(diagnostics.len(), 2);
+    assert!(
+        diagnostics
+            .iter()
+            .all(|d| matches!(d.error, LinkError::UnknownComponentOrNotRefer { .. }))
+    );
+
+    // fatal
+    let diagnostics = check_diagnostics(&[param(1, "a"), param(1, "b")], &fetch);
+    assert!(matches!(
+        diagnostics.as_slice(),
+        [d] if d.severity == DiagnosticSeverity::Fatal && d.component == Some(1.into())
+    ));
+
+    // valid
+    assert!(check_diagnostics(&[param(1, "a"), param(2, "b")], &fetch).is_empty());
+}
+
+#[test]
+fn test_switch() {
+    let fetch = MockCallFunction;
+    let check_switch = |value: LinkValue, cases: Vec<(&str, SwitchPattern)>, default: Option<&str>| {
+        let components = vec![
+            LinkComponent::Const(ComponentConst {
+                id: 1.into(),
+                metadata: ConstMetadata { value: value.clone() },
+                output: value.link_type(),
+            }),
+            LinkComponent::Switch(ComponentSwitch {
+                id: 2.into(),
+                inlets: Some(vec![Endpoint {
+                    id: 1.into(),
+                    index: None,
+                }]),
+                metadata: SwitchMetadata {
+                    value: ReferValue {
+                        endpoint: Endpoint {
+                            id: 1.into(),
+                            index: None,
+                        },
+                        refer: None,
+                    },
+                    cases: cases
+                        .into_iter()
+                        .map(|(name, pattern)| SwitchCase {
+                            name: name.into(),
+                            pattern,
+                        })
+                        .collect(),
+                    default: default.map(|d| d.into()),
+                },
+            }),
+        ];
+        check(&components, &fetch)
+    };
+    let texts = |texts: &[&str]| SwitchPattern::Text(texts.iter().map(|t| t.to_string()).collect());
+    let range = |min: Option<i64>, max: Option<i64>| SwitchPattern::Integer(SwitchRange { min, max });
+    let text = LinkValue::Text("a".into());
+    let integer = LinkValue::Integer(1);
+
+    // valid
+    let cases = vec![("a", texts(&["a", "b"])), ("c", texts(&["c"]))];
+    assert!(check_switch(text.clone(), cases, Some("other")).is_ok());
+    let cases = vec![("yes", SwitchPattern::Bool(true)), ("no", SwitchPattern::Bool(false))];
+    assert!(check_switch(LinkValue::Bool(true), cases, None).is_ok());
+    let cases = vec![("small", range(None, Some(10))), ("large", range(Some(5), None))];
+    assert!(check_switch(integer.clone(), cases, None).is_ok());
+    let object = LinkValue::Object(vec![ObjectSubitemValue {
+        key: "kind".into(),
+        value: LinkValue::Text("circle".into()),
+    }]);
+    let tag = |values: &[&str]| {
+        SwitchPattern::Tag(SwitchTag {
+            key: "kind".into(),
+            values: values.iter().map(|t| t.to_string()).collect(),
+        })
+    };
+    let cases = vec![("circle", tag(&["circle"])), ("square", tag(&["square"]))];
+    assert!(check_switch(object.clone(), cases, None).is_ok());
+
+    // duplicate name
+    let cases = vec![("a", texts(&["a"])), ("a", texts(&["b"]))];
+    assert!(matches!(
+        check_switch(text.clone(), cases, None),
+        Err(LinkError::DuplicateSwitchCase { name, .. }) if name == "a"
+    ));
+    let cases = vec![("a", texts(&["a"]))];
+    assert!(matches!(
+        check_switch(text.clone(), cases, Some("a")),
+        Err(LinkError::DuplicateSwitchCase { .. })
+    ));
+    // duplicate value
+    let cases = vec![("a", texts(&["a"])), ("b", texts(&["a", "b"]))];
+    assert!(matches!(
+        check_switch(text.clone(), cases, None),
+        Err(LinkError::DuplicateSwitchCase { name, .. }) if name == "b"
+    ));
+    let cases = vec![("a", texts(&["a", "b", "a"]))];
+    assert!(matches!(
+        check_switch(text.clone(), cases, None),
+        Err(LinkError::InvalidSwitch(e)) if e.message == "text a is repeated"
+    ));
+    let cases = vec![("circle", tag(&["circle", "circle"]))];
+    assert!(matches!(
+        check_switch(object.clone(), cases, None),
+        Err(LinkError::InvalidSwitch(e)) if e.message == "tag value circle is repeated"
+    ));
+    // unreachable
+    let cases = vec![("a", texts(&["a", "b"])), ("b", texts(&["b"]))];
+    assert!(matches!(
+        check_switch(text.clone(), cases, None),
+        Err(LinkError::UnreachableSwitchCase { name, .. }) if name == "b"
+    ));
+    let cases = vec![("circle", tag(&["circle", "square"])), ("square", tag(&["square"]))];
+    assert!(matches!(
+        check_switch(object.clone(), cases, None),
+        Err(LinkError::UnreachableSwitchCase { name, .. }) if name == "square"
+    ));
+    let cases = vec![
+        ("a", range(Some(0), Some(5))),
+        ("b", range(Some(6), Some(9))),
+        ("c", range(Some(3), Some(8))),
+    ];
+    assert!(matches!(
+        check_switch(integer.clone(), cases, None),
+        Err(LinkError::UnreachableSwitchCase { name, .. }) if name == "c"
+    ));
+    let cases = vec![("negative", range(None, Some(-1))), ("natural", range(Some(0), None))];
+    assert!(matches!(
+        check_switch(integer.clone(), cases, Some("other")),
+        Err(LinkError::UnreachableSwitchCase { name, .. }) if name == "other"
+    ));
+    // not exhaustive
+    let cases = vec![("yes", SwitchPattern::Bool(true))];
+    assert!(matches!(
+        check_switch(LinkValue::Bool(true), cases, None),
+        Err(LinkError::NonExhaustiveSwitch { missing, .. }) if missing == "false"
+    ));
+    // invalid pattern
+    let cases = vec![("a", range(Some(5), Some(1)))];
+    assert!(matches!(
+        check_switch(integer.clone(), cases, None),
+        Err(LinkError::InvalidSwitch(_))
+    ));
+    let cases = vec![("a", texts(&["a"]))];
+    assert!(matches!(
+        check_switch(integer, cases, None),
+        Err(LinkError::InvalidSwitch(_))
+    ));
+}
+
+#[test]
+fn test_loop() {
+    let fetch = MockCallFunction;
+    let endpoint = |id: u32| Endpoint {
+        id: id.into(),
+        index: None,
+    };
+    let refer = |id: u32| ReferValue {
+        endpoint: endpoint(id),
+        refer: None,
+    };
+    let components = |output: LinkType| {
+        vec![
+            LinkComponent::Const(ComponentConst {
+                id: 1.into(),
+                metadata: ConstMetadata {
+                    value: LinkValue::Array(ArrayLinkValue {
+                        ty: LinkType::Integer,
+                        values: vec![LinkValue::Integer(1), LinkValue::Integer(2)],
+                    }),
+                },
+                output: LinkType::Array(Box::new(LinkType::Integer)),
+            }),
+            LinkComponent::LoopStart(ComponentLoopStart {
+                id: 2.into(),
+                inlets: Some(vec![endpoint(1), endpoint(3)]), // ? 3 is the back edge
+                metadata: LoopStartMetadata {
+                    way: LoopWay::Iteration(refer(1)),
+                },
+                output: LinkType::Integer,
+            }),
+            LinkComponent::LoopEnd(ComponentLoopEnd {
+                id: 3.into(),
+                inlets: Some(vec![endpoint(2)]),
+                metadata: LoopEndMetadata {
+                    start: 2.into(),
+                    value: InputValue::Refer(refer(2)),
+                },
+                output,
+            }),
+        ]
+    };
+
+    let checked = check(&components(LinkType::Array(Box::new(LinkType::Integer))), &fetch);
+    println!("{:#?}", checked);
+    assert!(matches!(checked, Ok(CheckedCombined { .. })));
+
+    let checked = check(&components(LinkType::Array(Box::new(LinkType::Text))), &fetch);
+    println!("{:#?}", checked);
+    assert!(matches!(checked, Err(LinkError::MismatchedOutput { .. })));
+
+    // The loop end of other loop is not the back edge
+    let mut components = components(LinkType::Array(Box::new(LinkType::Integer)));
+    if let Some(LinkComponent::LoopEnd(end)) = components.last_mut() {
+        end.metadata.start = 1.into();
+    }
+    let checked = check(&components, &fetch);
+    println!("{:#?}", checked);
+    assert!(matches!(checked, Err(LinkError::CircularReference { .. })));
+}
+
+#[test]
+fn test_variable() {
+    let fetch = MockCallFunction;
+    let endpoint = |id: u32| Endpoint {
+        id: id.into(),
+        index: None,
+    };
+    let variable = |id: u32, name: &str| {
+        LinkComponent::VariableValue(ComponentVariableValue {
+            id: id.into(),
+            inlets: None,
+            metadata: VariableValueMetadata {
+                name: name.into(),
+                default: Some(LinkValue::Integer(0)),
+            },
+            output: LinkType::Integer,
+        })
+    };
+    let components = || {
+        vec![
+            LinkComponent::Const(ComponentConst {
+                id: 1.into(),
+                metadata: ConstMetadata {
+                    value: LinkValue::Integer(1),
+                },
+                output: LinkType::Integer,
+            }),
+            variable(2, "count"),
+            LinkComponent::VariableAssign(ComponentVariableAssign {
+                id: 3.into(),
+                inlets: Some(vec![endpoint(1), endpoint(2)]),
+                metadata: VariableAssignMetadata {
+                    variable: 2.into(),
+                    value: InputValue::Refer(ReferValue {
+                        endpoint: endpoint(1),
+                        refer: None,
+                    }),
+                },
+            }),
+        ]
+    };
+
+    let checked = check(&components(), &fetch);
+    println!("{:#?}", checked);
+    assert!(matches!(
+        checked.as_ref().map(|c| c.metadata.as_ref().and_then(|m| m.variables.as_ref()).map(|v| v.len())),
+        Ok(Some(1))
+    ));
+
+    // The assigned value must be the type of variable
+    let mut mismatched = components();
+    if let Some(LinkComponent::VariableAssign(assign)) = mismatched.last_mut() {
+        assign.metadata.value = InputValue::Const(LinkValue::Text("1".into()));
+    }
+    let checked = check(&mismatched, &fetch);
+    println!("{:#?}", checked);
+    assert!(matches!(checked, Err(LinkError::InvalidVariable { .. })));
+
+    // The variable name cannot be repeated
+    let mut duplicated = components();
+    duplicated.push(variable(4, "count"));
+    let checked = check(&duplicated, &fetch);
+    println!("{:#?}", checked);
+    assert!(matches!(checked, Err(LinkError::DuplicateVariableName { .. })));
+}
+
+#[test]
+fn test_incremental() {
+    let fetch = MockCallFunction;
+    let code = |id: u32, inlet: u32, code: &str| {
+        LinkComponent::Code(ComponentCode {
+            id: id.into(),
+            inlets: Some(vec![Endpoint {
+                id: inlet.into(),
+                index: None,
+            }]),
+            metadata: CodeMetadata {
+                data: None,
+                code: CodeContent::Code(OriginCodeContent {
+                    code: CodeItem {
+                        code: code.into(),
+                        args: None,
+                        ret: None,
+                    },
+                    js: "".into(),
+                }),
+            },
+            output: LinkType::Text,
+        })
+    };
+    let param = |name: &str| {
+        LinkComponent::Param(ComponentParam {
+            id: 1.into(),
+            metadata: ParamMetadata {
+                name: name.into(),
+                default: None,
+            },
+        })
+    };
+    let constant = LinkComponent::Const(ComponentConst {
+        id: 2.into(),
+        metadata: ConstMetadata {
+            value: LinkValue::Text("Anubis".into()),
+        },
+        output: LinkType::Text,
+    });
+
+    let mut checker = IncrementalChecker::default();
+    let check_same = |checker: &mut IncrementalChecker, components: &[LinkComponent]| {
+        let full = check(components, &fetch).map(|c| (serde_json::to_string(&c.components).ok(), c.combined_anchor));
+        let incremental = checker
+            .check(components, &fetch)
+            .map(|c| (serde_json::to_string(&c.components).ok(), c.combined_anchor));
+        assert_eq!(
+            full.as_ref().ok().map(|(c, a)| (c, a.as_ref())),
+            incremental.as_ref().ok().map(|(c, a)| (c, a.as_ref()))
+        );
+        checker.rechecked().to_vec()
+    };
+    let ids = |ids: &[u32]| ids.iter().map(|id| (*id).into()).collect::<Vec<_>>();
+
+    let mut components = vec![param("a"), constant, code(3, 1, "x"), code(4, 3, "y")];
+    assert_eq!(check_same(&mut checker, &components), ids(&[1, 2, 3, 4]));
+    assert_eq!(check_same(&mut checker, &components), ids(&[]));
+
+    // only the downstream of the edited
+    components[2] = code(3, 1, "z");
+    assert_eq!(check_same(&mut checker, &components), ids(&[3, 4]));
+    components[0] = param("b");
+    assert_eq!(check_same(&mut checker, &components), ids(&[1, 3, 4]));
+
+    // removed
+    components.pop();
+    assert_eq!(check_same(&mut checker, &components), ids(&[]));
+
+    // the failed is checked again
+    components.push(code(4, 9, "y"));
+    assert!(checker.check(&components, &fetch).is_err());
+    components[3] = code(4, 2, "y");
+    assert_eq!(check_same(&mut checker, &components), ids(&[4]));
+}
@@ -1,8 +1,11 @@
+use std::collections::HashSet;
+
 use serde::{Deserialize, Serialize};
 
 use crate::store::{api::anchor::ApiDataAnchor, code::anchor::CodeDataAnchor, combined::anchor::CombinedAnchor};
 
 use super::{
+    LinkComponent,
     common::{identity::ComponentId, types::LinkType},
     components::{identity::IdentityInnerMetadata, interaction::InteractionInnerMetadata},
 };
@@ -25,6 +28,9 @@ pub struct CombinedMetadata {
     /// Record interaction component
     #[serde(skip_serializing_if = "crate::is_empty_option_vec")]
     pub interactions: Option<Vec<ComponentInteractionRequired>>,
+    /// Record variable component
+    #[serde(skip_serializing_if = "crate::is_empty_option_vec")]
+    pub variables: Option<Vec<ComponentVariableRequired>>,
 
     // ! The data that can be loaded in advance, the data in Anchor is cached
     /// code
@@ -53,9 +59,84 @@ impl CombinedMetadata {
             && crate::is_empty_option_vec(&self.combined_anchors)
             && crate::is_empty_option_vec(&self.forms)
             && crate::is_empty_option_vec(&self.interactions)
+            && crate::is_empty_option_vec(&self.variables)
             && self.output.is_none()
     }
 
+    /// Take out the data required by the checked components, None if nothing is required
+    pub fn from_components(components: &[LinkComponent], output: Option<LinkType>) -> Option<Self> {
+        let params = components
+            .iter()
+            .filter_map(|component| component.get_param_required())
+            .collect::<Vec<_>>();
+
+        let forms = components
+            .iter()
+            .filter_map(|component| component.get_form_required())
+            .collect::<Vec<_>>();
+
+        let identities = components
+            .iter()
+            .filter_map(|component| component.get_identity_required())
+            .collect::<Vec<_>>();
+
+        let interactions = components
+            .iter()
+            .filter_map(|component| component.get_interaction_required())
+            .collect::<Vec<_>>();
+
+        let variables = components
+            .iter()
+            .filter_map(|component| component.get_variable_required())
+            .collect::<Vec<_>>();
+
+        // Remove the other kept KEY
+        let code_anchors = components
+            .iter()
+            .flat_map(|component| component.get_code_anchors())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        let apis_anchors = components
+            .iter()
+            .flat_map(|component| component.get_apis_anchors())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        let combined_anchors = components
+            .iter()
+            .flat_map(|component| component.get_combined_anchors())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        (!params.is_empty()
+            || !forms.is_empty()
+            || !identities.is_empty()
+            || !interactions.is_empty()
+            || !variables.is_empty()
+            || !code_anchors.is_empty()
+            || !apis_anchors.is_empty()
+            || !combined_anchors.is_empty()
+            || output.is_some())
+        .then(|| CombinedMetadata {
+            params: (!params.is_empty()).then_some(params),
+            identities: (!identities.is_empty()).then_some(identities),
+
+            forms: (!forms.is_empty()).then_some(forms),
+            interactions: (!interactions.is_empty()).then_some(interactions),
+            variables: (!variables.is_empty()).then_some(variables),
+
+            code_anchors: (!code_anchors.is_empty()).then_some(code_anchors),
+            apis_anchors: (!apis_anchors.is_empty()).then_some(apis_anchors),
+            combined_anchors: (!combined_anchors.is_empty()).then_some(combined_anchors),
+
+            output,
+        })
+    }
+
     /// Whether
     pub fn is_metadata_empty(metadata: &Option<Self>) -> bool {
         metadata.as_ref().is_none_or(|m| m.is_empty())
@@ -115,3 +196,16 @@ pub struct ComponentInteractionRequired {
     /// Metall data required for this component execution
     pub metadata: InteractionInnerMetadata,
 }
+
+/// variable
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ComponentVariableRequired {
+    /// Id with each component
+    pub id: ComponentId,
+
+    /// Variable name // ! Follow the variable name rules and unique
+    pub name: String,
+
+    /// Output type
+    pub output: LinkType, // The declared type of variable
+}
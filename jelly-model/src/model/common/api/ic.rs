@@ -110,6 +110,21 @@ impl IcCallApi {
         }
         Ok(self)
     }
+
+    /// Whether it is update call, the method without query annotation
+    /// None if the api can not be parsed without fetching
+    pub fn is_update(&self, from: ComponentId) -> Option<bool> {
+        let func = match self {
+            Self::Api(InternetComputerApi::Single(SingleInternetComputerApi { api })) => {
+                candid::parse_candid_for_data_and_output(&format!("service: {{ {api} }}"), None, from)
+            }
+            Self::Api(InternetComputerApi::Origin(OriginInternetComputerApi { candid, method })) => {
+                candid::parse_candid_for_data_and_output(candid, Some(method), from) // ? The candid may be replaced by hash
+            }
+            Self::Anchor(_) => return None,
+        };
+        func.ok().map(|func| func.annotation.is_none())
+    }
 }
 
 impl InternetComputerApi {
@@ -7,6 +7,13 @@ use crate::{
 
 use super::{refer::KeyRefer, types::LinkType, values::LinkValue};
 
+/// message catalog
+pub mod message;
+
+/// test
+#[cfg(test)]
+mod test;
+
 /// Error of examination
 #[derive(Debug, Serialize)]
 pub enum LinkError {
@@ -101,6 +108,12 @@ pub enum LinkError {
         name: String,
     },
 
+    /// Variable name is repeated
+    DuplicateVariableName {
+        /// Repeated variable name
+        name: String,
+    },
+
     // ------------ link and value ------------
     /// Types and values ​​do not correspond
     MismatchedLinkValueType {
@@ -353,6 +366,48 @@ pub enum LinkError {
     // ==================== Condition error ====================
     /// Invalid conditions
     InvalidCondition(CommonLinkError),
+
+    // ==================== Switch error ====================
+    /// Invalid switch
+    InvalidSwitch(CommonLinkError),
+    /// The name or the value of case is repeated
+    DuplicateSwitchCase {
+        /// The required component
+        from: ComponentId,
+        /// The name of case
+        name: String,
+    },
+    /// The case can never be matched
+    UnreachableSwitchCase {
+        /// The required component
+        from: ComponentId,
+        /// The name of case
+        name: String,
+    },
+    /// The cases of finite type are not exhaustive and there is no default
+    NonExhaustiveSwitch {
+        /// The required component
+        from: ComponentId,
+        /// The value not covered
+        missing: String,
+    },
+
+    // ==================== Loop error ====================
+    /// Invalid loop
+    InvalidLoop(CommonLinkError),
+
+    // ==================== Variable error ====================
+    /// Invalid variable
+    InvalidVariable(CommonLinkError),
+    /// mismatch variable default value
+    MismatchedVariableDefaultValue {
+        /// The required component
+        from: ComponentId,
+        /// Required type
+        output: LinkType,
+        /// Non -matching type
+        value: LinkValue,
+    },
     // // ==================== Combined error ====================
     // /// Invalid conditions
     // MismatchedCombinedMetadata {
@@ -373,6 +428,14 @@ pub enum LinkError {
     // },
 }
 
+impl core::fmt::Display for LinkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}: {}", self.code(), self.name(), self.message(message::Locale::En))
+    }
+}
+
+impl std::error::Error for LinkError {}
+
 /// The wrong code object is too large to place the stack on the stack
 #[derive(Debug, Serialize)]
 pub struct LinkErrorWrongCode {
@@ -0,0 +1,356 @@
+use serde::{Deserialize, Serialize};
+
+use super::{CommonLinkError, LinkError};
+
+/// Language of message
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    /// English
+    #[default]
+    #[serde(rename = "en")]
+    En,
+    /// Chinese
+    #[serde(rename = "zh")]
+    Zh,
+}
+
+/// Message of error variant
+/// The placeholders are the serialized field names of error, such as {from}
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorMessage {
+    /// Stable code // ! Never changed or reused
+    pub code: &'static str,
+    /// The name of variant
+    pub name: &'static str,
+    /// English template
+    pub en: &'static str,
+    /// Chinese template
+    pub zh: &'static str,
+}
+
+impl ErrorMessage {
+    /// The template of locale
+    pub fn template(&self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::En => self.en,
+            Locale::Zh => self.zh,
+        }
+    }
+}
+
+macro_rules! catalog {
+    ($($name:ident => $code:literal, $en:literal, $zh:literal;)*) => {
+        /// All messages of error variants
+        pub const CATALOG: &[ErrorMessage] = &[$(ErrorMessage {
+            code: $code,
+            name: stringify!($name),
+            en: $en,
+            zh: $zh,
+        },)*];
+
+        impl LinkError {
+            /// The message of variant
+            pub fn catalog(&self) -> &'static ErrorMessage {
+                match self {
+                    $(LinkError::$name { .. } => &ErrorMessage {
+                        code: $code,
+                        name: stringify!($name),
+                        en: $en,
+                        zh: $zh,
+                    },)*
+                }
+            }
+        }
+    };
+}
+
+catalog! {
+    SystemError => "E0001", "system error: {message}", "系统错误：{message}";
+    // ==================== Error between components ====================
+    EmptyComponents => "E0101", "no components: {message}", "没有组件：{message}";
+    InvalidComponentId => "E0102", "component id {id} is invalid", "组件 ID {id} 无效";
+    DuplicateComponentId => "E0103", "component id {id} is repeated", "组件 ID {id} 重复";
+    CircularReference => "E0104", "component {id} is in a circular reference", "组件 {id} 存在循环引用";
+    AffluxComponentId => "E0105",
+        "component {from} gathers multiple outputs of component {afflux}",
+        "组件 {from} 汇集了组件 {afflux} 的多个输出";
+    UnknownComponentOrNotRefer => "E0106",
+        "component {from} refers component {id} which is unknown or not referred",
+        "组件 {from} 引用的组件 {id} 不存在或未被引入";
+    InvalidEndpoint => "E0107", "component {from} refers invalid endpoint {inlet}", "组件 {from} 引用了无效的输出点 {inlet}";
+    ReferNoOutputComponent => "E0108",
+        "component {from} refers component {refer} which has no output",
+        "组件 {from} 引用的组件 {refer} 没有输出";
+    // ==================== An element error ====================
+    DuplicateParamName => "E0201", "param name {name} is repeated", "参数名 {name} 重复";
+    DuplicateFormName => "E0202", "form name {name} is repeated", "表单名 {name} 重复";
+    DuplicateIdentityName => "E0203", "identity name {name} is repeated", "身份名 {name} 重复";
+    DuplicateInteractionName => "E0204", "interaction name {name} is repeated", "交互名 {name} 重复";
+    DuplicateVariableName => "E0205", "variable name {name} is repeated", "变量名 {name} 重复";
+    MismatchedLinkValueType => "E0206",
+        "value {value} of component {from} does not match its type",
+        "组件 {from} 的值 {value} 与类型不匹配";
+    WrongLinkTypeForRefer => "E0207",
+        "component {from} refers {refer} of endpoint {inlet} with wrong type",
+        "组件 {from} 引用输出点 {inlet} 的 {refer} 类型错误";
+    DuplicateObjectKey => "E0208", "object key {key} of component {from} is repeated", "组件 {from} 的对象键 {key} 重复";
+    InvalidObjectKey => "E0209", "object key {key} of component {from} is invalid", "组件 {from} 的对象键 {key} 无效";
+    InvalidVariantKey => "E0210", "variable key {key} of component {from} is invalid", "组件 {from} 的变量名 {key} 无效";
+    DuplicateVariantKey => "E0211", "variable key {key} of component {from} is repeated", "组件 {from} 的变量名 {key} 重复";
+    InvalidName => "E0212", "name {name} of component {from} is invalid", "组件 {from} 的名称 {name} 无效";
+    DuplicateName => "E0213", "name {name} of component {from} is repeated", "组件 {from} 的名称 {name} 重复";
+    InvalidNamedValueType => "E0214",
+        "named value of component {from} has wrong type: {message}",
+        "组件 {from} 的命名值类型错误：{message}";
+    MismatchedInlets => "E0215", "inlets of component {from} do not match its references", "组件 {from} 的引入与引用不一致";
+    MismatchedOutput => "E0216", "output of component {from} does not match", "组件 {from} 的输出不匹配";
+    WrongCode => "E0217", "code of component {from} is wrong: {message}", "组件 {from} 的代码错误：{message}";
+    ValidateCodeFailed => "E0218",
+        "validate code of component {from} failed with {value}: {message}",
+        "组件 {from} 的校验代码对 {value} 执行失败：{message}";
+    InvalidConfirmText => "E0219", "confirm text of component {from} is invalid", "组件 {from} 的确认按钮文本无效";
+    // ==================== Const error ====================
+    MismatchedConstValue => "E0301",
+        "const value {value} of component {from} does not match type {output}",
+        "组件 {from} 的常量值 {value} 与类型 {output} 不匹配";
+    WrongConstValue => "E0302", "const value of component {from} is wrong: {message}", "组件 {from} 的常量值错误：{message}";
+    // ==================== Form error ====================
+    MismatchedFormDefaultValue => "E0401",
+        "default value {value} of form {from} does not match type {output}",
+        "表单 {from} 的默认值 {value} 与类型 {output} 不匹配";
+    MismatchedFormSuffixValue => "E0402",
+        "suffix of form {from} must be text, but got {value}",
+        "表单 {from} 的后缀必须是文本，实际为 {value}";
+    // ==================== Identity error ====================
+    InvalidIdentity => "E0501", "identity {from} is invalid: {message}", "身份 {from} 无效：{message}";
+    InvalidIdentityHttpProxy => "E0502", "http proxy {proxy} of identity {from} is invalid", "身份 {from} 的 HTTP 代理 {proxy} 无效";
+    // ==================== Call error ====================
+    InvalidCallTrigger => "E0601", "trigger of call {from} is invalid: {message}", "调用 {from} 的触发方式无效：{message}";
+    InvalidCallIdentity => "E0602", "identity of call {from} is invalid: {message}", "调用 {from} 的身份无效：{message}";
+    InvalidCallOutputType => "E0603", "output type of call {from} is invalid: {message}", "调用 {from} 的输出类型无效：{message}";
+    NeedlessCallHttpName => "E0611", "http call {from} should not have name", "HTTP 调用 {from} 不应有名称";
+    InvalidCallHttpUrl => "E0612", "url of http call {from} is invalid: {message}", "HTTP 调用 {from} 的地址无效：{message}";
+    InvalidCallIcCanisterId => "E0621",
+        "canister id of ic call {from} is invalid: {message}",
+        "IC 调用 {from} 的 canister id 无效：{message}";
+    InvalidCallIcApi => "E0622", "api of ic call {from} is invalid", "IC 调用 {from} 的接口无效";
+    CompileCallIcCandid => "E0623",
+        "candid of ic call {from} can not be compiled: {message}",
+        "IC 调用 {from} 的 candid 编译失败：{message}";
+    CompileCallIcCandidTypeUnsupported => "E0624",
+        "candid type {ty} of ic call {from} is not supported",
+        "IC 调用 {from} 的 candid 类型 {ty} 暂不支持";
+    InvalidCallIcApiArg => "E0625", "argument of ic call {from} is invalid: {message}", "IC 调用 {from} 的参数无效：{message}";
+    InvalidCallIcApiRet => "E0626", "result of ic call {from} is invalid: {message}", "IC 调用 {from} 的返回值无效：{message}";
+    InvalidCallEvmActionContract => "E0631",
+        "contract of evm call {from} is invalid: {message}",
+        "EVM 调用 {from} 的合约地址无效：{message}";
+    InvalidCallEvmActionApi => "E0632", "api of evm call {from} is invalid: {message}", "EVM 调用 {from} 的接口无效：{message}";
+    InvalidCallEvmActionArg => "E0633",
+        "argument of evm call {from} is invalid: {message}",
+        "EVM 调用 {from} 的参数无效：{message}";
+    InvalidCallEvmActionRet => "E0634",
+        "result of evm call {from} is invalid: {message}",
+        "EVM 调用 {from} 的返回值无效：{message}";
+    InvalidCallEvmActionSign => "E0635",
+        "sign of evm call {from} is invalid: {message}",
+        "EVM 调用 {from} 的签名内容无效：{message}";
+    InvalidCallEvmActionPayValue => "E0636",
+        "pay value of evm call {from} is invalid: {message}",
+        "EVM 调用 {from} 的支付金额无效：{message}";
+    InvalidCallEvmActionGasLimit => "E0637",
+        "gas limit of evm call {from} is invalid: {message}",
+        "EVM 调用 {from} 的 gas 上限无效：{message}";
+    InvalidCallEvmActionGasPrice => "E0638",
+        "gas price of evm call {from} is invalid: {message}",
+        "EVM 调用 {from} 的 gas 价格无效：{message}";
+    InvalidCallEvmActionNonce => "E0639",
+        "nonce of evm call {from} is invalid: {message}",
+        "EVM 调用 {from} 的 nonce 无效：{message}";
+    InvalidCallEvmActionAbi => "E0640", "abi of evm call {from} is invalid: {message}", "EVM 调用 {from} 的 ABI 无效：{message}";
+    InvalidCallEvmActionBytecode => "E0641",
+        "bytecode of evm call {from} is invalid: {message}",
+        "EVM 调用 {from} 的字节码无效：{message}";
+    InvalidCallEvmActionTransferTo => "E0642",
+        "transfer target of evm call {from} is invalid: {message}",
+        "EVM 调用 {from} 的转账地址无效：{message}";
+    InvalidCallEvmActionOutput => "E0643",
+        "output of evm call {from} is invalid: {message}",
+        "EVM 调用 {from} 的输出无效：{message}";
+    // ==================== Interaction error ====================
+    InvalidInteractionComponent => "E0701", "interaction {from} is invalid: {message}", "交互组件 {from} 无效：{message}";
+    // ==================== View error ====================
+    InvalidViewComponent => "E0801", "view {from} is invalid: {message}", "展示组件 {from} 无效：{message}";
+    // ==================== Output error ====================
+    MultipleOutput => "E0901", "only one output is allowed", "只能有一个输出组件";
+    // ==================== Condition error ====================
+    InvalidCondition => "E1001", "condition {from} is invalid: {message}", "条件组件 {from} 无效：{message}";
+    // ==================== Switch error ====================
+    InvalidSwitch => "E1101", "switch {from} is invalid: {message}", "分支组件 {from} 无效：{message}";
+    DuplicateSwitchCase => "E1102", "case {name} of switch {from} is repeated", "分支组件 {from} 的分支 {name} 重复";
+    UnreachableSwitchCase => "E1103",
+        "case {name} of switch {from} can never be matched",
+        "分支组件 {from} 的分支 {name} 永远不会匹配";
+    NonExhaustiveSwitch => "E1104",
+        "switch {from} does not cover {missing} and has no default",
+        "分支组件 {from} 未覆盖 {missing} 且没有默认分支";
+    // ==================== Loop error ====================
+    InvalidLoop => "E1201", "loop {from} is invalid: {message}", "循环组件 {from} 无效：{message}";
+    // ==================== Variable error ====================
+    InvalidVariable => "E1301", "variable {from} is invalid: {message}", "变量组件 {from} 无效：{message}";
+    MismatchedVariableDefaultValue => "E1302",
+        "default value {value} of variable {from} does not match type {output}",
+        "变量 {from} 的默认值 {value} 与类型 {output} 不匹配";
+}
+
+impl LinkError {
+    /// Stable code of variant
+    pub fn code(&self) -> &'static str {
+        self.catalog().code
+    }
+
+    /// The name of variant
+    pub fn name(&self) -> &'static str {
+        self.catalog().name
+    }
+
+    /// The localized message, the placeholders are interpolated
+    /// ? The template is scanned once, so the placeholders in values are kept as they are
+    pub fn message(&self, locale: Locale) -> String {
+        let args = self.args();
+        let mut template = self.catalog().template(locale);
+        let mut message = String::with_capacity(template.len());
+        while let Some(start) = template.find('{') {
+            message.push_str(&template[..start]);
+            let rest = &template[start..];
+            let Some(end) = rest.find('}') else {
+                template = rest;
+                break;
+            };
+            match args.iter().find(|(key, _)| *key == &rest[1..end]) {
+                Some((_, value)) => message.push_str(value),
+                None => message.push_str(&rest[..=end]),
+            }
+            template = &rest[end + 1..];
+        }
+        message.push_str(template);
+        message
+    }
+
+    /// The values of placeholders, keyed by the serialized field names
+    fn args(&self) -> Vec<(&'static str, String)> {
+        let common = |e: &CommonLinkError| vec![("from", e.from.to_string()), ("message", e.message.clone())];
+        match self {
+            LinkError::SystemError { message } | LinkError::EmptyComponents { message } => {
+                vec![("message", message.clone())]
+            }
+            LinkError::InvalidComponentId { id }
+            | LinkError::DuplicateComponentId { id }
+            | LinkError::CircularReference { id } => vec![("id", id.to_string())],
+            LinkError::UnknownComponentOrNotRefer { from, id } => vec![
+                ("from", from.map(|from| from.to_string()).unwrap_or_else(|| "?".into())),
+                ("id", id.to_string()),
+            ],
+            LinkError::AffluxComponentId { from, afflux } => {
+                vec![("from", from.to_string()), ("afflux", afflux.to_string())]
+            }
+            LinkError::InvalidEndpoint { from, inlet } => {
+                vec![("from", from.to_string()), ("inlet", inlet.to_string())]
+            }
+            LinkError::ReferNoOutputComponent { from, refer } => {
+                vec![("from", from.to_string()), ("refer", refer.to_string())]
+            }
+            LinkError::DuplicateParamName { name }
+            | LinkError::DuplicateFormName { name }
+            | LinkError::DuplicateIdentityName { name }
+            | LinkError::DuplicateInteractionName { name }
+            | LinkError::DuplicateVariableName { name } => vec![("name", name.clone())],
+            LinkError::MismatchedLinkValueType { from, value } => {
+                vec![("from", from.to_string()), ("value", json(value))]
+            }
+            LinkError::WrongLinkTypeForRefer { from, inlet, refer } => vec![
+                ("from", from.to_string()),
+                ("inlet", inlet.to_string()),
+                ("refer", json(refer)),
+            ],
+            LinkError::DuplicateObjectKey { from, key }
+            | LinkError::InvalidObjectKey { from, key }
+            | LinkError::InvalidVariantKey { from, key }
+            | LinkError::DuplicateVariantKey { from, key } => vec![("from", from.to_string()), ("key", key.clone())],
+            LinkError::InvalidName { from, name }
+            | LinkError::DuplicateName { from, name }
+            | LinkError::DuplicateSwitchCase { from, name }
+            | LinkError::UnreachableSwitchCase { from, name } => {
+                vec![("from", from.to_string()), ("name", name.clone())]
+            }
+            LinkError::MismatchedInlets { from }
+            | LinkError::MismatchedOutput { from }
+            | LinkError::InvalidConfirmText { from }
+            | LinkError::NeedlessCallHttpName { from }
+            | LinkError::InvalidCallIcApi { from } => vec![("from", from.to_string())],
+            LinkError::WrongCode(e) => vec![("from", e.from.to_string()), ("message", e.message.clone())],
+            LinkError::ValidateCodeFailed(e) => vec![
+                ("from", e.from.to_string()),
+                ("value", json(&e.value)),
+                ("message", e.message.clone()),
+            ],
+            LinkError::MismatchedConstValue { from, output, value }
+            | LinkError::MismatchedFormDefaultValue { from, output, value }
+            | LinkError::MismatchedVariableDefaultValue { from, output, value } => vec![
+                ("from", from.to_string()),
+                ("output", json(output)),
+                ("value", json(value)),
+            ],
+            LinkError::MismatchedFormSuffixValue { from, value } => {
+                vec![("from", from.to_string()), ("value", json(value))]
+            }
+            LinkError::InvalidIdentityHttpProxy { from, proxy } => {
+                vec![("from", from.to_string()), ("proxy", proxy.clone())]
+            }
+            LinkError::CompileCallIcCandid { from, message, .. } => {
+                vec![("from", from.to_string()), ("message", message.clone())]
+            }
+            LinkError::CompileCallIcCandidTypeUnsupported { from, ty } => {
+                vec![("from", from.to_string()), ("ty", ty.clone())]
+            }
+            LinkError::NonExhaustiveSwitch { from, missing } => {
+                vec![("from", from.to_string()), ("missing", missing.clone())]
+            }
+            LinkError::MultipleOutput => vec![],
+            LinkError::InvalidNamedValueType(e)
+            | LinkError::WrongConstValue(e)
+            | LinkError::InvalidIdentity(e)
+            | LinkError::InvalidCallTrigger(e)
+            | LinkError::InvalidCallIdentity(e)
+            | LinkError::InvalidCallOutputType(e)
+            | LinkError::InvalidCallHttpUrl(e)
+            | LinkError::InvalidCallIcCanisterId(e)
+            | LinkError::InvalidCallIcApiArg(e)
+            | LinkError::InvalidCallIcApiRet(e)
+            | LinkError::InvalidCallEvmActionContract(e)
+            | LinkError::InvalidCallEvmActionApi(e)
+            | LinkError::InvalidCallEvmActionArg(e)
+            | LinkError::InvalidCallEvmActionRet(e)
+            | LinkError::InvalidCallEvmActionSign(e)
+            | LinkError::InvalidCallEvmActionPayValue(e)
+            | LinkError::InvalidCallEvmActionGasLimit(e)
+            | LinkError::InvalidCallEvmActionGasPrice(e)
+            | LinkError::InvalidCallEvmActionNonce(e)
+            | LinkError::InvalidCallEvmActionAbi(e)
+            | LinkError::InvalidCallEvmActionBytecode(e)
+            | LinkError::InvalidCallEvmActionTransferTo(e)
+            | LinkError::InvalidCallEvmActionOutput(e)
+            | LinkError::InvalidInteractionComponent(e)
+            | LinkError::InvalidViewComponent(e)
+            | LinkError::InvalidCondition(e)
+            | LinkError::InvalidSwitch(e)
+            | LinkError::InvalidLoop(e)
+            | LinkError::InvalidVariable(e) => common(e),
+        }
+    }
+}
+
+/// The compact json of value
+fn json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
@@ -0,0 +1,93 @@
+use std::collections::HashSet;
+
+use crate::model::common::{lets::Endpoint, values::LinkValue};
+
+use super::{
+    CommonLinkError, LinkError,
+    message::{CATALOG, Locale},
+};
+
+#[test]
+fn test_catalog() {
+    let codes = CATALOG.iter().map(|m| m.code).collect::<HashSet<_>>();
+    let names = CATALOG.iter().map(|m| m.name).collect::<HashSet<_>>();
+    assert_eq!(codes.len(), CATALOG.len());
+    assert_eq!(names.len(), CATALOG.len());
+    for message in CATALOG {
+        assert!(
+            message.code.len() == 5 && message.code.starts_with('E'),
+            "{}",
+            message.code
+        );
+        // ? The placeholders of both locales are the same
+        let placeholders = |template: &str| {
+            let mut found = template
+                .split('{')
+                .skip(1)
+                .filter_map(|s| s.split_once('}').map(|(key, _)| key.to_string()))
+                .collect::<Vec<_>>();
+            found.sort();
+            found
+        };
+        assert_eq!(placeholders(message.en), placeholders(message.zh), "{}", message.name);
+    }
+}
+
+#[test]
+fn test_message() {
+    let error = LinkError::MismatchedInlets { from: 3.into() };
+    assert_eq!(error.code(), "E0215");
+    assert_eq!(error.name(), "MismatchedInlets");
+    assert_eq!(
+        error.to_string(),
+        "E0215 MismatchedInlets: inlets of component 3 do not match its references"
+    );
+    assert_eq!(error.message(Locale::Zh), "组件 3 的引入与引用不一致");
+
+    let error = LinkError::InvalidEndpoint {
+        from: 5.into(),
+        inlet: Endpoint {
+            id: 2.into(),
+            index: Some(1),
+        },
+    };
+    assert_eq!(error.message(Locale::En), "component 5 refers invalid endpoint 2#1");
+    assert_eq!(error.message(Locale::Zh), "组件 5 引用了无效的输出点 2#1");
+
+    let error = LinkError::MismatchedLinkValueType {
+        from: 1.into(),
+        value: LinkValue::Integer(7),
+    };
+    assert_eq!(
+        error.message(Locale::En),
+        r#"value {"integer":7} of component 1 does not match its type"#
+    );
+
+    let error = LinkError::InvalidCallHttpUrl(CommonLinkError {
+        from: 4.into(),
+        message: "empty".into(),
+    });
+    assert_eq!(error.code(), "E0612");
+    assert_eq!(error.message(Locale::En), "url of http call 4 is invalid: empty");
+
+    let error = LinkError::InvalidCallHttpUrl(CommonLinkError {
+        from: 6.into(),
+        message: "unknown {from} and {message}".into(),
+    });
+    assert_eq!(
+        error.message(Locale::En),
+        "url of http call 6 is invalid: unknown {from} and {message}"
+    );
+
+    let error = LinkError::UnknownComponentOrNotRefer {
+        from: Some(2.into()),
+        id: 9.into(),
+    };
+    assert_eq!(
+        error.message(Locale::En),
+        "component 2 refers component 9 which is unknown or not referred"
+    );
+    assert_eq!(error.message(Locale::Zh), "组件 2 引用的组件 9 不存在或未被引入");
+
+    assert_eq!(serde_json::from_str::<Locale>(r#""zh""#).ok(), Some(Locale::Zh));
+}
@@ -28,6 +28,12 @@ impl std::hash::Hash for ComponentId {
     }
 }
 
+impl core::fmt::Display for ComponentId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 impl serde::Serialize for ComponentId {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
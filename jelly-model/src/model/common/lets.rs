@@ -23,6 +23,15 @@ pub struct Endpoint {
     pub index: Option<u32>,
 }
 
+impl core::fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.index.filter(|index| *index != 0) {
+            Some(index) => write!(f, "{}#{}", self.id, index),
+            None => write!(f, "{}", self.id),
+        }
+    }
+}
+
 fn is_endpoint_index_skip(index: &Option<u32>) -> bool {
     index.as_ref().is_none_or(|index| *index == 0)
 }
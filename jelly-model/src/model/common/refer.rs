@@ -5,7 +5,7 @@ use super::{
     identity::ComponentId,
     lets::{AllEndpoints, Endpoint},
     types::{LinkType, ObjectSubitem},
-    values::LinkValue,
+    values::{LinkValue, ObjectSubitemValue},
 };
 
 /// Any variable name variable introduction
@@ -166,4 +166,17 @@ impl KeyRefer {
             refer: tip.clone(),
         })
     }
+
+    /// Query the value of key, additional key needs to recursively traversing search
+    pub fn get_value<'a>(&self, value: &'a LinkValue) -> Option<&'a LinkValue> {
+        if let LinkValue::Object(values) = value {
+            if let Some(ObjectSubitemValue { value, .. }) = values.iter().find(|item| item.key == self.key) {
+                return match &self.refer {
+                    Some(inner) => inner.get_value(value),
+                    None => Some(value),
+                };
+            }
+        }
+        None
+    }
 }
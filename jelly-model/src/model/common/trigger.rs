@@ -68,6 +68,19 @@ impl ComponentTriggered {
         }
     }
 
+    /// Build again without the checked result
+    pub fn renew(&self) -> Self {
+        match &self.triggered {
+            TriggeredComponent::Identity(TriggeredComponentIdentity { click }) => Self::from_identity(self.id, *click),
+            TriggeredComponent::Call(TriggeredComponentCall {
+                identity,
+                click,
+                update,
+            }) => Self::from_call(self.id, *identity, *click, *update),
+            TriggeredComponent::Interaction => Self::from_interaction(self.id),
+        }
+    }
+
     /// Check whether the necessary trigger method
     pub fn check(
         &self,
@@ -16,7 +16,7 @@ use super::super::store::combined::anchor::CombinedAnchor;
 use super::super::types::TimestampMills;
 use super::combined::{
     CombinedMetadata, ComponentFormRequired, ComponentIdentityRequired, ComponentInteractionRequired,
-    ComponentParamRequired,
+    ComponentParamRequired, ComponentVariableRequired,
 };
 use super::common::api::evm::EvmCallApi;
 use super::common::api::ic::IcCallApi;
@@ -66,7 +66,10 @@ pub mod view;
 /// condition
 pub mod condition;
 
-/// loop // ! Not yet
+/// switch
+pub mod switch;
+
+/// loop
 /// Four components
 /// 1. LoopStart: At the beginning of the cycle, the specified loop method, the traversal object is still infinitely loop
 /// 2. LoopEnd: Output each execution object
@@ -74,7 +77,7 @@ pub mod condition;
 /// 4. LoopBreak: Ignore this wheel and subsequent objects, can only be executed in order
 pub mod loops;
 
-/// variable // ! Not yet
+/// variable
 /// Two components
 /// 1. VariableValue
 /// 2. VariableAssign
@@ -94,8 +97,11 @@ use constant::ComponentConst;
 use form::ComponentForm;
 use identity::{ComponentIdentity, IdentityInnerMetadata, IdentityMetadata};
 use interaction::ComponentInteraction;
+use loops::{ComponentLoopBreak, ComponentLoopContinue, ComponentLoopEnd, ComponentLoopStart};
 use output::ComponentOutput;
 use param::ComponentParam;
+use switch::ComponentSwitch;
+use variable::{ComponentVariableAssign, ComponentVariableValue};
 use view::ComponentView;
 
 /// Component
@@ -137,6 +143,34 @@ pub enum LinkComponent {
     #[serde(rename = "condition")]
     Condition(ComponentCondition),
 
+    /// switch
+    #[serde(rename = "switch")]
+    Switch(ComponentSwitch),
+
+    /// loop start
+    #[serde(rename = "loop_start")]
+    LoopStart(ComponentLoopStart),
+
+    /// loop end
+    #[serde(rename = "loop_end")]
+    LoopEnd(ComponentLoopEnd),
+
+    /// loop continue
+    #[serde(rename = "loop_continue")]
+    LoopContinue(ComponentLoopContinue),
+
+    /// loop break
+    #[serde(rename = "loop_break")]
+    LoopBreak(ComponentLoopBreak),
+
+    /// variable value
+    #[serde(rename = "variable_value")]
+    VariableValue(ComponentVariableValue),
+
+    /// variable assign
+    #[serde(rename = "variable_assign")]
+    VariableAssign(ComponentVariableAssign),
+
     /// output
     #[serde(rename = "output")]
     Output(ComponentOutput),
@@ -159,6 +193,13 @@ impl LinkComponent {
             LinkComponent::Interaction(interaction) => interaction.id,
             LinkComponent::View(view) => view.id,
             LinkComponent::Condition(condition) => condition.id,
+            LinkComponent::Switch(switch) => switch.id,
+            LinkComponent::LoopStart(start) => start.id,
+            LinkComponent::LoopEnd(end) => end.id,
+            LinkComponent::LoopContinue(jump) => jump.id,
+            LinkComponent::LoopBreak(jump) => jump.id,
+            LinkComponent::VariableValue(variable) => variable.id,
+            LinkComponent::VariableAssign(assign) => assign.id,
             LinkComponent::Output(output) => output.id,
             LinkComponent::Combined(combined) => combined.id,
         }
@@ -176,6 +217,13 @@ impl LinkComponent {
             LinkComponent::Interaction(interaction) => interaction.get_inlets(),
             LinkComponent::View(view) => view.get_inlets(),
             LinkComponent::Condition(condition) => condition.get_inlets(),
+            LinkComponent::Switch(switch) => switch.get_inlets(),
+            LinkComponent::LoopStart(start) => start.get_inlets(),
+            LinkComponent::LoopEnd(end) => end.get_inlets(),
+            LinkComponent::LoopContinue(jump) => jump.get_inlets(),
+            LinkComponent::LoopBreak(jump) => jump.get_inlets(),
+            LinkComponent::VariableValue(variable) => variable.get_inlets(),
+            LinkComponent::VariableAssign(assign) => assign.get_inlets(),
             LinkComponent::Output(output) => output.get_inlets(),
             LinkComponent::Combined(combined) => combined.get_inlets(),
         }
@@ -193,6 +241,13 @@ impl LinkComponent {
             LinkComponent::Interaction(_) => 1,
             LinkComponent::View(_) => 1, // 1 access, but no data
             LinkComponent::Condition(condition) => condition.count_outputs(), // Multiple access, but no data
+            LinkComponent::Switch(switch) => switch.count_outputs(), // Multiple access, but no data
+            LinkComponent::LoopStart(_) => 1,
+            LinkComponent::LoopEnd(_) => 1,
+            LinkComponent::LoopContinue(_) => 1, // 1 access, but no data
+            LinkComponent::LoopBreak(_) => 1,    // 1 access, but no data
+            LinkComponent::VariableValue(_) => 1,
+            LinkComponent::VariableAssign(_) => 1, // 1 access, but no data
             LinkComponent::Output(_) => 1,
             LinkComponent::Combined(_) => 1, // 1 access, do you have any data to see if the combined has an output component
         }
@@ -210,6 +265,13 @@ impl LinkComponent {
             LinkComponent::Interaction(interaction) => interaction.get_code_anchors(),
             LinkComponent::View(_) => vec![],
             LinkComponent::Condition(_) => vec![],
+            LinkComponent::Switch(_) => vec![],
+            LinkComponent::LoopStart(_) => vec![],
+            LinkComponent::LoopEnd(_) => vec![],
+            LinkComponent::LoopContinue(_) => vec![],
+            LinkComponent::LoopBreak(_) => vec![],
+            LinkComponent::VariableValue(_) => vec![],
+            LinkComponent::VariableAssign(_) => vec![],
             LinkComponent::Output(_) => vec![],
             LinkComponent::Combined(_) => vec![], // What has been submitted is that there is no need to handle CODE
         }
@@ -227,6 +289,13 @@ impl LinkComponent {
             LinkComponent::Interaction(_) => vec![],
             LinkComponent::View(_) => vec![],
             LinkComponent::Condition(_) => vec![],
+            LinkComponent::Switch(_) => vec![],
+            LinkComponent::LoopStart(_) => vec![],
+            LinkComponent::LoopEnd(_) => vec![],
+            LinkComponent::LoopContinue(_) => vec![],
+            LinkComponent::LoopBreak(_) => vec![],
+            LinkComponent::VariableValue(_) => vec![],
+            LinkComponent::VariableAssign(_) => vec![],
             LinkComponent::Output(_) => vec![],
             LinkComponent::Combined(_) => vec![], // What has been submitted is no need to handle the API
         }
@@ -244,6 +313,13 @@ impl LinkComponent {
             LinkComponent::Interaction(_) => vec![],
             LinkComponent::View(_) => vec![],
             LinkComponent::Condition(_) => vec![],
+            LinkComponent::Switch(_) => vec![],
+            LinkComponent::LoopStart(_) => vec![],
+            LinkComponent::LoopEnd(_) => vec![],
+            LinkComponent::LoopContinue(_) => vec![],
+            LinkComponent::LoopBreak(_) => vec![],
+            LinkComponent::VariableValue(_) => vec![],
+            LinkComponent::VariableAssign(_) => vec![],
             LinkComponent::Output(_) => vec![],
             LinkComponent::Combined(combined) => combined.get_combined_anchors(),
         }
@@ -262,6 +338,9 @@ impl LinkComponent {
 
         for inlet in inlets {
             if let Some(c) = components.get(&inlet.id) {
+                if self.is_loop_back_edge(c) {
+                    continue; // ? The loop body is not the front path
+                }
                 endpoints.push(AllEndpoint {
                     id: inlet.id,
                     index: inlet.index.unwrap_or_default(),
@@ -316,7 +395,22 @@ impl LinkComponent {
                     refer: self.id(),
                 });
             } // ! No exact value is allowed
-            LinkComponent::Condition(_) => {
+            LinkComponent::Condition(_) | LinkComponent::Switch(_) => {
+                return Err(LinkError::ReferNoOutputComponent {
+                    from: *from,
+                    refer: self.id(),
+                });
+            } // ! No exact value is allowed
+            LinkComponent::LoopStart(start) => Cow::Borrowed(&start.output),
+            LinkComponent::LoopEnd(end) => Cow::Borrowed(&end.output),
+            LinkComponent::LoopContinue(_) | LinkComponent::LoopBreak(_) => {
+                return Err(LinkError::ReferNoOutputComponent {
+                    from: *from,
+                    refer: self.id(),
+                });
+            } // ! No exact value is allowed
+            LinkComponent::VariableValue(variable) => Cow::Borrowed(&variable.output),
+            LinkComponent::VariableAssign(_) => {
                 return Err(LinkError::ReferNoOutputComponent {
                     from: *from,
                     refer: self.id(),
@@ -352,6 +446,13 @@ impl LinkComponent {
             LinkComponent::Interaction(interaction) => interaction.get_origin_codes(),
             LinkComponent::View(_) => vec![],
             LinkComponent::Condition(_) => vec![],
+            LinkComponent::Switch(_) => vec![],
+            LinkComponent::LoopStart(_) => vec![],
+            LinkComponent::LoopEnd(_) => vec![],
+            LinkComponent::LoopContinue(_) => vec![],
+            LinkComponent::LoopBreak(_) => vec![],
+            LinkComponent::VariableValue(_) => vec![],
+            LinkComponent::VariableAssign(_) => vec![],
             LinkComponent::Output(_) => vec![],
             LinkComponent::Combined(_) => vec![], // What has been submitted is that there is no need to handle CODE
         };
@@ -390,6 +491,14 @@ impl LinkComponent {
         None
     }
 
+    /// Get the variable name
+    pub fn get_variable_name(&self) -> Option<&String> {
+        if let LinkComponent::VariableValue(variable) = self {
+            return Some(variable.get_variable_name());
+        }
+        None
+    }
+
     /// Query can be empty introduced point
     pub fn get_nullable_endpoints(&self) -> Option<Vec<Endpoint>> {
         match self {
@@ -402,11 +511,52 @@ impl LinkComponent {
             LinkComponent::Interaction(_) => None,
             LinkComponent::View(_) => None,
             LinkComponent::Condition(condition) => condition.get_nullable_endpoints(),
+            LinkComponent::Switch(_) => None,
+            LinkComponent::LoopStart(_) => None,
+            LinkComponent::LoopEnd(_) => None,
+            LinkComponent::LoopContinue(_) => None,
+            LinkComponent::LoopBreak(_) => None,
+            LinkComponent::VariableValue(_) => None,
+            LinkComponent::VariableAssign(_) => None,
             LinkComponent::Output(_) => None,
             LinkComponent::Combined(_) => None,
         }
     }
 
+    /// The loop start of loop end, continue and break
+    pub fn get_loop_start(&self) -> Option<ComponentId> {
+        match self {
+            LinkComponent::LoopEnd(end) => Some(end.metadata.start),
+            LinkComponent::LoopContinue(jump) => Some(jump.metadata.start),
+            LinkComponent::LoopBreak(jump) => Some(jump.metadata.start),
+            _ => None,
+        }
+    }
+
+    /// Whether the inlet is the back edge of loop body
+    /// The loop start introduces its own end, continue or break, it is not a cycle reference
+    pub fn is_loop_back_edge(&self, inlet: &LinkComponent) -> bool {
+        matches!(self, LinkComponent::LoopStart(_)) && inlet.get_loop_start() == Some(self.id())
+    }
+
+    /// All components referred by the component
+    /// Besides inlets, the loop start, the identity of call and the variable assigned are referred
+    pub fn get_references(&self) -> Vec<ComponentId> {
+        let mut references = self
+            .get_inlets()
+            .into_iter()
+            .flatten()
+            .map(|inlet| inlet.id)
+            .collect::<Vec<_>>();
+        references.extend(self.get_loop_start());
+        match self {
+            LinkComponent::Call(call) => references.extend(call.get_identity()),
+            LinkComponent::VariableAssign(assign) => references.push(assign.metadata.variable),
+            _ => {}
+        }
+        references
+    }
+
     /// Check whether the component is effective
     pub fn check<F: CheckFunction>(
         &self,
@@ -428,30 +578,44 @@ impl LinkComponent {
             }
             LinkComponent::View(view) => LinkComponent::View(view.check(endpoints)?),
             LinkComponent::Condition(condition) => LinkComponent::Condition(condition.check(endpoints)?),
+            LinkComponent::Switch(switch) => LinkComponent::Switch(switch.check(endpoints)?),
+            LinkComponent::LoopStart(start) => LinkComponent::LoopStart(start.check(endpoints)?),
+            LinkComponent::LoopEnd(end) => LinkComponent::LoopEnd(end.check(endpoints)?),
+            LinkComponent::LoopContinue(jump) => LinkComponent::LoopContinue(jump.check(endpoints)?),
+            LinkComponent::LoopBreak(jump) => LinkComponent::LoopBreak(jump.check(endpoints)?),
+            LinkComponent::VariableValue(variable) => LinkComponent::VariableValue(variable.check(endpoints)?),
+            LinkComponent::VariableAssign(assign) => LinkComponent::VariableAssign(assign.check(endpoints)?),
             LinkComponent::Output(output) => LinkComponent::Output(output.check(endpoints)?),
             LinkComponent::Combined(combined) => LinkComponent::Combined(combined.check(endpoints, fetch)?),
         };
         Ok(component)
     }
 
+    /// get param required
     pub(super) fn get_param_required(&self) -> Option<ComponentParamRequired> {
         if let LinkComponent::Param(param) = self {
             return Some(param.get_required());
         }
         None
     }
+
+    /// get form required
     pub(super) fn get_form_required(&self) -> Option<ComponentFormRequired> {
         if let LinkComponent::Form(form) = self {
             return Some(form.get_required());
         }
         None
     }
+
+    /// get identity required
     pub(super) fn get_identity_required(&self) -> Option<ComponentIdentityRequired> {
         if let LinkComponent::Identity(identity) = self {
             return identity.get_required();
         }
         None
     }
+
+    /// get interaction required
     pub(super) fn get_interaction_required(&self) -> Option<ComponentInteractionRequired> {
         if let LinkComponent::Interaction(interaction) = self {
             return Some(interaction.get_required());
@@ -459,6 +623,14 @@ impl LinkComponent {
         None
     }
 
+    /// get variable required
+    pub(super) fn get_variable_required(&self) -> Option<ComponentVariableRequired> {
+        if let LinkComponent::VariableValue(variable) = self {
+            return Some(variable.get_required());
+        }
+        None
+    }
+
     /// get call chain
     pub fn get_call_chain(&self) -> Option<CallChain> {
         if let LinkComponent::Identity(identity) = &self {
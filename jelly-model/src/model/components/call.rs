@@ -144,6 +144,15 @@ impl ComponentCall {
             CallMetadata::Evm(evm) => evm.get_call_chain(),
         }
     }
+
+    /// get the identity referred
+    pub fn get_identity(&self) -> Option<ComponentId> {
+        match &self.metadata {
+            CallMetadata::Http(http) => http.identity,
+            CallMetadata::Ic(ic) => ic.identity,
+            CallMetadata::Evm(evm) => evm.identity,
+        }
+    }
 }
 
 impl ComponentId {
@@ -19,7 +19,7 @@ use action::EvmAction;
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct CallEvmMetadata {
     /// Trigger condition
-    trigger: ComponentCallTrigger,
+    pub(crate) trigger: ComponentCallTrigger,
 
     /// The required identity empty indicates the use of anonymous identity
     /// It must be the IdentityEvmMetadata type node
@@ -27,10 +27,10 @@ pub struct CallEvmMetadata {
     pub identity: Option<ComponentId>,
 
     /// EVM compatible chain
-    chain: EvmChain,
+    pub(crate) chain: EvmChain,
 
     /// EVM call behavior
-    action: EvmAction,
+    pub(crate) action: EvmAction,
 }
 
 impl CallEvmMetadata {
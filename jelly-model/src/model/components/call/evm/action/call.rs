@@ -23,24 +23,24 @@ use ret::EvmCallRet;
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct EvmActionCall {
     /// If the target address is reference, it must be the text type // It must be in line with a string in 0xaaa format
-    contract: InputValue,
+    pub(crate) contract: InputValue,
 
     /// Specified method
-    api: EvmCallApi,
+    pub(crate) api: EvmCallApi,
 
     /// Call parameter
     // No parameter parameters
     // ! Simple parameters can be met with reference methods, and very complicated data structures are required to achieve
     // ? In most cases, users need to write code to meet the parameter data
     #[serde(skip_serializing_if = "Option::is_none")]
-    arg: Option<EvmCallArg>,
+    pub(crate) arg: Option<EvmCallArg>,
 
     /// Treatment after call results
     // Simple parameters can be converted into support types
     // ! Users can choose the specified simple data, which requires a very complicated data structure to achieve
     // ? In most cases, users need to write code to meet the output data
     #[serde(skip_serializing_if = "Option::is_none")]
-    ret: Option<EvmCallRet>,
+    pub(crate) ret: Option<EvmCallRet>,
 }
 
 impl EvmActionCall {
@@ -26,20 +26,20 @@ pub struct EvmActionDeploy {
     // #[serde(skip_serializing_if = "Option::is_none")]
     // pay_value: Option<InputValue>, // Whether to send tokens wei unit // text type // ! Deploying contracts cannot be accepted for transfer
     #[serde(skip_serializing_if = "Option::is_none")]
-    gas_limit: Option<InputValue>, // gas limit // integer type
+    pub(crate) gas_limit: Option<InputValue>, // gas limit // integer type
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    gas_price: Option<InputValue>, // gas price // text type
+    pub(crate) gas_price: Option<InputValue>, // gas price // text type
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    nonce: Option<InputValue>, // Whether to specify nonce // integer type
+    pub(crate) nonce: Option<InputValue>, // Whether to specify nonce // integer type
 
-    abi: InputValue, // Deploy contract code // text type json
+    pub(crate) abi: InputValue, // Deploy contract code // text type json
 
-    bytecode: InputValue, // Deploy contract code // text type hex
+    pub(crate) bytecode: InputValue, // Deploy contract code // text type hex
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    initial: Option<EvmDeployInitial>, // Construction parameters of contract code
+    pub(crate) initial: Option<EvmDeployInitial>, // Construction parameters of contract code
 }
 
 impl EvmActionDeploy {
@@ -12,9 +12,9 @@ use super::{
 pub struct EvmDeployInitialCode {
     /// Code parameter
     #[serde(skip_serializing_if = "crate::is_empty_option_vec")]
-    data: Option<Vec<CodeValue>>,
+    pub(crate) data: Option<Vec<CodeValue>>,
     /// Code
-    code: CodeContent,
+    pub(crate) code: CodeContent,
 }
 
 impl EvmDeployInitialCode {
@@ -18,29 +18,29 @@ lazy_static! {
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct EvmActionTransaction {
     /// If the target address is reference, it must be the text type // It must be in line with a string in 0xaaa format
-    contract: InputValue,
+    pub(crate) contract: InputValue,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pay_value: Option<InputValue>, // Whether to send tokens wei unit // text type
+    pub(crate) pay_value: Option<InputValue>, // Whether to send tokens wei unit // text type
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    gas_limit: Option<InputValue>, // gas limit // integer type
+    pub(crate) gas_limit: Option<InputValue>, // gas limit // integer type
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    gas_price: Option<InputValue>, // gas price // text type
+    pub(crate) gas_price: Option<InputValue>, // gas price // text type
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    nonce: Option<InputValue>, // Whether to specify nonce // integer type
+    pub(crate) nonce: Option<InputValue>, // Whether to specify nonce // integer type
 
     /// Specified method
-    api: EvmCallApi,
+    pub(crate) api: EvmCallApi,
 
     /// Call parameter
     // No parameter parameters
     // ! Simple parameters can be met with reference methods, and very complicated data structures are required to achieve
     // ? In most cases, users need to write code to meet the parameter data
     #[serde(skip_serializing_if = "Option::is_none")]
-    arg: Option<EvmCallArg>,
+    pub(crate) arg: Option<EvmCallArg>,
     // There should be post -processing transactions, because only the transaction ID can be returned
     // /// Treatment after call results
     // // Simple parameters can be converted into support types
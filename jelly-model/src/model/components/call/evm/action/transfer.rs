@@ -11,15 +11,15 @@ lazy_static! {
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct EvmActionTransfer {
     /// If the target address is reference, it must be the text type // It must be in line with a string in 0xaaa format
-    transfer_to: InputValue,
+    pub(crate) transfer_to: InputValue,
 
-    pay_value: InputValue, // Whether to send tokens wei unit // text type
+    pub(crate) pay_value: InputValue, // Whether to send tokens wei unit // text type
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    gas_price: Option<InputValue>, // gas price // text type
+    pub(crate) gas_price: Option<InputValue>, // gas price // text type
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    nonce: Option<InputValue>, // Whether to specify nonce // integer type
+    pub(crate) nonce: Option<InputValue>, // Whether to specify nonce // integer type
 }
 
 impl EvmActionTransfer {
@@ -31,7 +31,7 @@ impl HttpBodyPlain {
 pub struct HttpBodyCode {
     /// Code parameter
     #[serde(skip_serializing_if = "crate::is_empty_option_vec")]
-    data: Option<Vec<CodeValue>>,
+    pub(crate) data: Option<Vec<CodeValue>>,
     /// Code
     pub code: CodeContent,
 }
@@ -47,7 +47,7 @@ pub struct IcActionCall {
     /// Call parameter
     // No parameter parameters
     // ! Simple parameters can be met with reference methods, and very complicated data structures are required to achieve
-    // ? In most cases, users need to write code to meet the parameter data
+    // ? In most cases, users need to write code to meet the parameter data, or build it from candid type
     #[serde(skip_serializing_if = "Option::is_none")]
     pub arg: Option<IcCallArg>,
 
@@ -125,8 +125,8 @@ impl IcActionCall {
     fn check_arg<F: CheckFunction, H>(
         &self,
         endpoints: &AllEndpoints<'_>,
-        args_type: IcFunctionArgsType,
-        api_data: &CodeType, // API parameter type, here is the output result of the code
+        args: &[WrappedCandidType], // API parameters
+        api_data: &CodeType,        // API parameter type, here is the output result of the code
         from: ComponentId,
         fetch: &F,
         codes: &mut HashMap<CodeDataAnchor, CodeData>,
@@ -136,7 +136,7 @@ impl IcActionCall {
         H: FnMut(CodeType),
     {
         // 1 Check whether the presence of the parameter matches
-        match args_type {
+        match types_args_type(args) {
             IcFunctionArgsType::None => {
                 if self.arg.is_some() {
                     // 0 parameters do not require ARG
@@ -162,7 +162,7 @@ impl IcActionCall {
         // 2. Check the parameters
         let mut arg = None;
         if let Some(arg_ref) = &self.arg {
-            arg = Some(arg_ref.check(endpoints, api_data.to_owned(), from, fetch, codes, handle)?);
+            arg = Some(arg_ref.check(endpoints, args, api_data.to_owned(), from, fetch, codes, handle)?);
         }
 
         Ok(arg)
@@ -268,8 +268,7 @@ impl IcActionCall {
         );
 
         // 5. check arg
-        let args_type = types_args_type(&func.args);
-        let arg = self.check_arg(endpoints, args_type, &api_data, from, fetch, codes, |data| {
+        let arg = self.check_arg(endpoints, &func.args, &api_data, from, fetch, codes, |data| {
             data_of_args = data
         })?;
 
@@ -1,22 +1,28 @@
 use std::{borrow::Cow, collections::HashMap};
 
+use ic_canister_kit::types::WrappedCandidType;
 use serde::{Deserialize, Serialize};
 
 use crate::store::code::item::types::CodeType;
 
 use super::{
     AllEndpoints, ArgCodeType, CheckFunction, CheckedCodeItem, CodeContent, CodeData, CodeDataAnchor, CodeItem,
-    CodeValue, ComponentId, LinkError,
+    CodeValue, ComponentId, InputValue, LinkError, LinkType,
 };
 
+/// candid arg
+pub mod candid;
+
+use candid::IcCallArgCandid;
+
 /// ic call arg code
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct IcCallArgCode {
     /// Code parameter
     #[serde(skip_serializing_if = "crate::is_empty_option_vec")]
-    data: Option<Vec<CodeValue>>,
+    pub(crate) data: Option<Vec<CodeValue>>,
     /// Code
-    code: CodeContent,
+    pub(crate) code: CodeContent,
 }
 
 impl IcCallArgCode {
@@ -107,6 +113,9 @@ pub enum IcCallArg {
     /// Code calculation
     #[serde(rename = "code")]
     Code(IcCallArgCode),
+    /// Build from candid type
+    #[serde(rename = "candid")]
+    Candid(IcCallArgCandid),
 }
 
 impl IcCallArg {
@@ -116,6 +125,7 @@ impl IcCallArg {
 
         match self {
             IcCallArg::Code(code) => anchors.extend(code.get_code_anchors()),
+            IcCallArg::Candid(_) => {}
         }
 
         anchors
@@ -136,17 +146,20 @@ impl IcCallArg {
 
         match self {
             IcCallArg::Code(code) => codes.extend(code.get_origin_codes(endpoints, output, from, handle)?),
+            IcCallArg::Candid(_) => {}
         }
 
         Ok(codes)
     }
 
     /// Check whether the component is effective
+    #[allow(clippy::too_many_arguments)]
     #[inline]
     pub fn check<F: CheckFunction, H>(
         &self,
         endpoints: &AllEndpoints<'_>,
-        output: CodeType, // API parameter type
+        args: &[WrappedCandidType], // API parameters
+        output: CodeType,           // API parameter type
         from: ComponentId,
         fetch: &F,
         codes: &mut HashMap<CodeDataAnchor, CodeData>,
@@ -157,6 +170,7 @@ impl IcCallArg {
     {
         let arg = match self {
             Self::Code(code) => Self::Code(code.check(endpoints, output, from, fetch, codes, handle)?),
+            Self::Candid(candid) => Self::Candid(candid.check(endpoints, args, from)?),
         };
 
         Ok(arg)
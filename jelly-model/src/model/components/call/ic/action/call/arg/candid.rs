@@ -0,0 +1,423 @@
+use std::collections::HashSet;
+
+use ::candid::{
+    Principal,
+    types::{
+        Label,
+        number::{Int, Nat},
+        value::{IDLArgs, IDLField, IDLValue, VariantValue},
+    },
+};
+use ic_canister_kit::types::WrappedCandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{
+    common::values::{ArrayLinkValue, LinkValue},
+    components::condition::ConditionValues,
+};
+
+use super::{AllEndpoints, ComponentId, InputValue, LinkError, LinkType};
+
+/// test
+#[cfg(test)]
+mod test;
+
+/// ic call arg built from candid type, no code is required
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct IcCallArgCandid {
+    /// Each parameter of api, the omitted parameters at the end must be opt
+    pub args: Vec<CandidArgValue>,
+}
+
+/// The value of candid type
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum CandidArgValue {
+    /// Convert the value of input, the type must be compatible
+    // ? nat/int/nat64/int64 can be integer or text, nat8 ~ int32 must be integer
+    // ? principal must be text, the variant without value can be selected by text
+    #[serde(rename = "value")]
+    Value(InputValue),
+    /// null, also None of opt and reserved
+    #[serde(rename = "null")]
+    Null,
+    /// opt, None if absent
+    #[serde(rename = "opt")]
+    Opt(Option<Box<CandidArgValue>>),
+    /// vec
+    #[serde(rename = "vec")]
+    Vec(Vec<CandidArgValue>),
+    /// record, the omitted fields must be opt
+    #[serde(rename = "record")]
+    Record(Vec<CandidArgField>),
+    /// variant
+    #[serde(rename = "variant")]
+    Variant(CandidArgVariant),
+    /// tuple
+    #[serde(rename = "tuple")]
+    Tuple(Vec<CandidArgValue>),
+}
+
+/// The field of record
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct CandidArgField {
+    /// field name
+    pub key: String,
+    /// field value
+    pub value: CandidArgValue,
+}
+
+/// The selected tag of variant
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct CandidArgVariant {
+    /// tag name
+    pub tag: String,
+    /// The value of tag, can be omitted if the tag has no value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Box<CandidArgValue>>,
+}
+
+impl IcCallArgCandid {
+    /// Check whether the arg is compatible with the parameters of api
+    pub fn check(
+        &self,
+        endpoints: &AllEndpoints<'_>,
+        args: &[WrappedCandidType],
+        from: ComponentId,
+    ) -> Result<Self, LinkError> {
+        let error = |message: String| LinkError::InvalidCallIcApiArg((from, message).into());
+        let mut leaf = |input: &InputValue, ty: &WrappedCandidType, path: &str| match input {
+            InputValue::Const(constant) => {
+                constant.check(from)?;
+                link_value_to_idl(ty, constant, path).map(Some).map_err(error)
+            }
+            InputValue::Refer(_) => {
+                let link = endpoints.check_input_value(input, from)?;
+                if !is_compatible(ty, &link) {
+                    return Err(error(format!(
+                        "{path}: {} can not be {}",
+                        link.typescript(),
+                        ty.to_text()
+                    )));
+                }
+                Ok(Some(IDLValue::Reserved)) // ? The value is unknown until running
+            }
+        };
+        self.build(args, &mut leaf, &error)?;
+        Ok(self.clone())
+    }
+
+    /// Convert to candid args by the values of endpoints, None if some value is missing
+    pub fn to_idl_args(
+        &self,
+        args: &[WrappedCandidType],
+        values: &dyn ConditionValues,
+    ) -> Result<Option<IDLArgs>, String> {
+        let mut leaf = |input: &InputValue, ty: &WrappedCandidType, path: &str| match values.input_value(input) {
+            Some(value) => link_value_to_idl(ty, &value, path).map(Some),
+            None => Ok(None),
+        };
+        Ok(self
+            .build(args, &mut leaf, &|message| message)?
+            .map(|args| IDLArgs { args }))
+    }
+
+    fn build<E, L>(
+        &self,
+        args: &[WrappedCandidType],
+        leaf: &mut L,
+        error: &impl Fn(String) -> E,
+    ) -> Result<Option<Vec<IDLValue>>, E>
+    where
+        L: FnMut(&InputValue, &WrappedCandidType, &str) -> Result<Option<IDLValue>, E>,
+    {
+        if args.len() < self.args.len() {
+            return Err(error(format!(
+                "api has {} args, but found {}",
+                args.len(),
+                self.args.len()
+            )));
+        }
+        let mut values = Vec::with_capacity(args.len());
+        for (i, ty) in args.iter().enumerate() {
+            let path = format!("args[{i}]");
+            let value = match self.args.get(i) {
+                Some(value) => value.build(ty, &path, leaf, error)?,
+                None => Some(omitted(ty).ok_or_else(|| error(format!("{path}: {} is required", ty.to_text())))?),
+            };
+            let Some(value) = value else {
+                return Ok(None);
+            };
+            values.push(value);
+        }
+        Ok(Some(values))
+    }
+}
+
+impl CandidArgValue {
+    fn build<E, L>(
+        &self,
+        ty: &WrappedCandidType,
+        path: &str,
+        leaf: &mut L,
+        error: &impl Fn(String) -> E,
+    ) -> Result<Option<IDLValue>, E>
+    where
+        L: FnMut(&InputValue, &WrappedCandidType, &str) -> Result<Option<IDLValue>, E>,
+    {
+        let mismatch = |kind: &str| error(format!("{path}: {kind} can not be {}", ty.to_text()));
+        let value = match (self, ty) {
+            (_, WrappedCandidType::Rec(rec)) => return self.build(&rec.ty, path, leaf, error),
+            (Self::Value(input), _) => return leaf(input, ty, path),
+            (Self::Null, _) => omitted(ty).ok_or_else(|| mismatch("null"))?,
+            (Self::Opt(value), WrappedCandidType::Opt(opt)) => match value {
+                Some(value) => match value.build(&opt.subtype, &format!("{path}?"), leaf, error)? {
+                    Some(value) => IDLValue::Opt(Box::new(value)),
+                    None => return Ok(None),
+                },
+                None => IDLValue::None,
+            },
+            (Self::Vec(items), WrappedCandidType::Vec(vec)) => {
+                let mut values = Vec::with_capacity(items.len());
+                for (i, item) in items.iter().enumerate() {
+                    let Some(value) = item.build(&vec.subtype, &format!("{path}[{i}]"), leaf, error)? else {
+                        return Ok(None);
+                    };
+                    values.push(value);
+                }
+                IDLValue::Vec(values)
+            }
+            (Self::Record(fields), WrappedCandidType::Record(record)) => {
+                let mut visited = HashSet::new();
+                for field in fields {
+                    if !visited.insert(&field.key) {
+                        return Err(error(format!("{path}: duplicate field {}", field.key)));
+                    }
+                    if !record.subitems.iter().any(|(key, _)| key == &field.key) {
+                        return Err(error(format!("{path}: unknown field {}", field.key)));
+                    }
+                }
+                let mut values = Vec::with_capacity(record.subitems.len());
+                for (key, ty) in &record.subitems {
+                    let path = format!("{path}.{key}");
+                    let value = match fields.iter().find(|field| &field.key == key) {
+                        Some(field) => field.value.build(ty, &path, leaf, error)?,
+                        None => Some(omitted(ty).ok_or_else(|| error(format!("{path}: field is required")))?),
+                    };
+                    let Some(value) = value else {
+                        return Ok(None);
+                    };
+                    values.push(named_field(key, value));
+                }
+                record_value(values)
+            }
+            (Self::Variant(CandidArgVariant { tag, value }), WrappedCandidType::Variant(variant)) => {
+                let Some((_, tag_ty)) = variant.subitems.iter().find(|(key, _)| key == tag) else {
+                    return Err(error(format!("{path}: unknown tag {tag}")));
+                };
+                let path = format!("{path}.{tag}");
+                let value = match (value, tag_ty) {
+                    (Some(value), Some(tag_ty)) => value.build(tag_ty, &path, leaf, error)?,
+                    (Some(_), None) => return Err(error(format!("{path}: tag has no value"))),
+                    (None, Some(tag_ty)) => {
+                        Some(omitted(tag_ty).ok_or_else(|| error(format!("{path}: value is required")))?)
+                    }
+                    (None, None) => Some(IDLValue::Null),
+                };
+                let Some(value) = value else {
+                    return Ok(None);
+                };
+                variant_value(tag, value)
+            }
+            (Self::Tuple(items), WrappedCandidType::Tuple(tuple)) => {
+                if items.len() != tuple.subitems.len() {
+                    return Err(error(format!(
+                        "{path}: tuple has {} items, but found {}",
+                        tuple.subitems.len(),
+                        items.len()
+                    )));
+                }
+                let mut values = Vec::with_capacity(items.len());
+                for (i, (item, ty)) in items.iter().zip(tuple.subitems.iter()).enumerate() {
+                    let Some(value) = item.build(ty, &format!("{path}.{i}"), leaf, error)? else {
+                        return Ok(None);
+                    };
+                    values.push(IDLField {
+                        id: Label::Unnamed(i as u32),
+                        val: value,
+                    });
+                }
+                record_value(values)
+            }
+            (Self::Opt(_), _) => return Err(mismatch("opt")),
+            (Self::Vec(_), _) => return Err(mismatch("vec")),
+            (Self::Record(_), _) => return Err(mismatch("record")),
+            (Self::Variant(_), _) => return Err(mismatch("variant")),
+            (Self::Tuple(_), _) => return Err(mismatch("tuple")),
+        };
+        Ok(Some(value))
+    }
+}
+
+/// The value if it is omitted, None if it is required
+fn omitted(ty: &WrappedCandidType) -> Option<IDLValue> {
+    match ty {
+        WrappedCandidType::Opt(_) => Some(IDLValue::None),
+        WrappedCandidType::Null(_) => Some(IDLValue::Null),
+        WrappedCandidType::Reserved(_) => Some(IDLValue::Reserved),
+        WrappedCandidType::Rec(rec) => omitted(&rec.ty),
+        _ => None,
+    }
+}
+
+/// Whether the value of link type can be converted to candid type
+fn is_compatible(ty: &WrappedCandidType, link: &LinkType) -> bool {
+    match ty {
+        WrappedCandidType::Bool(_) => matches!(link, LinkType::Bool),
+        WrappedCandidType::Nat(_)
+        | WrappedCandidType::Int(_)
+        | WrappedCandidType::Nat64(_)
+        | WrappedCandidType::Int64(_) => matches!(link, LinkType::Integer | LinkType::Text),
+        WrappedCandidType::Nat8(_)
+        | WrappedCandidType::Nat16(_)
+        | WrappedCandidType::Nat32(_)
+        | WrappedCandidType::Int8(_)
+        | WrappedCandidType::Int16(_)
+        | WrappedCandidType::Int32(_) => matches!(link, LinkType::Integer),
+        WrappedCandidType::Float32(_) | WrappedCandidType::Float64(_) => {
+            matches!(link, LinkType::Number | LinkType::Integer)
+        }
+        WrappedCandidType::Text(_) | WrappedCandidType::Principal(_) => matches!(link, LinkType::Text),
+        WrappedCandidType::Reserved(_) => true,
+        WrappedCandidType::Opt(opt) => is_compatible(&opt.subtype, link),
+        WrappedCandidType::Vec(vec) => match link {
+            LinkType::Array(link) => is_compatible(&vec.subtype, link),
+            _ => false,
+        },
+        WrappedCandidType::Record(record) => match link {
+            LinkType::Object(subitems) => {
+                record
+                    .subitems
+                    .iter()
+                    .all(|(key, ty)| match subitems.iter().find(|subitem| &subitem.key == key) {
+                        Some(subitem) => is_compatible(ty, &subitem.ty),
+                        None => omitted(ty).is_some(),
+                    })
+            }
+            _ => false,
+        },
+        WrappedCandidType::Variant(variant) => {
+            matches!(link, LinkType::Text) && variant.subitems.iter().any(|(_, ty)| is_empty_tag(ty.as_ref()))
+        }
+        WrappedCandidType::Rec(rec) => is_compatible(&rec.ty, link),
+        _ => false,
+    }
+}
+
+/// Whether the tag of variant can be selected without value
+fn is_empty_tag(ty: Option<&WrappedCandidType>) -> bool {
+    ty.is_none_or(|ty| matches!(ty, WrappedCandidType::Null(_)))
+}
+
+/// Convert the value to candid type
+fn link_value_to_idl(ty: &WrappedCandidType, value: &LinkValue, path: &str) -> Result<IDLValue, String> {
+    let mismatch = || format!("{path}: {} can not be {}", value.link_type().typescript(), ty.to_text());
+    let out_of_range = |value: &dyn std::fmt::Display| format!("{path}: {value} is out of range of {}", ty.to_text());
+    let value = match (ty, value) {
+        (WrappedCandidType::Bool(_), LinkValue::Bool(value)) => IDLValue::Bool(*value),
+        (WrappedCandidType::Nat(_), LinkValue::Integer(value)) => {
+            IDLValue::Nat(Nat::from(u64::try_from(*value).map_err(|_| out_of_range(value))?))
+        }
+        (WrappedCandidType::Nat(_), LinkValue::Text(value)) => {
+            IDLValue::Nat(value.parse::<Nat>().map_err(|_| out_of_range(value))?)
+        }
+        (WrappedCandidType::Int(_), LinkValue::Integer(value)) => IDLValue::Int(Int::from(*value)),
+        (WrappedCandidType::Int(_), LinkValue::Text(value)) => {
+            IDLValue::Int(value.parse::<Int>().map_err(|_| out_of_range(value))?)
+        }
+        (WrappedCandidType::Nat8(_), LinkValue::Integer(value)) => {
+            IDLValue::Nat8(u8::try_from(*value).map_err(|_| out_of_range(value))?)
+        }
+        (WrappedCandidType::Nat16(_), LinkValue::Integer(value)) => {
+            IDLValue::Nat16(u16::try_from(*value).map_err(|_| out_of_range(value))?)
+        }
+        (WrappedCandidType::Nat32(_), LinkValue::Integer(value)) => {
+            IDLValue::Nat32(u32::try_from(*value).map_err(|_| out_of_range(value))?)
+        }
+        (WrappedCandidType::Nat64(_), LinkValue::Integer(value)) => {
+            IDLValue::Nat64(u64::try_from(*value).map_err(|_| out_of_range(value))?)
+        }
+        (WrappedCandidType::Nat64(_), LinkValue::Text(value)) => {
+            IDLValue::Nat64(value.parse::<u64>().map_err(|_| out_of_range(value))?)
+        }
+        (WrappedCandidType::Int8(_), LinkValue::Integer(value)) => {
+            IDLValue::Int8(i8::try_from(*value).map_err(|_| out_of_range(value))?)
+        }
+        (WrappedCandidType::Int16(_), LinkValue::Integer(value)) => {
+            IDLValue::Int16(i16::try_from(*value).map_err(|_| out_of_range(value))?)
+        }
+        (WrappedCandidType::Int32(_), LinkValue::Integer(value)) => {
+            IDLValue::Int32(i32::try_from(*value).map_err(|_| out_of_range(value))?)
+        }
+        (WrappedCandidType::Int64(_), LinkValue::Integer(value)) => IDLValue::Int64(*value),
+        (WrappedCandidType::Int64(_), LinkValue::Text(value)) => {
+            IDLValue::Int64(value.parse::<i64>().map_err(|_| out_of_range(value))?)
+        }
+        (WrappedCandidType::Float32(_), LinkValue::Number(value)) => IDLValue::Float32(*value as f32),
+        (WrappedCandidType::Float32(_), LinkValue::Integer(value)) => IDLValue::Float32(*value as f32),
+        (WrappedCandidType::Float64(_), LinkValue::Number(value)) => IDLValue::Float64(*value),
+        (WrappedCandidType::Float64(_), LinkValue::Integer(value)) => IDLValue::Float64(*value as f64),
+        (WrappedCandidType::Text(_), LinkValue::Text(value)) => IDLValue::Text(value.clone()),
+        (WrappedCandidType::Principal(_), LinkValue::Text(value)) => {
+            IDLValue::Principal(Principal::from_text(value).map_err(|_| format!("{path}: {value} is not principal"))?)
+        }
+        (WrappedCandidType::Reserved(_), _) => IDLValue::Reserved,
+        (WrappedCandidType::Opt(opt), value) => IDLValue::Opt(Box::new(link_value_to_idl(&opt.subtype, value, path)?)),
+        (WrappedCandidType::Vec(vec), LinkValue::Array(ArrayLinkValue { values, .. })) => {
+            let mut items = Vec::with_capacity(values.len());
+            for (i, value) in values.iter().enumerate() {
+                items.push(link_value_to_idl(&vec.subtype, value, &format!("{path}[{i}]"))?);
+            }
+            IDLValue::Vec(items)
+        }
+        (WrappedCandidType::Record(record), LinkValue::Object(subitems)) => {
+            let mut values = Vec::with_capacity(record.subitems.len());
+            for (key, ty) in &record.subitems {
+                let path = format!("{path}.{key}");
+                let value = match subitems.iter().find(|subitem| &subitem.key == key) {
+                    Some(subitem) => link_value_to_idl(ty, &subitem.value, &path)?,
+                    None => omitted(ty).ok_or_else(|| format!("{path}: field is required"))?,
+                };
+                values.push(named_field(key, value));
+            }
+            record_value(values)
+        }
+        (WrappedCandidType::Variant(variant), LinkValue::Text(tag)) => {
+            match variant.subitems.iter().find(|(key, _)| key == tag) {
+                Some((_, ty)) if is_empty_tag(ty.as_ref()) => variant_value(tag, IDLValue::Null),
+                Some(_) => return Err(format!("{path}: tag {tag} requires value")),
+                None => return Err(format!("{path}: unknown tag {tag}")),
+            }
+        }
+        (WrappedCandidType::Rec(rec), value) => link_value_to_idl(&rec.ty, value, path)?,
+        _ => return Err(mismatch()),
+    };
+    Ok(value)
+}
+
+fn named_field(key: &str, value: IDLValue) -> IDLField {
+    IDLField {
+        id: Label::Named(key.to_string()),
+        val: value,
+    }
+}
+
+/// The fields of record must be sorted by the hash of label
+fn record_value(mut fields: Vec<IDLField>) -> IDLValue {
+    fields.sort_by_key(|field| field.id.get_id());
+    IDLValue::Record(fields)
+}
+
+// ? The index is 0 since only the selected tag is serialized
+fn variant_value(tag: &str, value: IDLValue) -> IDLValue {
+    IDLValue::Variant(VariantValue(Box::new(named_field(tag, value)), 0))
+}
@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+
+use ::candid::{CandidType, Deserialize, Principal, decode_args};
+use ic_canister_kit::types::WrappedCandidType;
+
+use crate::model::{
+    LinkComponent,
+    common::{
+        api::ic::candid::parse_candid_for_data_and_output,
+        identity::ComponentId,
+        lets::{AllEndpoint, AllEndpoints, Endpoint},
+        refer::{InputValue, ReferValue},
+        types::LinkType,
+        values::{ArrayLinkValue, LinkValue},
+    },
+    components::{
+        condition::ConditionValues,
+        constant::{ComponentConst, ConstMetadata},
+    },
+};
+
+use super::{CandidArgField, CandidArgValue, CandidArgVariant, IcCallArgCandid};
+
+const API: &str = "service: { transfer : (record { owner : principal; amount : nat; memo : opt text; fee : vec nat64; kind : variant { fast; slow : nat8 } }, opt nat32) -> () }";
+
+const OWNER: &str = "rrkah-fqaaa-aaaaa-aaaaq-cai";
+
+/// The outputs of components
+struct Values(HashMap<ComponentId, LinkValue>);
+
+impl ConditionValues for Values {
+    fn refer_value(&self, refer: &ReferValue) -> Option<LinkValue> {
+        self.0.get(&refer.endpoint.id).cloned()
+    }
+}
+
+fn args() -> Vec<WrappedCandidType> {
+    #[allow(clippy::unwrap_used)] // ? checked
+    parse_candid_for_data_and_output(API, None, 0.into()).unwrap().args
+}
+
+fn constant(id: u32, output: LinkType, value: LinkValue) -> LinkComponent {
+    LinkComponent::Const(ComponentConst {
+        id: id.into(),
+        metadata: ConstMetadata { value },
+        output,
+    })
+}
+
+fn endpoints(components: &[LinkComponent]) -> AllEndpoints<'_> {
+    AllEndpoints {
+        endpoints: components
+            .iter()
+            .map(|component| AllEndpoint {
+                id: component.id(),
+                index: 0,
+                component,
+                inlets: None,
+            })
+            .collect(),
+    }
+}
+
+fn refer(id: u32) -> CandidArgValue {
+    CandidArgValue::Value(InputValue::Refer(ReferValue {
+        endpoint: Endpoint {
+            id: id.into(),
+            index: None,
+        },
+        refer: None,
+    }))
+}
+
+fn value(value: LinkValue) -> CandidArgValue {
+    CandidArgValue::Value(InputValue::Const(value))
+}
+
+fn field(key: &str, value: CandidArgValue) -> CandidArgField {
+    CandidArgField { key: key.into(), value }
+}
+
+fn arg(kind: CandidArgVariant, fields: Vec<CandidArgField>) -> IcCallArgCandid {
+    let mut record = vec![
+        field("owner", value(LinkValue::Text(OWNER.into()))),
+        field("amount", refer(1)),
+        field("fee", refer(2)),
+        field("kind", CandidArgValue::Variant(kind)),
+    ];
+    record.extend(fields);
+    IcCallArgCandid {
+        args: vec![CandidArgValue::Record(record)],
+    }
+}
+
+fn slow(speed: CandidArgValue) -> CandidArgVariant {
+    CandidArgVariant {
+        tag: "slow".into(),
+        value: Some(Box::new(speed)),
+    }
+}
+
+#[test]
+fn test_check() {
+    let components = vec![
+        constant(1, LinkType::Integer, LinkValue::Integer(100)),
+        constant(
+            2,
+            LinkType::Array(Box::new(LinkType::Integer)),
+            LinkValue::Array(ArrayLinkValue {
+                ty: LinkType::Integer,
+                values: vec![LinkValue::Integer(1)],
+            }),
+        ),
+        constant(3, LinkType::Bool, LinkValue::Bool(true)),
+        constant(4, LinkType::Text, LinkValue::Text("fast".into())),
+    ];
+    let endpoints = endpoints(&components);
+    let args = args();
+    let check = |arg: &IcCallArgCandid| arg.check(&endpoints, &args, 0.into()).map_err(|e| e.to_string());
+
+    // memo and the second arg are omitted
+    let passed = arg(slow(value(LinkValue::Integer(255))), vec![]);
+    assert_eq!(check(&passed), Ok(passed.clone()));
+    let passed = arg(
+        CandidArgVariant {
+            tag: "fast".into(),
+            value: None,
+        },
+        vec![field(
+            "memo",
+            CandidArgValue::Opt(Some(Box::new(value(LinkValue::Text("hi".into()))))),
+        )],
+    );
+    assert!(check(&passed).is_ok());
+    // the variant without value can be selected by text
+    let mut passed = arg(slow(value(LinkValue::Integer(1))), vec![]);
+    if let CandidArgValue::Record(record) = &mut passed.args[0] {
+        record[3].value = refer(4);
+    }
+    assert!(check(&passed).is_ok());
+
+    // range of nat8
+    let failed = check(&arg(slow(value(LinkValue::Integer(256))), vec![]));
+    assert!(failed.is_err_and(|e| e.contains("args[0].kind.slow: 256 is out of range of nat8")));
+    // type of refer
+    let failed = check(&arg(slow(refer(3)), vec![]));
+    assert!(failed.is_err_and(|e| e.contains("args[0].kind.slow: boolean can not be nat8")));
+    // principal
+    let mut failed = arg(slow(value(LinkValue::Integer(1))), vec![]);
+    if let CandidArgValue::Record(record) = &mut failed.args[0] {
+        record[0].value = value(LinkValue::Text("abc".into()));
+    }
+    assert!(check(&failed).is_err_and(|e| e.contains("args[0].owner: abc is not principal")));
+    // required field
+    if let CandidArgValue::Record(record) = &mut failed.args[0] {
+        record.remove(0);
+    }
+    assert!(check(&failed).is_err_and(|e| e.contains("args[0].owner: field is required")));
+    // unknown field and tag
+    let failed = check(&arg(
+        slow(value(LinkValue::Integer(1))),
+        vec![field("other", CandidArgValue::Null)],
+    ));
+    assert!(failed.is_err_and(|e| e.contains("unknown field other")));
+    let failed = check(&arg(
+        CandidArgVariant {
+            tag: "other".into(),
+            value: None,
+        },
+        vec![],
+    ));
+    assert!(failed.is_err_and(|e| e.contains("unknown tag other")));
+    // the value of tag
+    let failed = check(&arg(
+        CandidArgVariant {
+            tag: "slow".into(),
+            value: None,
+        },
+        vec![],
+    ));
+    assert!(failed.is_err_and(|e| e.contains("args[0].kind.slow: value is required")));
+    // wrong structure
+    let failed = check(&arg(slow(CandidArgValue::Vec(vec![])), vec![]));
+    assert!(failed.is_err_and(|e| e.contains("args[0].kind.slow: vec can not be nat8")));
+    // too many args
+    let mut failed = arg(slow(value(LinkValue::Integer(1))), vec![]);
+    failed.args.extend([CandidArgValue::Null, CandidArgValue::Null]);
+    assert!(check(&failed).is_err_and(|e| e.contains("api has 2 args, but found 3")));
+}
+
+#[derive(CandidType, Deserialize, Debug, PartialEq)]
+enum Kind {
+    #[serde(rename = "fast")]
+    Fast,
+    #[serde(rename = "slow")]
+    Slow(u8),
+}
+
+#[derive(CandidType, Deserialize, Debug, PartialEq)]
+struct Transfer {
+    owner: Principal,
+    amount: ::candid::Nat,
+    memo: Option<String>,
+    fee: Vec<u64>,
+    kind: Kind,
+}
+
+#[test]
+fn test_to_idl_args() {
+    let args = args();
+    let arg = IcCallArgCandid {
+        args: vec![
+            CandidArgValue::Record(vec![
+                field("owner", value(LinkValue::Text(OWNER.into()))),
+                field("amount", refer(1)),
+                field("fee", refer(2)),
+                field("kind", refer(3)),
+            ]),
+            CandidArgValue::Opt(Some(Box::new(value(LinkValue::Integer(7))))),
+        ],
+    };
+
+    // missing value
+    let values = Values(HashMap::new());
+    assert_eq!(arg.to_idl_args(&args, &values), Ok(None));
+
+    let values = Values(HashMap::from([
+        (1.into(), LinkValue::Text("123456789012345678901234567890".into())),
+        (
+            2.into(),
+            LinkValue::Array(ArrayLinkValue {
+                ty: LinkType::Integer,
+                values: vec![LinkValue::Integer(1), LinkValue::Integer(2)],
+            }),
+        ),
+        (3.into(), LinkValue::Text("fast".into())),
+    ]));
+    #[allow(clippy::unwrap_used)] // ? checked
+    let bytes = arg.to_idl_args(&args, &values).unwrap().unwrap().to_bytes().unwrap();
+    #[allow(clippy::unwrap_used)] // ? checked
+    let (transfer, speed) = decode_args::<(Transfer, Option<u32>)>(&bytes).unwrap();
+    assert_eq!(
+        transfer,
+        Transfer {
+            #[allow(clippy::unwrap_used)] // ? checked
+            owner: Principal::from_text(OWNER).unwrap(),
+            #[allow(clippy::unwrap_used)] // ? checked
+            amount: "123456789012345678901234567890".parse().unwrap(),
+            memo: None,
+            fee: vec![1, 2],
+            kind: Kind::Fast,
+        }
+    );
+    assert_eq!(speed, Some(7));
+
+    // the value is checked when running
+    let values = Values(HashMap::from([
+        (1.into(), LinkValue::Integer(-1)),
+        (
+            2.into(),
+            LinkValue::Array(ArrayLinkValue {
+                ty: LinkType::Integer,
+                values: vec![],
+            }),
+        ),
+        (3.into(), LinkValue::Text("fast".into())),
+    ]));
+    assert_eq!(
+        arg.to_idl_args(&args, &values).map(|_| ()),
+        Err("args[0].amount: -1 is out of range of nat".into())
+    );
+}
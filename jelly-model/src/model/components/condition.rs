@@ -2,7 +2,9 @@ use std::collections::HashSet;
 
 use serde::{Deserialize, Serialize};
 
-use super::{AllEndpoints, ComponentId, Endpoint, InputValue, LinkError, LinkType, ObjectSubitem, ReferValue};
+use super::{
+    AllEndpoints, ComponentId, Endpoint, InputValue, LinkError, LinkType, LinkValue, ObjectSubitem, ReferValue,
+};
 
 /// text
 pub mod text;
@@ -19,12 +21,30 @@ pub mod array;
 /// object
 pub mod object;
 
+/// test
+#[cfg(test)]
+mod test;
+
 use array::ConditionArrayCompare;
 use bool::ConditionBoolCompare;
 use number::ConditionNumberCompare;
 use object::ConditionObjectCompare;
 use text::ConditionTextCompare;
 
+/// The values of endpoints when condition is evaluated
+pub trait ConditionValues {
+    /// Find the value of refer, None if missing
+    fn refer_value(&self, refer: &ReferValue) -> Option<LinkValue>;
+
+    /// Find the value of input, None if missing
+    fn input_value(&self, input: &InputValue) -> Option<LinkValue> {
+        match input {
+            InputValue::Const(value) => Some(value.clone()),
+            InputValue::Refer(refer) => self.refer_value(refer),
+        }
+    }
+}
+
 /// condition
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct ComponentCondition {
@@ -152,6 +172,21 @@ impl ComponentCondition {
             metadata: ConditionMetadata { conditions },
         })
     }
+
+    /// Evaluate the conditions, return the index of output
+    /// The first satisfied condition is output, the last index is ELSE
+    /// None if no condition is satisfied and any value is missing, same as the runtime of typescript
+    pub fn evaluate<V: ConditionValues>(&self, values: &V) -> Result<Option<u32>, LinkError> {
+        let mut missing = false;
+        for (index, condition) in self.metadata.conditions.iter().enumerate() {
+            match condition.evaluate(values, self.id)? {
+                Some(true) => return Ok(Some(index as u32)),
+                Some(false) => {}
+                None => missing = true,
+            }
+        }
+        Ok((!missing).then_some(self.metadata.conditions.len() as u32))
+    }
 }
 
 impl Condition {
@@ -225,6 +260,42 @@ impl Condition {
             }
         }
     }
+
+    /// Evaluate the condition, None if any value is missing
+    pub fn evaluate<V: ConditionValues>(&self, values: &V, from: ComponentId) -> Result<Option<bool>, LinkError> {
+        match self {
+            Condition::None => Ok(Some(true)),
+            Condition::Required(item) => item.evaluate(values, from),
+            Condition::Deny(item) => Ok(item.evaluate(values, from)?.map(|r| !r)),
+            Condition::And(items) => {
+                for item in items {
+                    match item.evaluate(values, from)? {
+                        Some(true) => {}
+                        r => return Ok(r),
+                    }
+                }
+                Ok(Some(true))
+            }
+            Condition::Or(items) => {
+                for item in items {
+                    match item.evaluate(values, from)? {
+                        Some(false) => {}
+                        r => return Ok(r),
+                    }
+                }
+                Ok(Some(false))
+            }
+            Condition::Not(items) => {
+                for item in items {
+                    match item.evaluate(values, from)? {
+                        Some(false) => {}
+                        r => return Ok(r.map(|_| false)),
+                    }
+                }
+                Ok(Some(true))
+            }
+        }
+    }
 }
 
 impl ConditionItem {
@@ -241,7 +312,36 @@ impl ConditionItem {
     pub fn check(&self, endpoints: &AllEndpoints<'_>, from: ComponentId) -> Result<Self, LinkError> {
         let value = endpoints.check_refer_value(&self.value, from)?;
 
-        let matches = match (value.as_ref(), &self.matches) {
+        let matches = self.matches.check(value.as_ref(), endpoints, from)?;
+
+        Ok(Self {
+            value: self.value.clone(),
+            matches,
+        })
+    }
+
+    /// Evaluate the condition item, None if any value is missing
+    pub fn evaluate<V: ConditionValues>(&self, values: &V, from: ComponentId) -> Result<Option<bool>, LinkError> {
+        let value = values.refer_value(&self.value);
+        self.matches.evaluate(value.as_ref(), values, from)
+    }
+}
+
+impl ConditionMatches {
+    fn is_nullable(&self) -> bool {
+        match self {
+            ConditionMatches::Text(compare) => compare.is_nullable(),
+            ConditionMatches::Bool(compare) => compare.is_nullable(),
+            ConditionMatches::Integer(compare) => compare.is_nullable(),
+            ConditionMatches::Number(compare) => compare.is_nullable(),
+            ConditionMatches::Array(compare) => compare.is_nullable(),
+            ConditionMatches::Object(compare) => compare.is_nullable(),
+        }
+    }
+
+    /// Check the comparison by the type of left value
+    pub fn check(&self, ty: &LinkType, endpoints: &AllEndpoints<'_>, from: ComponentId) -> Result<Self, LinkError> {
+        let matches = match (ty, self) {
             (LinkType::Text, ConditionMatches::Text(compare)) => {
                 ConditionMatches::Text(compare.check(endpoints, from)?)
             }
@@ -266,23 +366,57 @@ impl ConditionItem {
                 ))
             }
         };
+        Ok(matches)
+    }
 
-        Ok(Self {
-            value: self.value.clone(),
-            matches,
-        })
+    /// Evaluate the left value, None if any value is missing
+    pub fn evaluate<V: ConditionValues>(
+        &self,
+        value: Option<&LinkValue>,
+        values: &V,
+        from: ComponentId,
+    ) -> Result<Option<bool>, LinkError> {
+        match self {
+            ConditionMatches::Text(compare) => compare.evaluate(value, values, from),
+            ConditionMatches::Bool(compare) => Ok(compare.evaluate(value, values)),
+            ConditionMatches::Integer(compare) => Ok(compare.evaluate(value, values)),
+            ConditionMatches::Number(compare) => Ok(compare.evaluate(value, values)),
+            ConditionMatches::Array(compare) => compare.evaluate(value, values, from),
+            ConditionMatches::Object(compare) => Ok(compare.evaluate(value, values)),
+        }
     }
 }
 
-impl ConditionMatches {
-    fn is_nullable(&self) -> bool {
-        match self {
-            ConditionMatches::Text(compare) => compare.is_nullable(),
-            ConditionMatches::Bool(compare) => compare.is_nullable(),
-            ConditionMatches::Integer(compare) => compare.is_nullable(),
-            ConditionMatches::Number(compare) => compare.is_nullable(),
-            ConditionMatches::Array(compare) => compare.is_nullable(),
-            ConditionMatches::Object(compare) => compare.is_nullable(),
+/// Whether the values are same, the type of array is ignored and the keys of object are unordered
+pub(super) fn same(a: &LinkValue, b: &LinkValue) -> bool {
+    match (a, b) {
+        (LinkValue::Text(a), LinkValue::Text(b)) => a == b,
+        (LinkValue::Bool(a), LinkValue::Bool(b)) => a == b,
+        (LinkValue::Integer(a), LinkValue::Integer(b)) => a == b,
+        (LinkValue::Number(a), LinkValue::Number(b)) => a == b,
+        (LinkValue::Integer(a), LinkValue::Number(b)) | (LinkValue::Number(b), LinkValue::Integer(a)) => {
+            *a as f64 == *b // ? Both are number in js
+        }
+        (LinkValue::Array(a), LinkValue::Array(b)) => {
+            a.values.len() == b.values.len() && a.values.iter().zip(b.values.iter()).all(|(a, b)| same(a, b))
         }
+        (LinkValue::Object(a), LinkValue::Object(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|a| b.iter().any(|b| a.key == b.key && same(&a.value, &b.value)))
+        }
+        _ => false,
+    }
+}
+
+/// Compare the length, None if the length is missing
+pub(super) fn compare_length(
+    length: usize,
+    expected: Option<LinkValue>,
+    compare: impl FnOnce(i64, i64) -> bool,
+) -> Option<bool> {
+    match expected {
+        Some(LinkValue::Integer(expected)) => Some(compare(length as i64, expected)),
+        _ => None,
     }
 }
@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 
-use super::{AllEndpoints, ComponentId, InputValue, LinkError, LinkType};
+use super::{
+    AllEndpoints, ComponentId, ConditionMatches, ConditionValues, InputValue, LinkError, LinkType, LinkValue,
+    compare_length, same,
+};
 
 /// Comparison of array
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -41,6 +44,12 @@ pub enum ConditionArrayCompare {
     /// Less than equal
     #[serde(rename = "length_less_equal")]
     LengthLessEqual(InputValue),
+    /// Any item is matched
+    #[serde(rename = "any")]
+    Any(Box<ConditionMatches>), // Sub -type
+    /// All items are matched
+    #[serde(rename = "all")]
+    All(Box<ConditionMatches>), // Sub -type
 }
 
 impl ConditionArrayCompare {
@@ -58,6 +67,8 @@ impl ConditionArrayCompare {
             Self::LengthGreaterEqual(_) => false,
             Self::LengthLess(_) => false,
             Self::LengthLessEqual(_) => false,
+            Self::Any(_) => false,
+            Self::All(_) => false,
         }
     }
 
@@ -90,7 +101,63 @@ impl ConditionArrayCompare {
                     ));
                 }
             }
+            ConditionArrayCompare::Any(matches) => {
+                return Ok(Self::Any(Box::new(matches.check(sub, endpoints, from)?)));
+            }
+            ConditionArrayCompare::All(matches) => {
+                return Ok(Self::All(Box::new(matches.check(sub, endpoints, from)?)));
+            }
         }
         Ok(self.clone())
     }
+
+    /// Evaluate the array, None if any value is missing
+    pub fn evaluate<V: ConditionValues>(
+        &self,
+        value: Option<&LinkValue>,
+        values: &V,
+        from: ComponentId,
+    ) -> Result<Option<bool>, LinkError> {
+        let (array, left) = match (self, value) {
+            (Self::Null, value) => return Ok(Some(value.is_none())),
+            (Self::NotNull, value) => return Ok(Some(value.is_some())),
+            (_, Some(array @ LinkValue::Array(left))) => (array, &left.values),
+            _ => return Ok(None),
+        };
+        let contains = |right: LinkValue| left.iter().any(|item| same(item, &right));
+        let result = match self {
+            Self::Null | Self::NotNull => None,
+            Self::Equal(value) => values.input_value(value).map(|right| same(array, &right)),
+            Self::NotEqual(value) => values.input_value(value).map(|right| !same(array, &right)),
+            Self::Contains(value) => values.input_value(value).map(contains),
+            Self::NotContains(value) => values.input_value(value).map(|right| !contains(right)),
+            Self::LengthEqual(value) => compare_length(left.len(), values.input_value(value), |l, r| l == r),
+            Self::LengthNotEqual(value) => compare_length(left.len(), values.input_value(value), |l, r| l != r),
+            Self::LengthGreater(value) => compare_length(left.len(), values.input_value(value), |l, r| l > r),
+            Self::LengthGreaterEqual(value) => compare_length(left.len(), values.input_value(value), |l, r| l >= r),
+            Self::LengthLess(value) => compare_length(left.len(), values.input_value(value), |l, r| l < r),
+            Self::LengthLessEqual(value) => compare_length(left.len(), values.input_value(value), |l, r| l <= r),
+            Self::Any(matches) => {
+                for item in left {
+                    match matches.evaluate(Some(item), values, from)? {
+                        Some(true) => return Ok(Some(true)),
+                        Some(false) => {}
+                        None => return Ok(None),
+                    }
+                }
+                Some(false)
+            }
+            Self::All(matches) => {
+                for item in left {
+                    match matches.evaluate(Some(item), values, from)? {
+                        Some(true) => {}
+                        Some(false) => return Ok(Some(false)),
+                        None => return Ok(None),
+                    }
+                }
+                Some(true)
+            }
+        };
+        Ok(result)
+    }
 }
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use super::{AllEndpoints, ComponentId, InputValue, LinkError};
+use super::{AllEndpoints, ComponentId, ConditionValues, InputValue, LinkError, LinkValue};
 
 /// Boer's comparative way
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -51,4 +51,25 @@ impl ConditionBoolCompare {
         }
         Ok(self.clone())
     }
+
+    /// Evaluate the bool, None if any value is missing
+    pub fn evaluate<V: ConditionValues>(&self, value: Option<&LinkValue>, values: &V) -> Option<bool> {
+        let left = match (self, value) {
+            (Self::Null, value) => return Some(value.is_none()),
+            (Self::NotNull, value) => return Some(value.is_some()),
+            (_, Some(LinkValue::Bool(left))) => *left,
+            _ => return None,
+        };
+        let bool = |input: &InputValue| match values.input_value(input) {
+            Some(LinkValue::Bool(bool)) => Some(bool),
+            _ => None,
+        };
+        match self {
+            Self::Null | Self::NotNull => None,
+            Self::Equal(value) => bool(value).map(|right| left == right),
+            Self::NotEqual(value) => bool(value).map(|right| left != right),
+            Self::IsTrue => Some(left),
+            Self::IsFalse => Some(!left),
+        }
+    }
 }
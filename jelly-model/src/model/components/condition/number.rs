@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use super::{AllEndpoints, ComponentId, InputValue, LinkError};
+use super::{AllEndpoints, ComponentId, ConditionValues, InputValue, LinkError, LinkValue};
 
 /// Number comparison
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -29,6 +29,27 @@ pub enum ConditionNumberCompare {
     /// Less than equal
     #[serde(rename = "less_equal")]
     LessEqual(InputValue),
+    /// Between min and max
+    #[serde(rename = "between")]
+    Between(ConditionRange),
+    /// Not between min and max
+    #[serde(rename = "not_between")]
+    NotBetween(ConditionRange),
+    /// In the set
+    #[serde(rename = "in_set")]
+    InSet(Vec<InputValue>),
+    /// Not in the set
+    #[serde(rename = "not_in_set")]
+    NotInSet(Vec<InputValue>),
+}
+
+/// Range of number, both ends are included
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ConditionRange {
+    /// min value
+    pub min: InputValue,
+    /// max value
+    pub max: InputValue,
 }
 
 impl ConditionNumberCompare {
@@ -42,11 +63,28 @@ impl ConditionNumberCompare {
             Self::GreaterEqual(_) => false,
             Self::Less(_) => false,
             Self::LessEqual(_) => false,
+            Self::Between(_) => false,
+            Self::NotBetween(_) => false,
+            Self::InSet(_) => false,
+            Self::NotInSet(_) => false,
         }
     }
 
     /// check
     pub fn check(&self, endpoints: &AllEndpoints<'_>, is_integer: bool, from: ComponentId) -> Result<Self, LinkError> {
+        let check_value = |value: &InputValue| {
+            let value = endpoints.check_input_value(value, from)?;
+            if is_integer {
+                if !value.is_integer() {
+                    return Err(LinkError::InvalidCondition(
+                        (from, "value is not integer".into()).into(),
+                    ));
+                }
+            } else if !value.is_number() {
+                return Err(LinkError::InvalidCondition((from, "value is not number".into()).into()));
+            }
+            Ok(())
+        };
         match self {
             ConditionNumberCompare::Null | ConditionNumberCompare::NotNull => {}
             ConditionNumberCompare::Equal(value)
@@ -54,19 +92,97 @@ impl ConditionNumberCompare {
             | ConditionNumberCompare::Greater(value)
             | ConditionNumberCompare::GreaterEqual(value)
             | ConditionNumberCompare::Less(value)
-            | ConditionNumberCompare::LessEqual(value) => {
-                let value = endpoints.check_input_value(value, from)?;
-                if is_integer {
-                    if !value.is_integer() {
-                        return Err(LinkError::InvalidCondition(
-                            (from, "value is not integer".into()).into(),
-                        ));
+            | ConditionNumberCompare::LessEqual(value) => check_value(value)?,
+            ConditionNumberCompare::Between(range) | ConditionNumberCompare::NotBetween(range) => {
+                check_value(&range.min)?;
+                check_value(&range.max)?;
+                // ? Only the constant range can be checked
+                let reversed = match (&range.min, &range.max) {
+                    (InputValue::Const(LinkValue::Integer(min)), InputValue::Const(LinkValue::Integer(max))) => {
+                        max < min
                     }
-                } else if !value.is_number() {
-                    return Err(LinkError::InvalidCondition((from, "value is not number".into()).into()));
+                    (InputValue::Const(LinkValue::Number(min)), InputValue::Const(LinkValue::Number(max))) => max < min,
+                    _ => false,
+                };
+                if reversed {
+                    return Err(LinkError::InvalidCondition(
+                        (from, "min is greater than max".into()).into(),
+                    ));
+                }
+            }
+            ConditionNumberCompare::InSet(set) | ConditionNumberCompare::NotInSet(set) => {
+                if set.is_empty() {
+                    return Err(LinkError::InvalidCondition((from, "set is empty".into()).into()));
+                }
+                for value in set {
+                    check_value(value)?;
                 }
             }
         }
         Ok(self.clone())
     }
+
+    /// Evaluate the integer or number, None if any value is missing
+    pub fn evaluate<V: ConditionValues>(&self, value: Option<&LinkValue>, values: &V) -> Option<bool> {
+        let right = match self {
+            Self::Null => return Some(value.is_none()),
+            Self::NotNull => return Some(value.is_some()),
+            Self::Between(range) => return Self::between(value?, range, values),
+            Self::NotBetween(range) => return Self::between(value?, range, values).map(|r| !r),
+            Self::InSet(set) => return Self::in_set(value?, set, values),
+            Self::NotInSet(set) => return Self::in_set(value?, set, values).map(|r| !r),
+            Self::Equal(right)
+            | Self::NotEqual(right)
+            | Self::Greater(right)
+            | Self::GreaterEqual(right)
+            | Self::Less(right)
+            | Self::LessEqual(right) => values.input_value(right),
+        };
+        match (value?, right?) {
+            (LinkValue::Integer(left), LinkValue::Integer(right)) => Some(self.compare(left, &right)),
+            (LinkValue::Number(left), LinkValue::Number(right)) => Some(self.compare(left, &right)), // ? NaN is not comparable
+            _ => None,
+        }
+    }
+
+    fn compare<T: PartialOrd>(&self, left: &T, right: &T) -> bool {
+        match self {
+            Self::Null
+            | Self::NotNull
+            | Self::Between(_)
+            | Self::NotBetween(_)
+            | Self::InSet(_)
+            | Self::NotInSet(_) => false,
+            Self::Equal(_) => left == right,
+            Self::NotEqual(_) => left != right,
+            Self::Greater(_) => left > right,
+            Self::GreaterEqual(_) => left >= right,
+            Self::Less(_) => left < right,
+            Self::LessEqual(_) => left <= right,
+        }
+    }
+
+    fn between<V: ConditionValues>(value: &LinkValue, range: &ConditionRange, values: &V) -> Option<bool> {
+        match (value, values.input_value(&range.min)?, values.input_value(&range.max)?) {
+            (LinkValue::Integer(value), LinkValue::Integer(min), LinkValue::Integer(max)) => {
+                Some(min <= *value && *value <= max)
+            }
+            (LinkValue::Number(value), LinkValue::Number(min), LinkValue::Number(max)) => {
+                Some(min <= *value && *value <= max)
+            }
+            _ => None,
+        }
+    }
+
+    fn in_set<V: ConditionValues>(value: &LinkValue, set: &[InputValue], values: &V) -> Option<bool> {
+        let mut contains = false;
+        for right in set {
+            contains |= match (value, values.input_value(right)?) {
+                (LinkValue::Integer(value), LinkValue::Integer(right)) => *value == right,
+                (LinkValue::Number(value), LinkValue::Number(right)) => *value == right,
+                _ => return None,
+            };
+        }
+        Some(contains)
+    }
 }
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 
-use super::{AllEndpoints, ComponentId, InputValue, LinkType, ObjectSubitem, LinkError};
+use super::{
+    AllEndpoints, ComponentId, ConditionValues, InputValue, LinkError, LinkType, LinkValue, ObjectSubitem, same,
+};
 
 /// Comparison of objects
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -87,4 +89,32 @@ impl ConditionObjectCompare {
 
         Ok(self.clone())
     }
+
+    /// Evaluate the object, None if any value is missing
+    pub fn evaluate<V: ConditionValues>(&self, value: Option<&LinkValue>, values: &V) -> Option<bool> {
+        let (object, left) = match (self, value) {
+            (Self::Null, value) => return Some(value.is_none()),
+            (Self::NotNull, value) => return Some(value.is_some()),
+            (_, Some(object @ LinkValue::Object(left))) => (object, left),
+            _ => return None,
+        };
+        let contains_key = |input: &InputValue| match values.input_value(input) {
+            Some(LinkValue::Text(key)) => Some(left.iter().any(|item| item.key == key)),
+            _ => None,
+        };
+        let contains_value = |input: &InputValue| {
+            values
+                .input_value(input)
+                .map(|right| left.iter().any(|item| same(&item.value, &right)))
+        };
+        match self {
+            Self::Null | Self::NotNull => None,
+            Self::Equal(value) => values.input_value(value).map(|right| same(object, &right)),
+            Self::NotEqual(value) => values.input_value(value).map(|right| !same(object, &right)),
+            Self::ContainsKey(value) => contains_key(value),
+            Self::NotContainsKey(value) => contains_key(value).map(|r| !r),
+            Self::ContainsValue(value) => contains_value(value),
+            Self::NotContainsValue(value) => contains_value(value).map(|r| !r),
+        }
+    }
 }
@@ -0,0 +1,532 @@
+use std::collections::HashMap;
+
+use crate::model::common::{
+    identity::ComponentId,
+    lets::Endpoint,
+    refer::{InputValue, KeyRefer, ReferValue},
+    types::LinkType,
+    values::{ArrayLinkValue, LinkValue, ObjectSubitemValue},
+};
+
+use super::{
+    ComponentCondition, Condition, ConditionItem, ConditionMatches, ConditionMetadata, ConditionValues,
+    array::ConditionArrayCompare,
+    bool::ConditionBoolCompare,
+    number::{ConditionNumberCompare, ConditionRange},
+    object::ConditionObjectCompare,
+    text::ConditionTextCompare,
+};
+
+/// The outputs of components, index is ignored
+struct Values(HashMap<ComponentId, LinkValue>);
+
+impl ConditionValues for Values {
+    fn refer_value(&self, refer: &ReferValue) -> Option<LinkValue> {
+        let value = self.0.get(&refer.endpoint.id)?;
+        match &refer.refer {
+            Some(refer) => refer.get_value(value).cloned(),
+            None => Some(value.clone()),
+        }
+    }
+}
+
+fn values(values: Vec<(u32, LinkValue)>) -> Values {
+    Values(values.into_iter().map(|(id, value)| (id.into(), value)).collect())
+}
+
+fn refer(id: u32) -> ReferValue {
+    ReferValue {
+        endpoint: Endpoint {
+            id: id.into(),
+            index: None,
+        },
+        refer: None,
+    }
+}
+
+fn item(id: u32, matches: ConditionMatches) -> Condition {
+    Condition::Required(ConditionItem {
+        value: refer(id),
+        matches,
+    })
+}
+
+fn text(text: &str) -> InputValue {
+    InputValue::Const(LinkValue::Text(text.into()))
+}
+
+fn integer(integer: i64) -> InputValue {
+    InputValue::Const(LinkValue::Integer(integer))
+}
+
+fn array(values: &[i64]) -> LinkValue {
+    LinkValue::Array(ArrayLinkValue {
+        ty: LinkType::Integer,
+        values: values.iter().map(|v| LinkValue::Integer(*v)).collect(),
+    })
+}
+
+fn object(values: &[(&str, LinkValue)]) -> LinkValue {
+    LinkValue::Object(
+        values
+            .iter()
+            .map(|(key, value)| ObjectSubitemValue {
+                key: key.to_string(),
+                value: value.clone(),
+            })
+            .collect(),
+    )
+}
+
+fn evaluate(condition: Condition, values: &Values) -> Option<bool> {
+    #[allow(clippy::unwrap_used)] // ? checked
+    condition.evaluate(values, 0.into()).unwrap()
+}
+
+#[test]
+fn test_text() {
+    let values = values(vec![(1, LinkValue::Text("hello 世界😀".into()))]);
+    let text_item = |compare| item(1, ConditionMatches::Text(compare));
+
+    assert_eq!(evaluate(text_item(ConditionTextCompare::Null), &values), Some(false));
+    assert_eq!(evaluate(text_item(ConditionTextCompare::NotNull), &values), Some(true));
+    assert_eq!(
+        evaluate(item(2, ConditionMatches::Text(ConditionTextCompare::Null)), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(item(2, ConditionMatches::Text(ConditionTextCompare::NotNull)), &values),
+        Some(false)
+    );
+
+    assert_eq!(
+        evaluate(text_item(ConditionTextCompare::Equal(text("hello 世界😀"))), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(text_item(ConditionTextCompare::NotEqual(text("hello"))), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(text_item(ConditionTextCompare::Contains(text("o 世"))), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(text_item(ConditionTextCompare::Contains(text(""))), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(text_item(ConditionTextCompare::NotContains(text("x"))), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(text_item(ConditionTextCompare::StartsWith(text("hello"))), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(text_item(ConditionTextCompare::StartsWith(text("Hello"))), &values),
+        Some(false)
+    );
+    assert_eq!(
+        evaluate(text_item(ConditionTextCompare::NotStartsWith(text("世界"))), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(text_item(ConditionTextCompare::EndsWith(text("😀"))), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(text_item(ConditionTextCompare::NotEndsWith(text("😀"))), &values),
+        Some(false)
+    );
+
+    // The length is the length of js string, the emoji is 2
+    assert_eq!(
+        evaluate(text_item(ConditionTextCompare::LengthEqual(integer(10))), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(text_item(ConditionTextCompare::LengthNotEqual(integer(10))), &values),
+        Some(false)
+    );
+    assert_eq!(
+        evaluate(text_item(ConditionTextCompare::LengthGreater(integer(9))), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(
+            text_item(ConditionTextCompare::LengthGreaterEqual(integer(11))),
+            &values
+        ),
+        Some(false)
+    );
+    assert_eq!(
+        evaluate(text_item(ConditionTextCompare::LengthLess(integer(10))), &values),
+        Some(false)
+    );
+    assert_eq!(
+        evaluate(text_item(ConditionTextCompare::LengthLessEqual(integer(10))), &values),
+        Some(true)
+    );
+
+    assert_eq!(
+        evaluate(text_item(ConditionTextCompare::Regex(text("^hel+o"))), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(text_item(ConditionTextCompare::NotRegex(text("\\d"))), &values),
+        Some(true)
+    );
+    assert!(
+        text_item(ConditionTextCompare::Regex(text("(")))
+            .evaluate(&values, 0.into())
+            .is_err()
+    );
+
+    // The missing value can not be compared
+    assert_eq!(
+        evaluate(
+            item(2, ConditionMatches::Text(ConditionTextCompare::Equal(text("")))),
+            &values
+        ),
+        None
+    );
+    let missing = InputValue::Refer(refer(3));
+    assert_eq!(evaluate(text_item(ConditionTextCompare::Equal(missing)), &values), None);
+}
+
+#[test]
+fn test_bool_and_number() {
+    let values = values(vec![
+        (1, LinkValue::Bool(true)),
+        (2, LinkValue::Integer(5)),
+        (3, LinkValue::Number(1.5)),
+        (4, LinkValue::Number(f64::NAN)),
+    ]);
+
+    assert_eq!(
+        evaluate(item(1, ConditionMatches::Bool(ConditionBoolCompare::IsTrue)), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(item(1, ConditionMatches::Bool(ConditionBoolCompare::IsFalse)), &values),
+        Some(false)
+    );
+    let equal = ConditionBoolCompare::Equal(InputValue::Const(LinkValue::Bool(false)));
+    assert_eq!(evaluate(item(1, ConditionMatches::Bool(equal)), &values), Some(false));
+    assert_eq!(
+        evaluate(item(9, ConditionMatches::Bool(ConditionBoolCompare::IsFalse)), &values),
+        None
+    );
+
+    let integer_item = |compare| item(2, ConditionMatches::Integer(compare));
+    assert_eq!(
+        evaluate(integer_item(ConditionNumberCompare::Equal(integer(5))), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(integer_item(ConditionNumberCompare::NotEqual(integer(5))), &values),
+        Some(false)
+    );
+    assert_eq!(
+        evaluate(integer_item(ConditionNumberCompare::Greater(integer(5))), &values),
+        Some(false)
+    );
+    assert_eq!(
+        evaluate(integer_item(ConditionNumberCompare::GreaterEqual(integer(5))), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(integer_item(ConditionNumberCompare::Less(integer(6))), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(integer_item(ConditionNumberCompare::LessEqual(integer(4))), &values),
+        Some(false)
+    );
+
+    let number = |number: f64| InputValue::Const(LinkValue::Number(number));
+    let number_item = |id, compare| item(id, ConditionMatches::Number(compare));
+    assert_eq!(
+        evaluate(number_item(3, ConditionNumberCompare::Greater(number(1.0))), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(number_item(3, ConditionNumberCompare::Less(number(1.0))), &values),
+        Some(false)
+    );
+    // NaN is not equal to anything
+    assert_eq!(
+        evaluate(number_item(4, ConditionNumberCompare::Equal(number(f64::NAN))), &values),
+        Some(false)
+    );
+    assert_eq!(
+        evaluate(
+            number_item(4, ConditionNumberCompare::NotEqual(number(f64::NAN))),
+            &values
+        ),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(
+            number_item(4, ConditionNumberCompare::GreaterEqual(number(0.0))),
+            &values
+        ),
+        Some(false)
+    );
+}
+
+#[test]
+fn test_array_and_object() {
+    let values = values(vec![
+        (1, array(&[1, 2, 3])),
+        (2, object(&[("a", LinkValue::Integer(1)), ("b", array(&[]))])),
+        (3, array(&[])),
+    ]);
+
+    let array_item = |compare| item(1, ConditionMatches::Array(compare));
+    assert_eq!(
+        evaluate(array_item(ConditionArrayCompare::NotNull), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(
+            item(9, ConditionMatches::Array(ConditionArrayCompare::NotNull)),
+            &values
+        ),
+        Some(false)
+    );
+    let equal = InputValue::Const(array(&[1, 2, 3]));
+    assert_eq!(
+        evaluate(array_item(ConditionArrayCompare::Equal(equal)), &values),
+        Some(true)
+    );
+    let not_equal = InputValue::Const(array(&[3, 2, 1]));
+    assert_eq!(
+        evaluate(array_item(ConditionArrayCompare::NotEqual(not_equal)), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(array_item(ConditionArrayCompare::Contains(integer(2))), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(array_item(ConditionArrayCompare::NotContains(integer(4))), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(array_item(ConditionArrayCompare::LengthEqual(integer(3))), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(array_item(ConditionArrayCompare::LengthLess(integer(3))), &values),
+        Some(false)
+    );
+    let empty = |compare| item(3, ConditionMatches::Array(compare));
+    assert_eq!(
+        evaluate(empty(ConditionArrayCompare::Contains(integer(1))), &values),
+        Some(false)
+    );
+    assert_eq!(
+        evaluate(empty(ConditionArrayCompare::LengthEqual(integer(0))), &values),
+        Some(true)
+    );
+
+    let object_item = |compare| item(2, ConditionMatches::Object(compare));
+    // The keys of object are unordered and the subtype of empty array is ignored
+    let equal = InputValue::Const(object(&[
+        (
+            "b",
+            LinkValue::Array(ArrayLinkValue {
+                ty: LinkType::Text,
+                values: vec![],
+            }),
+        ),
+        ("a", LinkValue::Integer(1)),
+    ]));
+    assert_eq!(
+        evaluate(object_item(ConditionObjectCompare::Equal(equal)), &values),
+        Some(true)
+    );
+    let not_equal = InputValue::Const(object(&[("a", LinkValue::Integer(2)), ("b", array(&[]))]));
+    assert_eq!(
+        evaluate(object_item(ConditionObjectCompare::NotEqual(not_equal)), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(object_item(ConditionObjectCompare::ContainsKey(text("a"))), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(object_item(ConditionObjectCompare::NotContainsKey(text("c"))), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(object_item(ConditionObjectCompare::ContainsValue(integer(1))), &values),
+        Some(true)
+    );
+    let value = InputValue::Const(array(&[]));
+    assert_eq!(
+        evaluate(object_item(ConditionObjectCompare::NotContainsValue(value)), &values),
+        Some(false)
+    );
+
+    // refer the key of object
+    let condition = Condition::Required(ConditionItem {
+        value: ReferValue {
+            endpoint: Endpoint {
+                id: 2.into(),
+                index: None,
+            },
+            #[allow(clippy::unwrap_used)] // ? checked
+            refer: Some(serde_json::from_str::<KeyRefer>(r#"{"key":"a"}"#).unwrap()),
+        },
+        matches: ConditionMatches::Integer(ConditionNumberCompare::Equal(integer(1))),
+    });
+    assert_eq!(evaluate(condition, &values), Some(true));
+}
+
+#[test]
+fn test_range_set_and_items() {
+    let values = values(vec![
+        (1, LinkValue::Integer(5)),
+        (2, LinkValue::Number(f64::NAN)),
+        (3, array(&[1, 2, 3])),
+        (4, array(&[])),
+        (5, LinkValue::Integer(1)),
+    ]);
+    let range = |min: InputValue, max: InputValue| ConditionRange { min, max };
+
+    let integer_item = |compare| item(1, ConditionMatches::Integer(compare));
+    let between = ConditionNumberCompare::Between(range(integer(1), integer(5)));
+    assert_eq!(evaluate(integer_item(between), &values), Some(true));
+    let between = ConditionNumberCompare::Between(range(integer(6), integer(9)));
+    assert_eq!(evaluate(integer_item(between), &values), Some(false));
+    let not_between = ConditionNumberCompare::NotBetween(range(integer(6), integer(9)));
+    assert_eq!(evaluate(integer_item(not_between), &values), Some(true));
+    // refer the min value
+    let between = ConditionNumberCompare::Between(range(InputValue::Refer(refer(5)), integer(5)));
+    assert_eq!(evaluate(integer_item(between), &values), Some(true));
+    let between = ConditionNumberCompare::Between(range(InputValue::Refer(refer(9)), integer(5)));
+    assert_eq!(evaluate(integer_item(between), &values), None);
+    let in_set = ConditionNumberCompare::InSet(vec![integer(1), integer(5)]);
+    assert_eq!(evaluate(integer_item(in_set), &values), Some(true));
+    let not_in_set = ConditionNumberCompare::NotInSet(vec![integer(1), integer(2)]);
+    assert_eq!(evaluate(integer_item(not_in_set), &values), Some(true));
+
+    // NaN is not in any range
+    let number = |n: f64| InputValue::Const(LinkValue::Number(n));
+    let number_item = |compare| item(2, ConditionMatches::Number(compare));
+    let between = ConditionNumberCompare::Between(range(number(f64::MIN), number(f64::MAX)));
+    assert_eq!(evaluate(number_item(between), &values), Some(false));
+    let not_between = ConditionNumberCompare::NotBetween(range(number(f64::MIN), number(f64::MAX)));
+    assert_eq!(evaluate(number_item(not_between), &values), Some(true));
+
+    let greater = |n| Box::new(ConditionMatches::Integer(ConditionNumberCompare::Greater(integer(n))));
+    let array_item = |id, compare| item(id, ConditionMatches::Array(compare));
+    assert_eq!(
+        evaluate(array_item(3, ConditionArrayCompare::Any(greater(2))), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(array_item(3, ConditionArrayCompare::Any(greater(3))), &values),
+        Some(false)
+    );
+    assert_eq!(
+        evaluate(array_item(3, ConditionArrayCompare::All(greater(0))), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(array_item(3, ConditionArrayCompare::All(greater(1))), &values),
+        Some(false)
+    );
+    // The empty array
+    assert_eq!(
+        evaluate(array_item(4, ConditionArrayCompare::Any(greater(0))), &values),
+        Some(false)
+    );
+    assert_eq!(
+        evaluate(array_item(4, ConditionArrayCompare::All(greater(0))), &values),
+        Some(true)
+    );
+    // missing value of item comparison
+    let missing = Box::new(ConditionMatches::Integer(ConditionNumberCompare::Greater(
+        InputValue::Refer(refer(9)),
+    )));
+    assert_eq!(
+        evaluate(array_item(3, ConditionArrayCompare::Any(missing)), &values),
+        None
+    );
+}
+
+#[test]
+fn test_logic_and_index() {
+    let values = values(vec![(1, LinkValue::Integer(5))]);
+    let greater = |n| {
+        item(
+            1,
+            ConditionMatches::Integer(ConditionNumberCompare::Greater(integer(n))),
+        )
+    };
+    let missing = || {
+        item(
+            2,
+            ConditionMatches::Integer(ConditionNumberCompare::Greater(integer(0))),
+        )
+    };
+    let deny = |n| {
+        Condition::Deny(ConditionItem {
+            value: refer(1),
+            matches: ConditionMatches::Integer(ConditionNumberCompare::Greater(integer(n))),
+        })
+    };
+
+    assert_eq!(evaluate(Condition::None, &values), Some(true));
+    assert_eq!(evaluate(deny(10), &values), Some(true));
+    assert_eq!(evaluate(deny(1), &values), Some(false));
+    assert_eq!(
+        evaluate(Condition::And(vec![greater(1), greater(2)]), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(Condition::And(vec![greater(1), greater(9)]), &values),
+        Some(false)
+    );
+    assert_eq!(
+        evaluate(Condition::And(vec![greater(9), missing()]), &values),
+        Some(false)
+    ); // ? Short circuit
+    assert_eq!(evaluate(Condition::And(vec![missing(), greater(9)]), &values), None);
+    assert_eq!(
+        evaluate(Condition::Or(vec![greater(9), greater(1)]), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(Condition::Or(vec![greater(1), missing()]), &values),
+        Some(true)
+    );
+    assert_eq!(evaluate(Condition::Or(vec![greater(9), missing()]), &values), None);
+    assert_eq!(
+        evaluate(Condition::Not(vec![greater(9), greater(10)]), &values),
+        Some(true)
+    );
+    assert_eq!(
+        evaluate(Condition::Not(vec![greater(9), greater(1)]), &values),
+        Some(false)
+    );
+    assert_eq!(evaluate(Condition::Not(vec![missing()]), &values), None);
+
+    let component = |conditions| ComponentCondition {
+        id: 3.into(),
+        inlets: None,
+        metadata: ConditionMetadata { conditions },
+    };
+    let index = |conditions| {
+        #[allow(clippy::unwrap_used)] // ? checked
+        component(conditions).evaluate(&values).unwrap()
+    };
+    assert_eq!(index(vec![greater(9), greater(1), Condition::None]), Some(1));
+    assert_eq!(index(vec![greater(9), greater(8)]), Some(2)); // ? ELSE
+    assert_eq!(index(vec![missing(), greater(1)]), Some(1)); // ? The satisfied condition is first
+    assert_eq!(index(vec![missing(), greater(9)]), None);
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use super::{AllEndpoints, ComponentId, InputValue, LinkError};
+use super::{AllEndpoints, ComponentId, ConditionValues, InputValue, LinkError, LinkValue, compare_length};
 
 /// Comparison of string
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -96,14 +96,21 @@ impl ConditionTextCompare {
             | ConditionTextCompare::StartsWith(value)
             | ConditionTextCompare::NotStartsWith(value)
             | ConditionTextCompare::EndsWith(value)
-            | ConditionTextCompare::NotEndsWith(value)
-            | ConditionTextCompare::Regex(value)
-            | ConditionTextCompare::NotRegex(value) => {
+            | ConditionTextCompare::NotEndsWith(value) => {
                 let value = endpoints.check_input_value(value, from)?;
                 if !value.is_text() {
                     return Err(LinkError::InvalidCondition((from, "value is not text".into()).into()));
                 }
             }
+            ConditionTextCompare::Regex(value) | ConditionTextCompare::NotRegex(value) => {
+                let ty = endpoints.check_input_value(value, from)?;
+                if !ty.is_text() {
+                    return Err(LinkError::InvalidCondition((from, "value is not text".into()).into()));
+                }
+                if let InputValue::Const(LinkValue::Text(pattern)) = value {
+                    regex(pattern, from)?; // ? The constant pattern must be valid
+                }
+            }
             ConditionTextCompare::LengthEqual(value)
             | ConditionTextCompare::LengthNotEqual(value)
             | ConditionTextCompare::LengthGreater(value)
@@ -120,4 +127,56 @@ impl ConditionTextCompare {
         }
         Ok(self.clone())
     }
+
+    /// Evaluate the text, None if any value is missing
+    pub fn evaluate<V: ConditionValues>(
+        &self,
+        value: Option<&LinkValue>,
+        values: &V,
+        from: ComponentId,
+    ) -> Result<Option<bool>, LinkError> {
+        let text = |input: &InputValue| match values.input_value(input) {
+            Some(LinkValue::Text(text)) => Some(text),
+            _ => None,
+        };
+        let left = match (self, value) {
+            (Self::Null, value) => return Ok(Some(value.is_none())),
+            (Self::NotNull, value) => return Ok(Some(value.is_some())),
+            (_, Some(LinkValue::Text(left))) => left.as_str(),
+            _ => return Ok(None),
+        };
+        let length = left.encode_utf16().count(); // ? The length of js string
+
+        let result = match self {
+            Self::Null | Self::NotNull => None,
+            Self::Equal(value) => text(value).map(|right| left == right),
+            Self::NotEqual(value) => text(value).map(|right| left != right),
+            Self::Contains(value) => text(value).map(|right| left.contains(&right)),
+            Self::NotContains(value) => text(value).map(|right| !left.contains(&right)),
+            Self::StartsWith(value) => text(value).map(|right| left.starts_with(&right)),
+            Self::NotStartsWith(value) => text(value).map(|right| !left.starts_with(&right)),
+            Self::EndsWith(value) => text(value).map(|right| left.ends_with(&right)),
+            Self::NotEndsWith(value) => text(value).map(|right| !left.ends_with(&right)),
+            Self::LengthEqual(value) => compare_length(length, values.input_value(value), |l, r| l == r),
+            Self::LengthNotEqual(value) => compare_length(length, values.input_value(value), |l, r| l != r),
+            Self::LengthGreater(value) => compare_length(length, values.input_value(value), |l, r| l > r),
+            Self::LengthGreaterEqual(value) => compare_length(length, values.input_value(value), |l, r| l >= r),
+            Self::LengthLess(value) => compare_length(length, values.input_value(value), |l, r| l < r),
+            Self::LengthLessEqual(value) => compare_length(length, values.input_value(value), |l, r| l <= r),
+            Self::Regex(value) => match text(value) {
+                Some(right) => Some(regex(&right, from)?.is_match(left)),
+                None => None,
+            },
+            Self::NotRegex(value) => match text(value) {
+                Some(right) => Some(!regex(&right, from)?.is_match(left)),
+                None => None,
+            },
+        };
+        Ok(result)
+    }
+}
+
+fn regex(pattern: &str, from: ComponentId) -> Result<regex::Regex, LinkError> {
+    regex::Regex::new(pattern)
+        .map_err(|e| LinkError::InvalidCondition((from, format!("invalid regex {pattern}: {e}")).into()))
 }
@@ -1,33 +1,32 @@
-use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 
-use crate::model::common::{lets::Endpoint, refer::ReferValue};
+use serde::{Deserialize, Serialize};
 
-use super::ComponentId;
+use super::{AllEndpoints, ComponentId, Endpoint, InputValue, LinkComponent, LinkError, LinkType, ReferValue};
 
-/// Output component
+/// loop start
+/// The inlets may contain the LoopEnd/LoopContinue/LoopBreak of this loop, it is the back edge of loop body
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
-pub struct ComponentLoop {
+pub struct ComponentLoopStart {
     /// Id with each component
     pub id: ComponentId,
 
     /// Dependencies
     #[serde(skip_serializing_if = "crate::is_empty_option_vec")]
-    inlets: Option<Vec<Endpoint>>,
+    pub inlets: Option<Vec<Endpoint>>,
 
     /// metadata required for this component execution
-    metadata: LoopMetadata,
-    // Output type
-    // pub output: LinkType, // Loop output type
+    pub metadata: LoopStartMetadata,
+
+    /// Output type
+    pub output: LinkType, // The item of iteration, or the round of infinite loop
 }
 
-/// loop metadata
+/// loop start metadata
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
-pub struct LoopMetadata {
+pub struct LoopStartMetadata {
     /// Cycle
-    way: LoopWay,
-    // /// Cyclic content
-    // /// OUTPUT must be the only one, otherwise it will not be sure that the output type
-    // components: Vec<LoopComponent>,
+    pub way: LoopWay,
 }
 
 /// loop way
@@ -38,5 +37,183 @@ pub enum LoopWay {
     Iteration(ReferValue), // This reference must be an array
     /// unlimited
     #[serde(rename = "infinite")]
-    Infinite,
+    Infinite, // ! Must be stopped by LoopBreak
+}
+
+/// loop end
+/// Collect the value of each round, the output is array
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ComponentLoopEnd {
+    /// Id with each component
+    pub id: ComponentId,
+
+    /// Dependencies
+    #[serde(skip_serializing_if = "crate::is_empty_option_vec")]
+    pub inlets: Option<Vec<Endpoint>>,
+
+    /// metadata required for this component execution
+    pub metadata: LoopEndMetadata,
+
+    /// Output type
+    pub output: LinkType, // Must be array of value type
+}
+
+/// loop end metadata
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct LoopEndMetadata {
+    /// The loop start component
+    pub start: ComponentId,
+    /// The value of each round
+    pub value: InputValue,
+}
+
+/// loop continue
+/// Ignore the value of this round
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ComponentLoopContinue {
+    /// Id with each component
+    pub id: ComponentId,
+
+    /// Dependencies
+    #[serde(skip_serializing_if = "crate::is_empty_option_vec")]
+    pub inlets: Option<Vec<Endpoint>>,
+
+    /// metadata required for this component execution
+    pub metadata: LoopJumpMetadata,
+    // Output type // There is no output type
+}
+
+/// loop break
+/// Ignore the value of this round and subsequent rounds
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ComponentLoopBreak {
+    /// Id with each component
+    pub id: ComponentId,
+
+    /// Dependencies
+    #[serde(skip_serializing_if = "crate::is_empty_option_vec")]
+    pub inlets: Option<Vec<Endpoint>>,
+
+    /// metadata required for this component execution
+    pub metadata: LoopJumpMetadata,
+    // Output type // There is no output type
+}
+
+/// loop continue or break metadata
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct LoopJumpMetadata {
+    /// The loop start component
+    pub start: ComponentId,
+}
+
+/// The loop start must be introduced by the loop body
+fn check_loop_start(endpoints: &AllEndpoints<'_>, start: ComponentId, from: ComponentId) -> Result<(), LinkError> {
+    let endpoint = endpoints.find_endpoint(&Endpoint { id: start, index: None });
+    match endpoint.map(|e| e.component) {
+        Some(LinkComponent::LoopStart(_)) => Ok(()),
+        Some(_) => Err(LinkError::InvalidLoop((from, "start must be loop start".into()).into())),
+        None => Err(LinkError::InvalidLoop((from, "start is not introduced".into()).into())),
+    }
+}
+
+impl ComponentLoopStart {
+    /// Get the introduction point
+    pub fn get_inlets(&self) -> Option<&Vec<Endpoint>> {
+        self.inlets.as_ref()
+    }
+
+    /// check
+    pub fn check(&self, endpoints: &Option<AllEndpoints<'_>>) -> Result<Self, LinkError> {
+        self.output.check(self.id)?; // ? Check whether the output type is correct
+
+        let endpoints = endpoints.as_ref().map(Cow::Borrowed).unwrap_or_default();
+
+        // 1. check way
+        let output = match &self.metadata.way {
+            LoopWay::Iteration(refer) => match endpoints.check_refer_value(refer, self.id)?.as_ref() {
+                LinkType::Array(ty) => ty.as_ref().clone(),
+                _ => {
+                    return Err(LinkError::InvalidLoop(
+                        (self.id, "iteration data must be array".into()).into(),
+                    ));
+                }
+            },
+            LoopWay::Infinite => LinkType::Integer,
+        };
+
+        // 2. check output
+        if self.output != output {
+            return Err(LinkError::MismatchedOutput { from: self.id });
+        }
+
+        Ok(self.clone())
+    }
+}
+
+impl ComponentLoopEnd {
+    /// Get the introduction point
+    pub fn get_inlets(&self) -> Option<&Vec<Endpoint>> {
+        self.inlets.as_ref()
+    }
+
+    /// check
+    pub fn check(&self, endpoints: &Option<AllEndpoints<'_>>) -> Result<Self, LinkError> {
+        self.output.check(self.id)?; // ? Check whether the output type is correct
+
+        // 0 Check whether the reference is matched
+        let endpoints = endpoints
+            .as_ref()
+            .ok_or(LinkError::MismatchedInlets { from: self.id })?; // There must be reference
+
+        // 1. check start
+        check_loop_start(endpoints, self.metadata.start, self.id)?;
+
+        // 2. check output
+        let ty = endpoints.check_input_value(&self.metadata.value, self.id)?;
+        if self.output != LinkType::Array(Box::new(ty.into_owned())) {
+            return Err(LinkError::MismatchedOutput { from: self.id });
+        }
+
+        Ok(self.clone())
+    }
+}
+
+impl ComponentLoopContinue {
+    /// Get the introduction point
+    pub fn get_inlets(&self) -> Option<&Vec<Endpoint>> {
+        self.inlets.as_ref()
+    }
+
+    /// check
+    pub fn check(&self, endpoints: &Option<AllEndpoints<'_>>) -> Result<Self, LinkError> {
+        // 0 Check whether the reference is matched
+        let endpoints = endpoints
+            .as_ref()
+            .ok_or(LinkError::MismatchedInlets { from: self.id })?; // There must be reference
+
+        // 1. check start
+        check_loop_start(endpoints, self.metadata.start, self.id)?;
+
+        Ok(self.clone())
+    }
+}
+
+impl ComponentLoopBreak {
+    /// Get the introduction point
+    pub fn get_inlets(&self) -> Option<&Vec<Endpoint>> {
+        self.inlets.as_ref()
+    }
+
+    /// check
+    pub fn check(&self, endpoints: &Option<AllEndpoints<'_>>) -> Result<Self, LinkError> {
+        // 0 Check whether the reference is matched
+        let endpoints = endpoints
+            .as_ref()
+            .ok_or(LinkError::MismatchedInlets { from: self.id })?; // There must be reference
+
+        // 1. check start
+        check_loop_start(endpoints, self.metadata.start, self.id)?;
+
+        Ok(self.clone())
+    }
 }
@@ -43,6 +43,11 @@ impl ComponentOutput {
         self.inlets.as_ref()
     }
 
+    /// Get the output data
+    pub fn get_data(&self) -> Option<&Vec<CodeValue>> {
+        self.metadata.as_ref().and_then(|m| m.data.as_ref())
+    }
+
     /// check
     pub fn check(&self, endpoints: &Option<AllEndpoints<'_>>) -> Result<Self, LinkError> {
         self.output.check(self.id)?; // ? Check whether the output type is correct
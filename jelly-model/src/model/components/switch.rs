@@ -0,0 +1,316 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::{AllEndpoints, ComponentId, Endpoint, LinkError, LinkType, LinkValue, ReferValue};
+
+/// switch
+/// Match the value by the cases in order, every case has its output in order
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ComponentSwitch {
+    /// Id with each component
+    pub id: ComponentId,
+
+    /// Dependencies
+    #[serde(skip_serializing_if = "crate::is_empty_option_vec")]
+    pub inlets: Option<Vec<Endpoint>>,
+
+    /// metadata required for this component execution
+    pub metadata: SwitchMetadata,
+    // Output type // ? No need here
+    // pub output: LinkType, // ! The output of each case is BOOL type
+}
+
+/// switch metadata
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SwitchMetadata {
+    /// The matched value
+    pub value: ReferValue,
+
+    /// cases, at least one
+    pub cases: Vec<SwitchCase>,
+
+    /// The name of default output, it is the last output
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+}
+
+/// case of switch
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SwitchCase {
+    /// The name of case // ! unique, the output is linked by index
+    pub name: String,
+    /// pattern
+    pub pattern: SwitchPattern,
+}
+
+/// pattern of case
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum SwitchPattern {
+    /// Boolean
+    #[serde(rename = "bool")]
+    Bool(bool),
+    /// Any of the texts
+    #[serde(rename = "text")]
+    Text(Vec<String>),
+    /// Integer in the range
+    #[serde(rename = "integer")]
+    Integer(SwitchRange),
+    /// The text field of object is any of the values
+    #[serde(rename = "tag")]
+    Tag(SwitchTag),
+}
+
+/// Range of integer, both ends are included
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SwitchRange {
+    /// min value, None is unbounded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<i64>,
+    /// max value, None is unbounded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<i64>,
+}
+
+/// Tag of object
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SwitchTag {
+    /// The key of text field
+    pub key: String,
+    /// tag values
+    pub values: Vec<String>,
+}
+
+impl ComponentSwitch {
+    /// Get the introduction point
+    pub fn get_inlets(&self) -> Option<&Vec<Endpoint>> {
+        self.inlets.as_ref()
+    }
+
+    /// Calculate the output point of the component
+    /// Number of cases + 1 default if exists
+    pub fn count_outputs(&self) -> u32 {
+        self.metadata.cases.len() as u32 + self.metadata.default.is_some() as u32
+    }
+
+    /// check
+    pub fn check(&self, endpoints: &Option<AllEndpoints<'_>>) -> Result<Self, LinkError> {
+        // 0 Check whether the reference is matched
+        let endpoints = endpoints
+            .as_ref()
+            .ok_or(LinkError::MismatchedInlets { from: self.id })?; // There must be reference
+
+        let ty = endpoints.check_refer_value(&self.metadata.value, self.id)?;
+
+        // 1. The number of cases cannot be empty
+        if self.metadata.cases.is_empty() {
+            return Err(LinkError::InvalidSwitch(
+                (self.id, "cases can not be empty".into()).into(),
+            ));
+        }
+
+        // 2. The names of outputs must be unique
+        let mut names = HashSet::with_capacity(self.metadata.cases.len() + 1);
+        for name in self
+            .metadata
+            .cases
+            .iter()
+            .map(|case| &case.name)
+            .chain(self.metadata.default.iter())
+        {
+            if name.is_empty() {
+                return Err(LinkError::InvalidSwitch(
+                    (self.id, "name can not be empty".into()).into(),
+                ));
+            }
+            if !names.insert(name) {
+                return Err(LinkError::DuplicateSwitchCase {
+                    from: self.id,
+                    name: name.clone(),
+                });
+            }
+        }
+
+        // 3. Check every pattern, the covered values of previous cases are recorded
+        let mut covered = Covered::default();
+        for case in self.metadata.cases.iter() {
+            case.pattern.check(ty.as_ref(), self.id)?;
+            covered.push(case, self.id)?;
+        }
+
+        // 4. Check whether the cases are exhaustive
+        match (covered.missing(ty.as_ref()), &self.metadata.default) {
+            (None, Some(default)) => {
+                return Err(LinkError::UnreachableSwitchCase {
+                    from: self.id,
+                    name: default.clone(),
+                });
+            }
+            (Some(missing), None) if matches!(ty.as_ref(), LinkType::Bool) => {
+                return Err(LinkError::NonExhaustiveSwitch { from: self.id, missing });
+            } // ! Only the finite type must be exhaustive
+            _ => {}
+        }
+
+        Ok(self.clone())
+    }
+
+    /// Evaluate the value, return the index of output
+    /// None if the value is missing or no case is matched without default
+    pub fn evaluate(&self, value: Option<&LinkValue>) -> Option<u32> {
+        let value = value?;
+        if let Some(index) = self.metadata.cases.iter().position(|case| case.pattern.is_match(value)) {
+            return Some(index as u32);
+        }
+        self.metadata.default.as_ref().map(|_| self.metadata.cases.len() as u32)
+    }
+}
+
+impl SwitchPattern {
+    fn check(&self, ty: &LinkType, from: ComponentId) -> Result<(), LinkError> {
+        let invalid = |message: &str| Err(LinkError::InvalidSwitch((from, message.into()).into()));
+        match (ty, self) {
+            (LinkType::Bool, SwitchPattern::Bool(_)) => {}
+            (LinkType::Text, SwitchPattern::Text(texts)) => {
+                if texts.is_empty() {
+                    return invalid("texts can not be empty");
+                }
+                if let Some(text) = find_repeated(texts) {
+                    return invalid(&format!("text {text} is repeated"));
+                }
+            }
+            (LinkType::Integer, SwitchPattern::Integer(range)) => {
+                if let (Some(min), Some(max)) = (range.min, range.max) {
+                    if max < min {
+                        return invalid("min is greater than max");
+                    }
+                }
+            }
+            (LinkType::Object(items), SwitchPattern::Tag(tag)) => {
+                if !items.iter().any(|item| item.key == tag.key && item.ty.is_text()) {
+                    return invalid("tag must be text field of object");
+                }
+                if tag.values.is_empty() {
+                    return invalid("tag values can not be empty");
+                }
+                if let Some(value) = find_repeated(&tag.values) {
+                    return invalid(&format!("tag value {value} is repeated"));
+                }
+            }
+            _ => return invalid("pattern is not match"),
+        }
+        Ok(())
+    }
+
+    fn is_match(&self, value: &LinkValue) -> bool {
+        match (self, value) {
+            (SwitchPattern::Bool(bool), LinkValue::Bool(value)) => bool == value,
+            (SwitchPattern::Text(texts), LinkValue::Text(value)) => texts.contains(value),
+            (SwitchPattern::Integer(range), LinkValue::Integer(value)) => range.bounds().contains(value),
+            (SwitchPattern::Tag(tag), LinkValue::Object(items)) => items.iter().any(|item| {
+                item.key == tag.key && matches!(&item.value, LinkValue::Text(value) if tag.values.contains(value))
+            }),
+            _ => false,
+        }
+    }
+}
+
+impl SwitchRange {
+    fn bounds(&self) -> std::ops::RangeInclusive<i64> {
+        self.min.unwrap_or(i64::MIN)..=self.max.unwrap_or(i64::MAX)
+    }
+}
+
+/// The values covered by previous cases
+#[derive(Default)]
+struct Covered<'a> {
+    bools: HashSet<bool>,
+    texts: HashSet<(Option<&'a str>, &'a str)>, // ? The key of tag and the text
+    ranges: Vec<(i64, i64)>,
+}
+
+impl<'a> Covered<'a> {
+    /// Record the values of case, the case must match something new
+    fn push(&mut self, case: &'a SwitchCase, from: ComponentId) -> Result<(), LinkError> {
+        let unreachable = || LinkError::UnreachableSwitchCase {
+            from,
+            name: case.name.clone(),
+        };
+        let duplicate = || LinkError::DuplicateSwitchCase {
+            from,
+            name: case.name.clone(),
+        };
+        match &case.pattern {
+            SwitchPattern::Bool(bool) => {
+                if !self.bools.insert(*bool) {
+                    return Err(unreachable());
+                }
+            }
+            SwitchPattern::Text(texts) => self
+                .push_texts(None, texts)
+                .map_err(|all| if all { unreachable() } else { duplicate() })?,
+            SwitchPattern::Tag(tag) => self
+                .push_texts(Some(&tag.key), &tag.values)
+                .map_err(|all| if all { unreachable() } else { duplicate() })?,
+            SwitchPattern::Integer(range) => {
+                let (min, max) = (*range.bounds().start(), *range.bounds().end());
+                if is_covered(&self.ranges, min, max) {
+                    return Err(unreachable());
+                }
+                self.ranges.push((min, max)); // ? The overlapped range is allowed
+            }
+        }
+        Ok(())
+    }
+
+    /// Err(true) if all texts are covered, Err(false) if any text is covered by previous cases
+    fn push_texts(&mut self, key: Option<&'a str>, texts: &'a [String]) -> Result<(), bool> {
+        let mut repeated = 0;
+        for text in texts {
+            if !self.texts.insert((key, text)) {
+                repeated += 1;
+            }
+        }
+        match repeated {
+            0 => Ok(()),
+            n => Err(n == texts.len()),
+        }
+    }
+
+    /// The value not covered of finite type, None if exhaustive
+    fn missing(&self, ty: &LinkType) -> Option<String> {
+        match ty {
+            LinkType::Bool => [true, false]
+                .into_iter()
+                .find(|bool| !self.bools.contains(bool))
+                .map(|bool| bool.to_string()),
+            LinkType::Integer if is_covered(&self.ranges, i64::MIN, i64::MAX) => None,
+            _ => Some("_".into()), // ? Infinite type is never exhaustive
+        }
+    }
+}
+
+/// The first text repeated in the same case
+fn find_repeated(texts: &[String]) -> Option<&String> {
+    let mut found = HashSet::with_capacity(texts.len());
+    texts.iter().find(|text| !found.insert(*text))
+}
+
+/// Whether the range is covered by the ranges
+fn is_covered(ranges: &[(i64, i64)], min: i64, max: i64) -> bool {
+    let mut ranges = ranges.to_vec();
+    ranges.sort();
+    let mut current = min;
+    for (start, end) in ranges {
+        if current < start {
+            break;
+        }
+        if current <= end {
+            if max <= end {
+                return true;
+            }
+            current = end + 1; // ? end < max <= i64::MAX
+        }
+    }
+    false
+}
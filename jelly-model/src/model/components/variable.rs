@@ -0,0 +1,162 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::check::is_valid_variant_name;
+
+use super::{
+    AllEndpoints, ComponentId, ComponentVariableRequired, Endpoint, InputValue, LinkComponent, LinkError, LinkType,
+    LinkValue,
+};
+
+/// variable value
+/// Output the current value of variable, the initial value is default
+/// ! The reader sees the assigned value only if it takes the assign as an inlet,
+/// ! otherwise it may see the value before the assign, which depends on the order of components
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ComponentVariableValue {
+    /// Id with each component
+    pub id: ComponentId,
+
+    /// Dependencies
+    #[serde(skip_serializing_if = "crate::is_empty_option_vec")]
+    pub inlets: Option<Vec<Endpoint>>,
+
+    /// metadata required for this component execution
+    pub metadata: VariableValueMetadata,
+
+    /// Output type
+    pub output: LinkType, // The declared type of variable
+}
+
+/// variable value metadata
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct VariableValueMetadata {
+    /// Variable name // ! Follow the variable name rules and unique
+    pub name: String,
+
+    /// default value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<LinkValue>,
+}
+
+/// variable assign
+/// Assign the value to the variable
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ComponentVariableAssign {
+    /// Id with each component
+    pub id: ComponentId,
+
+    /// Dependencies
+    #[serde(skip_serializing_if = "crate::is_empty_option_vec")]
+    pub inlets: Option<Vec<Endpoint>>,
+
+    /// metadata required for this component execution
+    pub metadata: VariableAssignMetadata,
+    // Output type // There is no output type
+}
+
+/// variable assign metadata
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct VariableAssignMetadata {
+    /// The variable value component
+    pub variable: ComponentId,
+    /// The assigned value, must be the type of variable
+    pub value: InputValue,
+}
+
+impl ComponentVariableValue {
+    /// Get the introduction point
+    pub fn get_inlets(&self) -> Option<&Vec<Endpoint>> {
+        self.inlets.as_ref()
+    }
+
+    /// Get the variable name
+    pub fn get_variable_name(&self) -> &String {
+        &self.metadata.name
+    }
+
+    /// check
+    pub fn check(&self, endpoints: &Option<AllEndpoints<'_>>) -> Result<Self, LinkError> {
+        self.output.check(self.id)?; // ? Check whether the output type is correct
+
+        // 1. Check the introduction variable
+        if !matches!(
+            (endpoints.as_ref(), self.inlets.as_ref()),
+            (Some(_), Some(_)) | (None, None)
+        ) {
+            return Err(LinkError::MismatchedInlets { from: self.id });
+        }
+
+        // 2. Check the validity of the name
+        if !is_valid_variant_name(&self.metadata.name) {
+            return Err(LinkError::InvalidVariantKey {
+                from: self.id,
+                key: self.metadata.name.clone(),
+            });
+        }
+
+        // 3. Check whether the default value matching
+        if let Some(default) = self.metadata.default.as_ref() {
+            if !self.output.is_match(default) {
+                return Err(LinkError::MismatchedVariableDefaultValue {
+                    from: self.id,
+                    output: self.output.clone(),
+                    value: default.clone(),
+                });
+            }
+        }
+
+        Ok(self.clone())
+    }
+
+    /// Get the necessary information
+    pub fn get_required(&self) -> ComponentVariableRequired {
+        ComponentVariableRequired {
+            id: self.id,
+            name: self.metadata.name.clone(),
+            output: self.output.clone(),
+        }
+    }
+}
+
+impl ComponentVariableAssign {
+    /// Get the introduction point
+    pub fn get_inlets(&self) -> Option<&Vec<Endpoint>> {
+        self.inlets.as_ref()
+    }
+
+    /// check
+    pub fn check(&self, endpoints: &Option<AllEndpoints<'_>>) -> Result<Self, LinkError> {
+        let endpoints = endpoints.as_ref().map(Cow::Borrowed).unwrap_or_default();
+
+        // 1. check variable
+        let variable = endpoints.find_endpoint(&Endpoint {
+            id: self.metadata.variable,
+            index: None,
+        });
+        let output = match variable.map(|e| e.component) {
+            Some(LinkComponent::VariableValue(variable)) => &variable.output,
+            Some(_) => {
+                return Err(LinkError::InvalidVariable(
+                    (self.id, "variable must be variable value".into()).into(),
+                ));
+            }
+            None => {
+                return Err(LinkError::InvalidVariable(
+                    (self.id, "variable is not introduced".into()).into(),
+                ));
+            }
+        };
+
+        // 2. check value
+        let ty = endpoints.check_input_value(&self.metadata.value, self.id)?;
+        if ty.as_ref() != output {
+            return Err(LinkError::InvalidVariable(
+                (self.id, "value is mismatched with variable".into()).into(),
+            ));
+        }
+
+        Ok(self.clone())
+    }
+}
@@ -0,0 +1,557 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::{
+    CombinedMetadata, LinkComponent,
+    common::{identity::ComponentId, lets::Endpoint, types::LinkType},
+};
+
+/// test
+#[cfg(test)]
+mod test;
+
+/// The keys compared separately
+const INLETS: &str = "inlets";
+const OUTPUT: &str = "output";
+
+/// Change of a value in the component
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ValueChange {
+    /// Json pointer in the component, such as /metadata/name
+    pub path: String,
+    /// The old value, None if added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<Value>,
+    /// The new value, None if removed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<Value>,
+}
+
+/// Change of component
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum ComponentChange {
+    /// The component is added at the index of new components
+    #[serde(rename = "added")]
+    Added {
+        /// index
+        index: usize,
+        /// The added component
+        component: LinkComponent,
+    },
+    /// The component is removed, or replaced by another kind
+    #[serde(rename = "removed")]
+    Removed {
+        /// The removed component
+        component: LinkComponent,
+    },
+    /// The inlets are changed
+    #[serde(rename = "rewired")]
+    Rewired {
+        /// The changed component
+        id: ComponentId,
+        /// The old inlets
+        #[serde(skip_serializing_if = "Option::is_none")]
+        from: Option<Vec<Endpoint>>,
+        /// The new inlets
+        #[serde(skip_serializing_if = "Option::is_none")]
+        to: Option<Vec<Endpoint>>,
+    },
+    /// The output type is changed
+    #[serde(rename = "output")]
+    Output {
+        /// The changed component
+        id: ComponentId,
+        /// The old type
+        from: LinkType,
+        /// The new type
+        to: LinkType,
+    },
+    /// The metadata is changed
+    #[serde(rename = "metadata")]
+    Metadata {
+        /// The changed component
+        id: ComponentId,
+        /// The changed values
+        changes: Vec<ValueChange>,
+    },
+}
+
+/// Kind of breaking change, the name is stable
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum BreakingKind {
+    /// The param is removed
+    #[serde(rename = "removed_param")]
+    RemovedParam,
+    /// The param without default value is added, or the default value is removed
+    #[serde(rename = "required_param")]
+    RequiredParam,
+    /// The identity is required
+    #[serde(rename = "added_identity")]
+    AddedIdentity,
+    /// The required identity is changed
+    #[serde(rename = "changed_identity")]
+    ChangedIdentity,
+    /// The output type of form is changed
+    #[serde(rename = "changed_form")]
+    ChangedForm,
+    /// The output type is changed or removed
+    #[serde(rename = "changed_output")]
+    ChangedOutput,
+}
+
+/// The change breaks the consumers of combined
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct BreakingChange {
+    /// kind
+    pub kind: BreakingKind,
+    /// The component of new combined, or the removed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub component: Option<ComponentId>,
+    /// message
+    pub message: String,
+}
+
+/// Semantic diff of components
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ComponentsDiff {
+    /// The removed first, then the others in order of new components
+    pub changes: Vec<ComponentChange>,
+    /// The order of new components, only if it can not be known by the indexes of added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<Vec<ComponentId>>,
+    /// The breaking changes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub breaking: Vec<BreakingChange>,
+}
+
+impl ComponentsDiff {
+    /// Whether nothing is changed
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty() && self.order.is_none()
+    }
+
+    /// Whether the consumers of combined are broken
+    pub fn is_breaking(&self) -> bool {
+        !self.breaking.is_empty()
+    }
+}
+
+/// Error of patch
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub enum PatchError {
+    /// Can not find the changed component
+    UnknownComponent {
+        /// The changed component
+        id: ComponentId,
+    },
+    /// The added component exists
+    DuplicateComponent {
+        /// The added component
+        id: ComponentId,
+    },
+    /// The current value is not the old value of change
+    Conflict {
+        /// The changed component
+        id: ComponentId,
+        /// Json pointer in the component
+        path: String,
+    },
+    /// The patched component is invalid
+    InvalidComponent {
+        /// The changed component
+        id: ComponentId,
+        /// error message
+        message: String,
+    },
+    /// The order is not the patched components
+    InvalidOrder,
+}
+
+impl core::fmt::Display for PatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PatchError::UnknownComponent { id } => write!(f, "unknown component {id}"),
+            PatchError::DuplicateComponent { id } => write!(f, "component {id} exists"),
+            PatchError::Conflict { id, path } => write!(f, "conflict at {path} of component {id}"),
+            PatchError::InvalidComponent { id, message } => write!(f, "component {id} is invalid: {message}"),
+            PatchError::InvalidOrder => write!(f, "invalid order"),
+        }
+    }
+}
+
+/// Diff the components of two versions
+pub fn diff(old: &[LinkComponent], new: &[LinkComponent]) -> ComponentsDiff {
+    let old_components: HashMap<ComponentId, &LinkComponent> = old.iter().map(|c| (c.id(), c)).collect();
+    let new_ids = new.iter().map(|c| c.id()).collect::<HashSet<_>>();
+
+    let mut changes = old
+        .iter()
+        .filter(|c| !new_ids.contains(&c.id()))
+        .map(|c| ComponentChange::Removed { component: c.clone() })
+        .collect::<Vec<_>>();
+    let mut removed = changes.len();
+    for (index, component) in new.iter().enumerate() {
+        let added = ComponentChange::Added {
+            index,
+            component: component.clone(),
+        };
+        let Some(old) = old_components.get(&component.id()) else {
+            changes.push(added);
+            continue;
+        };
+        match (split(old), split(component)) {
+            (Some((old_kind, old_inner)), Some((new_kind, new_inner))) if old_kind == new_kind => {
+                changes.extend(diff_component(component.id(), old_inner, new_inner));
+            }
+            _ => {
+                // ? Replaced by another kind
+                let component = (*old).clone();
+                changes.insert(removed, ComponentChange::Removed { component });
+                changes.push(added);
+                removed += 1;
+            }
+        }
+    }
+
+    // ? The kept components are in the old order, the added are inserted by index
+    let mut order = old
+        .iter()
+        .map(|c| c.id())
+        .filter(|id| {
+            !changes.iter().any(|c| match c {
+                ComponentChange::Removed { component } => component.id() == *id,
+                _ => false,
+            })
+        })
+        .collect::<Vec<_>>();
+    for change in &changes {
+        if let ComponentChange::Added { index, component } = change {
+            order.insert((*index).min(order.len()), component.id());
+        }
+    }
+    let new_order = new.iter().map(|c| c.id()).collect::<Vec<_>>();
+
+    ComponentsDiff {
+        changes,
+        order: (order != new_order).then_some(new_order),
+        breaking: find_breaking_changes(
+            CombinedMetadata::from_components(old, find_output(old)).as_ref(),
+            CombinedMetadata::from_components(new, find_output(new)).as_ref(),
+        ),
+    }
+}
+
+/// Apply the diff to the old components
+pub fn patch(components: &[LinkComponent], diff: &ComponentsDiff) -> Result<Vec<LinkComponent>, PatchError> {
+    let mut components = components.to_vec();
+    let position = |components: &[LinkComponent], id: ComponentId| {
+        components
+            .iter()
+            .position(|c| c.id() == id)
+            .ok_or(PatchError::UnknownComponent { id })
+    };
+
+    let mut added = Vec::new();
+    for change in &diff.changes {
+        match change {
+            ComponentChange::Added { index, component } => added.push((*index, component)),
+            ComponentChange::Removed { component } => {
+                let id = component.id();
+                let index = position(&components, id)?;
+                if components[index] != *component {
+                    return Err(PatchError::Conflict { id, path: "".into() });
+                }
+                components.remove(index);
+            }
+            ComponentChange::Rewired { id, from, to } => {
+                let index = position(&components, *id)?;
+                let changes = [value_change(INLETS, from, to)];
+                components[index] = patch_component(&components[index], &changes)?;
+            }
+            ComponentChange::Output { id, from, to } => {
+                let index = position(&components, *id)?;
+                let changes = [value_change(OUTPUT, &Some(from), &Some(to))];
+                components[index] = patch_component(&components[index], &changes)?;
+            }
+            ComponentChange::Metadata { id, changes } => {
+                let index = position(&components, *id)?;
+                components[index] = patch_component(&components[index], changes)?;
+            }
+        }
+    }
+
+    added.sort_by_key(|(index, _)| *index);
+    for (index, component) in added {
+        let id = component.id();
+        if components.iter().any(|c| c.id() == id) {
+            return Err(PatchError::DuplicateComponent { id });
+        }
+        components.insert(index.min(components.len()), component.clone());
+    }
+
+    if let Some(order) = &diff.order {
+        if order.len() != components.len() {
+            return Err(PatchError::InvalidOrder);
+        }
+        let mut ordered = Vec::with_capacity(components.len());
+        for id in order {
+            let index = position(&components, *id).map_err(|_| PatchError::InvalidOrder)?;
+            ordered.push(components.swap_remove(index));
+        }
+        components = ordered;
+    }
+
+    Ok(components)
+}
+
+/// Find the breaking changes by the metadata of two versions
+pub fn find_breaking_changes(old: Option<&CombinedMetadata>, new: Option<&CombinedMetadata>) -> Vec<BreakingChange> {
+    let mut breaking = Vec::new();
+    let mut push = |kind: BreakingKind, component: Option<ComponentId>, message: String| {
+        breaking.push(BreakingChange {
+            kind,
+            component,
+            message,
+        })
+    };
+
+    // params are referred by name
+    let old_params = old.and_then(|m| m.params.as_deref()).unwrap_or_default();
+    let new_params = new.and_then(|m| m.params.as_deref()).unwrap_or_default();
+    for param in old_params {
+        if !new_params.iter().any(|p| p.name == param.name) {
+            push(
+                BreakingKind::RemovedParam,
+                Some(param.id),
+                format!("param {} is removed", param.name),
+            );
+        }
+    }
+    for param in new_params {
+        let required = match old_params.iter().find(|p| p.name == param.name) {
+            Some(old) => old.default.is_some() && param.default.is_none(),
+            None => param.default.is_none(),
+        };
+        if required {
+            push(
+                BreakingKind::RequiredParam,
+                Some(param.id),
+                format!("param {} is required", param.name),
+            );
+        }
+    }
+
+    // identities
+    let old_identities = old.and_then(|m| m.identities.as_ref()).into_iter().flatten();
+    let old_identities = old_identities.map(|i| (i.id, i)).collect::<HashMap<_, _>>();
+    for identity in new.and_then(|m| m.identities.as_ref()).into_iter().flatten() {
+        match old_identities.get(&identity.id) {
+            None => push(
+                BreakingKind::AddedIdentity,
+                Some(identity.id),
+                "identity is required".into(),
+            ),
+            Some(old) if old.metadata != identity.metadata => push(
+                BreakingKind::ChangedIdentity,
+                Some(identity.id),
+                "required identity is changed".into(),
+            ),
+            Some(_) => {}
+        }
+    }
+
+    // forms
+    let old_forms = old.and_then(|m| m.forms.as_ref()).into_iter().flatten();
+    let old_forms = old_forms.map(|f| (f.id, f)).collect::<HashMap<_, _>>();
+    for form in new.and_then(|m| m.forms.as_ref()).into_iter().flatten() {
+        if old_forms.get(&form.id).is_some_and(|old| old.output != form.output) {
+            push(
+                BreakingKind::ChangedForm,
+                Some(form.id),
+                "output type of form is changed".into(),
+            );
+        }
+    }
+
+    // output
+    let old_output = old.and_then(|m| m.output.as_ref());
+    let new_output = new.and_then(|m| m.output.as_ref());
+    if old_output.is_some() && old_output != new_output {
+        push(BreakingKind::ChangedOutput, None, "output type is changed".into());
+    }
+
+    breaking
+}
+
+/// The output type of components
+fn find_output(components: &[LinkComponent]) -> Option<LinkType> {
+    components.iter().find_map(|c| match c {
+        LinkComponent::Output(output) => Some(output.output.clone()),
+        _ => None,
+    })
+}
+
+/// The kind and the inner object of component
+fn split(component: &LinkComponent) -> Option<(String, Map<String, Value>)> {
+    match serde_json::to_value(component).ok()? {
+        Value::Object(object) => {
+            let (kind, inner) = object.into_iter().next()?;
+            match inner {
+                Value::Object(inner) => Some((kind, inner)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn diff_component(id: ComponentId, old: Map<String, Value>, new: Map<String, Value>) -> Vec<ComponentChange> {
+    let mut changes = Vec::new();
+
+    let from = old
+        .get(INLETS)
+        .map(|v| serde_json::from_value::<Vec<Endpoint>>(v.clone()));
+    let to = new
+        .get(INLETS)
+        .map(|v| serde_json::from_value::<Vec<Endpoint>>(v.clone()));
+    if let (Ok(from), Ok(to)) = (from.transpose(), to.transpose()) {
+        if from != to {
+            changes.push(ComponentChange::Rewired { id, from, to });
+        }
+    }
+
+    let from = old.get(OUTPUT).map(|v| serde_json::from_value::<LinkType>(v.clone()));
+    let to = new.get(OUTPUT).map(|v| serde_json::from_value::<LinkType>(v.clone()));
+    if let (Some(Ok(from)), Some(Ok(to))) = (from, to) {
+        if from != to {
+            changes.push(ComponentChange::Output { id, from, to });
+        }
+    }
+
+    let mut values = Vec::new();
+    diff_value(
+        &mut String::new(),
+        &Value::Object(old),
+        &Value::Object(new),
+        &mut values,
+    );
+    values.retain(|c| !is_separated(&c.path));
+    if !values.is_empty() {
+        changes.push(ComponentChange::Metadata { id, changes: values });
+    }
+
+    changes
+}
+
+/// Whether the path is compared separately
+fn is_separated(path: &str) -> bool {
+    [INLETS, OUTPUT].iter().any(|key| {
+        path.strip_prefix('/')
+            .and_then(|p| p.strip_prefix(key))
+            .is_some_and(|p| p.is_empty() || p.starts_with('/'))
+    })
+}
+
+fn diff_value(path: &mut String, old: &Value, new: &Value, changes: &mut Vec<ValueChange>) {
+    match (old, new) {
+        // ? The variant of enum is changed, such as {"text":"a"} and {"integer":1}
+        (Value::Object(old), Value::Object(new)) if !is_same_variant(old, new) => changes.push(ValueChange {
+            path: path.clone(),
+            from: Some(Value::Object(old.clone())),
+            to: Some(Value::Object(new.clone())),
+        }),
+        (Value::Object(old), Value::Object(new)) => {
+            for (key, old) in old {
+                let len = path.len();
+                path.push('/');
+                path.push_str(&escape(key));
+                match new.get(key) {
+                    Some(new) => diff_value(path, old, new, changes),
+                    None => changes.push(ValueChange {
+                        path: path.clone(),
+                        from: Some(old.clone()),
+                        to: None,
+                    }),
+                }
+                path.truncate(len);
+            }
+            for (key, new) in new.iter().filter(|(key, _)| !old.contains_key(*key)) {
+                changes.push(ValueChange {
+                    path: format!("{path}/{}", escape(key)),
+                    from: None,
+                    to: Some(new.clone()),
+                });
+            }
+        }
+        (Value::Array(old), Value::Array(new)) if old.len() == new.len() => {
+            for (index, (old, new)) in old.iter().zip(new.iter()).enumerate() {
+                let len = path.len();
+                path.push_str(&format!("/{index}"));
+                diff_value(path, old, new, changes);
+                path.truncate(len);
+            }
+        }
+        (old, new) if old != new => changes.push(ValueChange {
+            path: path.clone(),
+            from: Some(old.clone()),
+            to: Some(new.clone()),
+        }),
+        _ => {}
+    }
+}
+
+/// Whether the objects are not enums of different variants
+fn is_same_variant(old: &Map<String, Value>, new: &Map<String, Value>) -> bool {
+    old.len() != 1 || new.len() != 1 || old.keys().eq(new.keys())
+}
+
+/// Escape the key of json pointer
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn value_change<T: Serialize>(key: &str, from: &Option<T>, to: &Option<T>) -> ValueChange {
+    ValueChange {
+        path: format!("/{key}"),
+        from: from.as_ref().and_then(|v| serde_json::to_value(v).ok()),
+        to: to.as_ref().and_then(|v| serde_json::to_value(v).ok()),
+    }
+}
+
+fn patch_component(component: &LinkComponent, changes: &[ValueChange]) -> Result<LinkComponent, PatchError> {
+    let id = component.id();
+    let invalid = |message: String| PatchError::InvalidComponent { id, message };
+    let (kind, inner) = split(component).ok_or_else(|| invalid("can not serialize component".into()))?;
+    let mut inner = Value::Object(inner);
+
+    for change in changes {
+        let conflict = || PatchError::Conflict {
+            id,
+            path: change.path.clone(),
+        };
+        if inner.pointer(&change.path) != change.from.as_ref() {
+            return Err(conflict());
+        }
+        let (parent, key) = change.path.rsplit_once('/').ok_or_else(conflict)?;
+        let key = key.replace("~1", "/").replace("~0", "~");
+        match (inner.pointer_mut(parent).ok_or_else(conflict)?, &change.to) {
+            (Value::Object(object), Some(to)) => {
+                object.insert(key, to.clone());
+            }
+            (Value::Object(object), None) => {
+                object.remove(&key);
+            }
+            (Value::Array(array), Some(to)) => {
+                let value = key.parse::<usize>().ok().and_then(|index| array.get_mut(index));
+                *value.ok_or_else(conflict)? = to.clone();
+            }
+            _ => return Err(conflict()),
+        }
+    }
+
+    let mut object = Map::new();
+    object.insert(kind, inner);
+    serde_json::from_value(Value::Object(object)).map_err(|e| invalid(e.to_string()))
+}
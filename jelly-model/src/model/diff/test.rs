@@ -0,0 +1,154 @@
+use crate::model::{
+    common::{lets::Endpoint, types::LinkType, values::LinkValue},
+    components::{
+        LinkComponent,
+        constant::{ComponentConst, ConstMetadata},
+        param::{ComponentParam, ParamMetadata},
+    },
+};
+
+use super::{BreakingKind, ComponentChange, PatchError, ValueChange, diff, patch};
+
+fn param(id: u32, name: &str, default: Option<&str>) -> LinkComponent {
+    LinkComponent::Param(ComponentParam {
+        id: id.into(),
+        metadata: ParamMetadata {
+            name: name.into(),
+            default: default.map(|d| d.into()),
+        },
+    })
+}
+
+fn constant(id: u32, value: LinkValue, output: LinkType) -> LinkComponent {
+    LinkComponent::Const(ComponentConst {
+        id: id.into(),
+        metadata: ConstMetadata { value },
+        output,
+    })
+}
+
+#[allow(clippy::unwrap_used)] // ? checked
+fn output(id: u32, inlet: u32, output: LinkType) -> LinkComponent {
+    serde_json::from_value(serde_json::json!({
+        "output": { "id": id, "inlets": [{ "id": inlet }], "output": output }
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_diff() {
+    let old = vec![
+        param(1, "name", Some("Bob")),
+        param(2, "age", None),
+        constant(3, LinkValue::Text("a".into()), LinkType::Text),
+        output(10, 1, LinkType::Text),
+    ];
+    let new = vec![
+        param(1, "nickname", Some("Bob")),
+        constant(3, LinkValue::Integer(1), LinkType::Integer),
+        param(4, "email", None),
+        output(10, 3, LinkType::Integer),
+    ];
+
+    let changes = diff(&old, &new);
+    assert_eq!(
+        changes.changes,
+        vec![
+            ComponentChange::Removed {
+                component: old[1].clone()
+            },
+            ComponentChange::Metadata {
+                id: 1.into(),
+                changes: vec![ValueChange {
+                    path: "/metadata/name".into(),
+                    from: Some("name".into()),
+                    to: Some("nickname".into()),
+                }],
+            },
+            ComponentChange::Output {
+                id: 3.into(),
+                from: LinkType::Text,
+                to: LinkType::Integer,
+            },
+            ComponentChange::Metadata {
+                id: 3.into(),
+                changes: vec![ValueChange {
+                    path: "/metadata/value".into(),
+                    from: Some(serde_json::json!({ "text": "a" })),
+                    to: Some(serde_json::json!({ "integer": 1 })),
+                }],
+            },
+            ComponentChange::Added {
+                index: 2,
+                component: new[2].clone(),
+            },
+            ComponentChange::Rewired {
+                id: 10.into(),
+                from: Some(vec![Endpoint {
+                    id: 1.into(),
+                    index: None
+                }]),
+                to: Some(vec![Endpoint {
+                    id: 3.into(),
+                    index: None
+                }]),
+            },
+            ComponentChange::Output {
+                id: 10.into(),
+                from: LinkType::Text,
+                to: LinkType::Integer,
+            },
+        ]
+    );
+    assert_eq!(changes.order, None);
+
+    let breaking = changes
+        .breaking
+        .iter()
+        .map(|b| (b.kind, b.component))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        breaking,
+        vec![
+            (BreakingKind::RemovedParam, Some(1.into())),
+            (BreakingKind::RemovedParam, Some(2.into())),
+            (BreakingKind::RequiredParam, Some(4.into())),
+            (BreakingKind::ChangedOutput, None),
+        ]
+    );
+    assert!(changes.is_breaking());
+
+    // patch
+    assert_eq!(patch(&old, &changes), Ok(new.clone()));
+    assert!(diff(&new, &new).is_empty());
+
+    // serialized and applied
+    let json = serde_json::to_string(&changes).unwrap_or_default();
+    let parsed = serde_json::from_str(&json).unwrap_or_default();
+    assert_eq!(changes, parsed);
+    assert_eq!(patch(&old, &parsed), Ok(new.clone()));
+
+    // conflict
+    assert_eq!(
+        patch(&new, &changes),
+        Err(PatchError::UnknownComponent { id: 2.into() })
+    );
+}
+
+#[test]
+fn test_diff_order() {
+    let old = vec![param(1, "a", None), param(2, "b", None), param(3, "c", None)];
+    let new = vec![
+        param(3, "c", None),
+        constant(1, LinkValue::Bool(true), LinkType::Bool),
+        param(2, "b", None),
+    ];
+    let changes = diff(&old, &new);
+    assert!(matches!(
+        changes.changes.as_slice(),
+        [ComponentChange::Removed { .. }, ComponentChange::Added { index: 1, .. }]
+    ));
+    assert_eq!(changes.order, Some(vec![3.into(), 1.into(), 2.into()]));
+    assert_eq!(patch(&old, &changes), Ok(new));
+    assert!(!diff(&old, &old).is_breaking());
+}
@@ -0,0 +1,175 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    LinkComponent,
+    common::{
+        call_trigger::{CallTriggerClock, ComponentCallTrigger},
+        identity::ComponentId,
+    },
+    components::call::{CallMetadata, ComponentCall, evm::action::EvmAction, http::HttpMethod, ic::action::IcAction},
+};
+
+/// test
+#[cfg(test)]
+mod test;
+
+/// The clock of update call should not be shorter // ? 1 minute
+pub const MIN_UPDATE_CLOCK_SLEEP: u32 = 60_000;
+
+/// Rule of lint, the name is stable
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LintRule {
+    /// The component has no path to any view or output
+    #[serde(rename = "orphan_component")]
+    OrphanComponent,
+    /// The form has no validate
+    #[serde(rename = "form_without_validate")]
+    FormWithoutValidate,
+    /// The update call is triggered by clock with short sleep
+    #[serde(rename = "short_update_clock")]
+    ShortUpdateClock,
+    /// The param is not referred
+    #[serde(rename = "unused_param")]
+    UnusedParam,
+    /// The identity is not referred
+    #[serde(rename = "unused_identity")]
+    UnusedIdentity,
+}
+
+/// Options of lint
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct LintOptions {
+    /// The suppressed rules
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<LintRule>,
+}
+
+/// Warning of lint
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct LintWarning {
+    /// rule
+    pub rule: LintRule,
+    /// The suspicious component
+    pub component: ComponentId,
+    /// message
+    pub message: String,
+}
+
+/// Lint the components, the warnings are sorted by component and rule
+/// ! The components should be checked, nothing is wrong but suspicious
+pub fn lint(components: &[LinkComponent], options: &LintOptions) -> Vec<LintWarning> {
+    let referred = components
+        .iter()
+        .flat_map(LinkComponent::get_references)
+        .collect::<HashSet<_>>();
+    let reached = find_reached(components);
+
+    let mut warnings = Vec::new();
+    for component in components {
+        let id = component.id();
+        let mut warn = |rule: LintRule, message: String| {
+            if !options.allow.contains(&rule) {
+                warnings.push(LintWarning {
+                    rule,
+                    component: id,
+                    message,
+                });
+            }
+        };
+        match component {
+            LinkComponent::Param(param) if !referred.contains(&id) => {
+                warn(
+                    LintRule::UnusedParam,
+                    format!("param {} is not used", param.metadata.name),
+                );
+                continue; // ? Orphan is reported by the special rule
+            }
+            LinkComponent::Identity(_) if !referred.contains(&id) => {
+                warn(LintRule::UnusedIdentity, "identity is not referred by any call".into());
+                continue;
+            }
+            LinkComponent::Form(form) if form.metadata.as_ref().is_none_or(|m| m.validate.is_none()) => {
+                warn(LintRule::FormWithoutValidate, "form has no validate".into());
+            }
+            LinkComponent::Call(call) => {
+                if let Some(sleep) = find_update_clock(call).filter(|sleep| *sleep < MIN_UPDATE_CLOCK_SLEEP) {
+                    warn(
+                        LintRule::ShortUpdateClock,
+                        format!("update call is triggered every {sleep}ms, less than {MIN_UPDATE_CLOCK_SLEEP}ms"),
+                    );
+                }
+            }
+            _ => {}
+        }
+        if !reached.contains(&id) {
+            warn(
+                LintRule::OrphanComponent,
+                "component has no path to any view or output".into(),
+            );
+        }
+    }
+
+    warnings.sort_by_key(|w| (w.component, w.rule));
+    warnings
+}
+
+/// The components have path to view or output
+fn find_reached(components: &[LinkComponent]) -> HashSet<ComponentId> {
+    let all_components: HashMap<ComponentId, &LinkComponent> = components.iter().map(|c| (c.id(), c)).collect();
+    let mut assigns: HashMap<ComponentId, Vec<ComponentId>> = HashMap::new();
+    for component in components {
+        if let LinkComponent::VariableAssign(assign) = component {
+            assigns.entry(assign.metadata.variable).or_default().push(assign.id);
+        }
+    }
+
+    let mut reached = HashSet::new();
+    let mut pending = components
+        .iter()
+        .filter(|c| matches!(c, LinkComponent::View(_) | LinkComponent::Output(_)))
+        .map(|c| c.id())
+        .collect::<Vec<_>>();
+    while let Some(id) = pending.pop() {
+        if !reached.insert(id) {
+            continue;
+        }
+        if let Some(component) = all_components.get(&id) {
+            pending.extend(component.get_references());
+        }
+        // ? The assigned value is output by the variable
+        pending.extend(assigns.get(&id).into_iter().flatten());
+    }
+    reached
+}
+
+/// The sleep of clock if the call is update
+fn find_update_clock(call: &ComponentCall) -> Option<u32> {
+    let (trigger, update) = match &call.metadata {
+        CallMetadata::Http(http) => (
+            &http.trigger,
+            Some(matches!(
+                http.method,
+                HttpMethod::Post | HttpMethod::Put | HttpMethod::Delete
+            )),
+        ),
+        CallMetadata::Ic(ic) => (
+            &ic.trigger,
+            match &ic.action {
+                IcAction::Call(action) => action.api.is_update(call.id),
+            },
+        ),
+        CallMetadata::Evm(evm) => (
+            &evm.trigger,
+            Some(matches!(
+                evm.action,
+                EvmAction::Sign(_) | EvmAction::Transaction(_) | EvmAction::Deploy(_) | EvmAction::Transfer(_)
+            )),
+        ),
+    };
+    match trigger {
+        ComponentCallTrigger::Clock(CallTriggerClock { sleep, .. }) if update == Some(true) => Some(*sleep),
+        _ => None,
+    }
+}
@@ -0,0 +1,142 @@
+use crate::model::{
+    common::{
+        call_trigger::{CallTriggerClock, ComponentCallTrigger},
+        code::{CodeContent, OriginCodeContent},
+        lets::Endpoint,
+        refer::InputValue,
+        types::LinkType,
+        values::LinkValue,
+    },
+    components::{
+        LinkComponent,
+        call::{
+            CallMetadata, ComponentCall,
+            http::{CallHttpMetadata, HttpMethod, ParsedWay},
+        },
+        code::{CodeMetadata, ComponentCode},
+        form::ComponentForm,
+        param::{ComponentParam, ParamMetadata},
+    },
+};
+
+use super::{LintOptions, LintRule, LintWarning, lint};
+
+fn param(id: u32, name: &str) -> LinkComponent {
+    LinkComponent::Param(ComponentParam {
+        id: id.into(),
+        metadata: ParamMetadata {
+            name: name.into(),
+            default: None,
+        },
+    })
+}
+
+fn http_call(id: u32, method: HttpMethod, sleep: u32) -> LinkComponent {
+    LinkComponent::Call(ComponentCall {
+        id: id.into(),
+        inlets: None,
+        metadata: CallMetadata::Http(CallHttpMetadata {
+            trigger: ComponentCallTrigger::Clock(CallTriggerClock { sleep, loading: None }),
+            identity: None,
+            url: InputValue::Const(LinkValue::Text("https://example.com/api".into())),
+            method,
+            headers: None,
+            body: None,
+            parsed: ParsedWay::Json,
+            post: None,
+        }),
+        output: LinkType::Integer,
+    })
+}
+
+#[allow(clippy::unwrap_used)] // ? checked
+fn components() -> Vec<LinkComponent> {
+    let identity = serde_json::json!({ "identity": { "id": 5, "metadata": { "metadata": { "http": {} } } } });
+    let output = serde_json::json!({
+        "output": { "id": 10, "inlets": [{ "id": 3 }], "output": LinkType::Text }
+    });
+    vec![
+        param(1, "name"),
+        param(2, "unused"),
+        LinkComponent::Code(ComponentCode {
+            id: 3.into(),
+            inlets: Some(vec![Endpoint {
+                id: 1.into(),
+                index: None,
+            }]),
+            metadata: CodeMetadata {
+                data: None,
+                code: CodeContent::Code(OriginCodeContent {
+                    code: crate::store::code::item::CodeItem {
+                        code: "result = 'hello';".into(),
+                        args: None,
+                        ret: None,
+                    },
+                    js: "".into(),
+                }),
+            },
+            output: LinkType::Text,
+        }),
+        LinkComponent::Form(ComponentForm {
+            id: 4.into(),
+            inlets: None,
+            metadata: None,
+            output: LinkType::Text,
+        }),
+        serde_json::from_value(identity).unwrap(),
+        http_call(6, HttpMethod::Post, 10000),
+        http_call(7, HttpMethod::Get, 10000), // ? Query is fine
+        serde_json::from_value(output).unwrap(),
+    ]
+}
+
+#[test]
+fn test_lint() {
+    let components = components();
+    let warnings = lint(&components, &LintOptions::default());
+    let found = warnings.iter().map(|w| (w.component, w.rule)).collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        vec![
+            (2.into(), LintRule::UnusedParam),
+            (4.into(), LintRule::OrphanComponent),
+            (4.into(), LintRule::FormWithoutValidate),
+            (5.into(), LintRule::UnusedIdentity),
+            (6.into(), LintRule::OrphanComponent),
+            (6.into(), LintRule::ShortUpdateClock),
+            (7.into(), LintRule::OrphanComponent),
+        ]
+    );
+
+    // The json is stable
+    assert_eq!(
+        serde_json::to_string(&warnings[0]).ok().as_deref(),
+        Some(r#"{"rule":"unused_param","component":2,"message":"param unused is not used"}"#)
+    );
+
+    // suppressed
+    let options: LintOptions =
+        serde_json::from_str(r#"{"allow":["orphan_component","unused_param"]}"#).unwrap_or_default();
+    let warnings = lint(&components, &options);
+    assert!(warnings.iter().all(|w| !options.allow.contains(&w.rule)));
+    assert_eq!(
+        warnings,
+        vec![
+            LintWarning {
+                rule: LintRule::FormWithoutValidate,
+                component: 4.into(),
+                message: "form has no validate".into(),
+            },
+            LintWarning {
+                rule: LintRule::UnusedIdentity,
+                component: 5.into(),
+                message: "identity is not referred by any call".into(),
+            },
+            LintWarning {
+                rule: LintRule::ShortUpdateClock,
+                component: 6.into(),
+                message: "update call is triggered every 10000ms, less than 60000ms".into(),
+            },
+        ]
+    );
+}
@@ -0,0 +1,172 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use super::{CombinedJson, LinkComponent};
+
+/// test
+#[cfg(test)]
+mod test;
+
+/// The version of current schema, it is advanced when the serialized components are changed
+/// ! It is not the version of crate, 0.0.17 adds loop, variable and switch which 0.0.16 can not read
+pub const CURRENT_VERSION: &str = "0.0.17";
+
+/// The free-form versions before registry, they are the earliest same as no version
+const LEGACY_VERSIONS: &[&str] = &["", "beta"];
+
+/// Upgrade the json of a single component
+pub type MigrateComponent = fn(&mut Value) -> Result<(), String>;
+
+/// Migration of schema
+pub struct Migration {
+    /// The version introducing the change, the json of older version is upgraded
+    pub version: &'static str,
+    /// What is changed
+    pub description: &'static str,
+    /// Upgrade the json of a single component
+    pub migrate: MigrateComponent,
+}
+
+/// All migrations in order of version
+/// ! Register a migration when the serialized component is changed incompatibly
+/// ? Nothing is changed incompatibly since 0.0.16, the components added by 0.0.17 need no migration
+pub const MIGRATIONS: &[Migration] = &[];
+
+/// Error of migration
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub enum MigrationError {
+    /// The version is neither x.y.z nor legacy
+    InvalidVersion {
+        /// The wrong version
+        version: String,
+    },
+    /// The version is newer than current
+    UnsupportedVersion {
+        /// The future version
+        version: String,
+        /// The current version
+        current: String,
+    },
+    /// The json is wrong
+    InvalidJson {
+        /// error message
+        message: String,
+    },
+    /// The migration failed
+    MigrateFailed {
+        /// The version of migration
+        version: String,
+        /// error message
+        message: String,
+    },
+}
+
+impl core::fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MigrationError::InvalidVersion { version } => write!(f, "invalid version: {version}"),
+            MigrationError::UnsupportedVersion { version, current } => {
+                write!(f, "version {version} is newer than current version {current}")
+            }
+            MigrationError::InvalidJson { message } => write!(f, "invalid json: {message}"),
+            MigrationError::MigrateFailed { version, message } => {
+                write!(f, "migrate to version {version} failed: {message}")
+            }
+        }
+    }
+}
+
+/// Upgrade the combined json of any previous version to current
+pub fn migrate_combined_json(json: &str) -> Result<CombinedJson, MigrationError> {
+    let value = serde_json::from_str(json).map_err(invalid_json)?;
+    migrate_combined_json_with(value, MIGRATIONS, CURRENT_VERSION)
+}
+
+/// Upgrade the serialized components of the version to current, the legacy version is the earliest
+pub fn migrate_components(version: &str, components: Value) -> Result<Vec<LinkComponent>, MigrationError> {
+    let components = migrate_components_value(version, components, MIGRATIONS, CURRENT_VERSION)?;
+    serde_json::from_value(components).map_err(invalid_json)
+}
+
+fn migrate_combined_json_with(
+    mut value: Value,
+    migrations: &[Migration],
+    current: &str,
+) -> Result<CombinedJson, MigrationError> {
+    let object = value
+        .as_object_mut()
+        .ok_or_else(|| invalid_json("combined json must be object"))?;
+    let version = match object.get("version") {
+        Some(Value::String(version)) => version.clone(),
+        None | Some(Value::Null) => String::new(), // ? The legacy json without version
+        Some(_) => return Err(invalid_json("version must be string")),
+    };
+    let components = object.remove("components").unwrap_or(Value::Array(vec![]));
+    let components = migrate_components_value(&version, components, migrations, current)?;
+    object.insert("components".into(), components);
+    object.insert("version".into(), Value::String(current.into()));
+    serde_json::from_value(value).map_err(invalid_json)
+}
+
+fn migrate_components_value(
+    version: &str,
+    mut components: Value,
+    migrations: &[Migration],
+    current: &str,
+) -> Result<Value, MigrationError> {
+    // ? The free-form version before registry is the earliest, same as no version
+    let from = if LEGACY_VERSIONS.contains(&version) {
+        (0, 0, 0)
+    } else {
+        parse_version(version)?
+    };
+    let to = parse_version(current)?;
+    if to < from {
+        return Err(MigrationError::UnsupportedVersion {
+            version: version.into(),
+            current: current.into(),
+        });
+    }
+
+    let components_mut = components
+        .as_array_mut()
+        .ok_or_else(|| invalid_json("components must be array"))?;
+    for migration in migrations {
+        let target = parse_version(migration.version)?;
+        if target <= from || to < target {
+            continue;
+        }
+        for component in components_mut.iter_mut() {
+            (migration.migrate)(component).map_err(|message| MigrationError::MigrateFailed {
+                version: migration.version.into(),
+                message,
+            })?;
+        }
+    }
+    Ok(components)
+}
+
+/// Parse x.y.z
+fn parse_version(version: &str) -> Result<(u32, u32, u32), MigrationError> {
+    let invalid = || MigrationError::InvalidVersion {
+        version: version.into(),
+    };
+    let mut parts = version
+        .split('.')
+        .map(|part| part.parse::<u32>().map_err(|_| invalid()));
+    let parsed = (
+        parts.next().ok_or_else(invalid)??,
+        parts.next().ok_or_else(invalid)??,
+        parts.next().ok_or_else(invalid)??,
+    );
+    if parts.next().is_some() {
+        return Err(invalid());
+    }
+    Ok(parsed)
+}
+
+fn invalid_json(message: impl ToString) -> MigrationError {
+    MigrationError::InvalidJson {
+        message: message.to_string(),
+    }
+}
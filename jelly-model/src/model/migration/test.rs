@@ -0,0 +1,111 @@
+use serde_json::Value;
+
+use super::{
+    CURRENT_VERSION, MIGRATIONS, Migration, MigrationError, migrate_combined_json, migrate_combined_json_with,
+    parse_version,
+};
+
+/// The golden fixtures serialized by every released version
+/// The legacy json has no version or a free-form version before registry
+const FIXTURES: &[(&str, &str)] = &[
+    ("legacy", include_str!("fixtures/legacy.json")),
+    ("beta", include_str!("fixtures/beta.json")),
+    ("0.0.16", include_str!("fixtures/0.0.16.json")),
+];
+
+/// The fixtures are upgraded to this, the version is current
+const EXPECTED: &str = include_str!("fixtures/current.json");
+
+#[test]
+fn test_migrate_fixtures() {
+    let mut expected: Value = serde_json::from_str(EXPECTED).unwrap_or_default();
+    if let Some(expected) = expected.as_object_mut() {
+        expected.insert("version".into(), CURRENT_VERSION.into());
+    }
+    for (version, fixture) in FIXTURES {
+        let migrated = migrate_combined_json(fixture);
+        let migrated = migrated.ok().and_then(|m| serde_json::to_value(m).ok());
+        assert_eq!(migrated.as_ref(), Some(&expected), "{version}");
+    }
+}
+
+#[test]
+fn test_migrations_order() {
+    let current = parse_version(CURRENT_VERSION).ok();
+    let mut previous = Some((0, 0, 0));
+    for migration in MIGRATIONS {
+        let version = parse_version(migration.version).ok();
+        assert!(
+            version.is_some() && previous < version && version <= current,
+            "{}",
+            migration.version
+        );
+        previous = version;
+    }
+}
+
+#[test]
+fn test_migration_registry() {
+    fn rename_param_title(component: &mut Value) -> Result<(), String> {
+        if let Some(metadata) = component.pointer_mut("/param/metadata").and_then(|m| m.as_object_mut()) {
+            let title = metadata.remove("title").ok_or("param title is missing")?;
+            metadata.insert("name".into(), title);
+        }
+        Ok(())
+    }
+    let migrations = [Migration {
+        version: "0.1.0",
+        description: "param title is renamed to name",
+        migrate: rename_param_title,
+    }];
+    let combined = |version: &str, key: &str| {
+        serde_json::json!({
+            "version": version,
+            "components": [{ "param": { "id": 1, "metadata": { key: "a" } } }],
+            "nodes": [],
+        })
+    };
+
+    // upgraded
+    let migrated = migrate_combined_json_with(combined("0.0.9", "title"), &migrations, "0.2.0");
+    let migrated = migrated.map(|m| (m.version, m.components[0].get_param_name().cloned()));
+    assert_eq!(migrated, Ok(("0.2.0".into(), Some("a".into()))));
+
+    // not changed since the version
+    let migrated = migrate_combined_json_with(combined("0.1.0", "name"), &migrations, "0.2.0");
+    assert!(migrated.is_ok());
+
+    // failed
+    assert!(matches!(
+        migrate_combined_json_with(combined("0.0.9", "name"), &migrations, "0.2.0"),
+        Err(MigrationError::MigrateFailed { .. })
+    ));
+
+    // unknown future version
+    assert_eq!(
+        migrate_combined_json_with(combined("0.3.0", "name"), &migrations, "0.2.0").err(),
+        Some(MigrationError::UnsupportedVersion {
+            version: "0.3.0".into(),
+            current: "0.2.0".into(),
+        })
+    );
+
+    // The free-form version before registry is the earliest
+    let migrated = migrate_combined_json_with(combined("beta", "title"), &migrations, "0.2.0");
+    let migrated = migrated.map(|m| (m.version, m.components[0].get_param_name().cloned()));
+    assert_eq!(migrated, Ok(("0.2.0".into(), Some("a".into()))));
+
+    // The unknown version is refused
+    for version in ["v1", "v2", "0.1.0-rc.1", "0.1"] {
+        assert_eq!(
+            migrate_combined_json_with(combined(version, "title"), &migrations, "0.2.0").err(),
+            Some(MigrationError::InvalidVersion { version: version.into() })
+        );
+    }
+
+    // The registry must be x.y.z
+    assert_eq!(
+        migrate_combined_json_with(combined("0.0.9", "title"), &migrations, "v2").err(),
+        Some(MigrationError::InvalidVersion { version: "v2".into() })
+    );
+}
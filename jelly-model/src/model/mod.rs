@@ -21,6 +21,15 @@ pub mod node;
 /// check
 pub mod check;
 
+/// lint
+pub mod lint;
+
+/// diff
+pub mod diff;
+
+/// migration
+pub mod migration;
+
 /// link component
 pub use components::LinkComponent;
 
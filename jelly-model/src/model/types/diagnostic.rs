@@ -0,0 +1,57 @@
+use serde::Serialize;
+
+use crate::model::common::{error::LinkError, identity::ComponentId};
+
+/// Severity of diagnostic
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DiagnosticSeverity {
+    /// The checking can not go on, the later stages are not checked
+    #[serde(rename = "fatal")]
+    Fatal,
+    /// The component is wrong, other components are still checked
+    #[serde(rename = "error")]
+    Error,
+}
+
+/// A problem found by checking
+#[derive(Debug, Serialize)]
+pub struct Diagnostic {
+    /// severity
+    pub severity: DiagnosticSeverity,
+    /// The component with the problem, None if it belongs to the whole combined
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub component: Option<ComponentId>,
+    /// error
+    pub error: LinkError,
+    /// Other components involved in the problem
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub related: Vec<ComponentId>,
+}
+
+impl Diagnostic {
+    /// fatal diagnostic
+    pub fn fatal(component: Option<ComponentId>, error: LinkError) -> Self {
+        Self {
+            severity: DiagnosticSeverity::Fatal,
+            component,
+            error,
+            related: Vec::new(),
+        }
+    }
+
+    /// error diagnostic
+    pub fn error(component: Option<ComponentId>, error: LinkError) -> Self {
+        Self {
+            severity: DiagnosticSeverity::Error,
+            component,
+            error,
+            related: Vec::new(),
+        }
+    }
+
+    /// Set the related components
+    pub fn with_related(mut self, related: Vec<ComponentId>) -> Self {
+        self.related = related;
+        self
+    }
+}
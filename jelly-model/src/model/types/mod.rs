@@ -1,6 +1,9 @@
 /// Check information
 pub mod check;
 
+/// diagnostic of checking
+pub mod diagnostic;
+
 /// evm
 pub mod evm;
 
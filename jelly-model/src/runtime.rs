@@ -0,0 +1,415 @@
+use std::collections::{HashMap, HashSet};
+
+use jelly_executor::{error::ExecuteCodeError, options::ExecuteOptions};
+
+use crate::{
+    common::check::{VariantName, is_valid_variant_name},
+    model::{
+        LinkComponent,
+        common::{
+            code::{CodeContent, OriginCodeContent},
+            identity::ComponentId,
+            lets::Endpoint,
+            types::LinkType,
+            values::LinkValue,
+        },
+        components::{
+            code::ComponentCode, form::ComponentForm, output::ComponentOutput, param::ComponentParam,
+            variable::ComponentVariableAssign,
+        },
+        types::check::CheckedCombined,
+    },
+};
+
+/// error
+pub mod error;
+
+/// value
+pub mod value;
+
+/// call
+pub mod call;
+
+/// conform
+pub mod conform;
+
+/// optimize
+pub mod optimize;
+
+/// convert
+mod convert;
+
+/// loops
+mod loops;
+
+/// test
+#[cfg(test)]
+mod test;
+
+use call::CallAdapter;
+use error::RuntimeError;
+use loops::LoopJump;
+use value::RuntimeValues;
+
+/// The input of running
+#[derive(Debug, Default, Clone)]
+pub struct RuntimeInputs {
+    /// query parameter, param name -> value
+    pub params: HashMap<String, String>,
+    /// The value supplied by the host, such as Form, Identity and Interaction
+    pub values: HashMap<ComponentId, LinkValue>,
+    /// The clicked component, only the call triggered by click needs it
+    pub trigger: Option<ComponentId>,
+}
+
+/// The result of running
+#[derive(Debug, Clone)]
+pub struct RuntimeOutput {
+    /// The value of the Output component, if it is produced
+    pub output: Option<LinkValue>,
+    /// The value of all nodes running
+    pub values: RuntimeValues,
+}
+
+/// Execute the checked combined
+pub struct CombinedRuntime<'a, A: CallAdapter> {
+    /// checked combined
+    combined: &'a CheckedCombined,
+    /// input
+    inputs: RuntimeInputs,
+    /// The calls are delegated to
+    adapter: &'a A,
+}
+
+impl<'a, A: CallAdapter> CombinedRuntime<'a, A> {
+    /// new
+    pub fn new(combined: &'a CheckedCombined, inputs: RuntimeInputs, adapter: &'a A) -> Self {
+        Self {
+            combined,
+            inputs,
+            adapter,
+        }
+    }
+
+    /// Execute all components in order, return the output
+    pub fn execute(&self) -> Result<RuntimeOutput, RuntimeError> {
+        // 1. Sort components by inlets
+        let components = sort_components(&self.combined.components)?;
+
+        // 2. Execute each component whose inlets are ready
+        let all_components = self.combined.components.iter().map(|c| (c.id(), c)).collect();
+        let mut values = RuntimeValues::default();
+        self.execute_components(&components, &all_components, &mut values)?; // ? Jump must be in loop body
+
+        // 3. Find the output
+        let output = self.combined.components.iter().find_map(|component| match component {
+            LinkComponent::Output(output) => values
+                .get_output(&Endpoint {
+                    id: output.id,
+                    index: None,
+                })
+                .cloned(),
+            _ => None,
+        });
+
+        Ok(RuntimeOutput { output, values })
+    }
+
+    /// Execute components in order, the loop body is executed by loop start
+    /// Return the jump if LoopContinue or LoopBreak is reached
+    fn execute_components(
+        &self,
+        components: &[&LinkComponent],
+        all_components: &HashMap<ComponentId, &LinkComponent>,
+        values: &mut RuntimeValues,
+    ) -> Result<Option<LoopJump>, RuntimeError> {
+        let mut executed = HashSet::new(); // ? Executed by loop
+        for (i, component) in components.iter().enumerate() {
+            if executed.contains(&component.id()) {
+                continue;
+            }
+            if !is_ready(component, all_components, values) {
+                continue; // ! Missing value, skip
+            }
+            match component {
+                LinkComponent::LoopStart(start) => {
+                    let body = loops::find_loop_body(start.id, components.get(i + 1..).unwrap_or_default());
+                    executed.extend(body.iter().map(|c| c.id()));
+                    if let Some(jump) = self.execute_loop(start, &body, all_components, values)? {
+                        return Ok(Some(jump)); // ? The jump of outer loop
+                    }
+                }
+                LinkComponent::LoopContinue(jump) => return Ok(Some(LoopJump::Continue(jump.metadata.start))),
+                LinkComponent::LoopBreak(jump) => return Ok(Some(LoopJump::Break(jump.metadata.start))),
+                _ => self.execute_component(component, values)?,
+            }
+        }
+        Ok(None)
+    }
+
+    /// Execute single component
+    fn execute_component(&self, component: &LinkComponent, values: &mut RuntimeValues) -> Result<(), RuntimeError> {
+        let id = component.id();
+        let value = match component {
+            LinkComponent::Param(param) => self.execute_param(param),
+            LinkComponent::Const(constant) => Some(constant.metadata.value.clone()),
+            LinkComponent::Form(form) => self.execute_form(form)?,
+            LinkComponent::Code(code) => self.execute_code(code, values)?,
+            LinkComponent::Identity(identity) => self.find_preset_value(id, &identity.get_output_type())?,
+            LinkComponent::Call(call) => self.execute_call(call, values)?,
+            LinkComponent::Interaction(interaction) => self.find_preset_value(id, &interaction.get_output_type())?,
+            LinkComponent::View(_) => Some(LinkValue::Bool(true)), // ? Once output, no data
+            LinkComponent::Condition(condition) => {
+                let index = condition.evaluate(values).map_err(|e| RuntimeError::ConditionFailed {
+                    from: id,
+                    message: format!("{e:?}"),
+                })?;
+                if let Some(index) = index {
+                    values.set(id, index, LinkValue::Bool(true)); // ? Only the matched branch is output, no data
+                }
+                return Ok(());
+            }
+            LinkComponent::Switch(switch) => {
+                if let Some(index) = switch.evaluate(values.find_refer_value(&switch.metadata.value)) {
+                    values.set(id, index, LinkValue::Bool(true)); // ? Only the matched case is output, no data
+                }
+                return Ok(());
+            }
+            LinkComponent::LoopStart(_) | LinkComponent::LoopContinue(_) | LinkComponent::LoopBreak(_) => None, // ? Executed in order
+            LinkComponent::LoopEnd(end) => values.find_input_value(&end.metadata.value), // ? The value of this round
+            LinkComponent::VariableValue(variable) => values
+                .get_variable(&variable.id)
+                .or(variable.metadata.default.as_ref())
+                .cloned(),
+            LinkComponent::VariableAssign(assign) => execute_assign(assign, values)?,
+            LinkComponent::Output(output) => execute_output(output, values)?,
+            LinkComponent::Combined(_) => return Err(RuntimeError::UnsupportedComponent { from: id }), // ! Not yet
+        };
+        if let Some(value) = value {
+            values.set(id, 0, value); // ? Only 1 output
+        }
+        Ok(())
+    }
+
+    fn execute_param(&self, param: &ComponentParam) -> Option<LinkValue> {
+        self.inputs
+            .params
+            .get(&param.metadata.name)
+            .or(param.metadata.default.as_ref())
+            .map(|value| LinkValue::Text(value.clone()))
+    }
+
+    fn execute_form(&self, form: &ComponentForm) -> Result<Option<LinkValue>, RuntimeError> {
+        match self.inputs.values.get(&form.id) {
+            Some(value) => check_value(form.id, &form.output, value).map(|_| Some(value.clone())),
+            None => Ok(form.metadata.as_ref().and_then(|m| m.default.clone())),
+        }
+    }
+
+    fn find_preset_value(&self, id: ComponentId, ty: &LinkType) -> Result<Option<LinkValue>, RuntimeError> {
+        match self.inputs.values.get(&id) {
+            Some(value) => check_value(id, ty, value).map(|_| Some(value.clone())),
+            None => Ok(None),
+        }
+    }
+
+    fn execute_code(&self, code: &ComponentCode, values: &RuntimeValues) -> Result<Option<LinkValue>, RuntimeError> {
+        // 1. get arguments
+        let Some(data) = values.find_data(code.metadata.data.as_deref().unwrap_or_default()) else {
+            return Ok(None);
+        };
+
+        // 2. find js
+        let js = self.find_js(code.id, &code.metadata.code)?;
+
+        // 3. execute
+        let value = execute_js(
+            code.id,
+            js,
+            &[("data", Some(convert::link_value_to_js_value(&data)))],
+            &code.output,
+        )?;
+
+        Ok(Some(value))
+    }
+
+    /// Find the compiled js of code
+    fn find_js<'b>(&'b self, from: ComponentId, code: &'b CodeContent) -> Result<&'b str, RuntimeError> {
+        match code {
+            CodeContent::Code(OriginCodeContent { js, .. }) => Ok(js),
+            CodeContent::Anchor(anchor) => match self.combined.codes.get(anchor) {
+                Some(code_data) => Ok(&code_data.js),
+                None => Err(RuntimeError::MissingCode {
+                    from,
+                    anchor: anchor.clone(),
+                }),
+            },
+        }
+    }
+}
+
+/// Execute js code with named arguments, None means undefined
+pub(crate) fn execute_js_value(
+    from: ComponentId,
+    js: &str,
+    args: &[(&str, Option<serde_json::Value>)],
+) -> Result<serde_json::Value, RuntimeError> {
+    let args = args
+        .iter()
+        .map(|(name, value)| (*name, value.as_ref()))
+        .collect::<Vec<_>>();
+    jelly_executor::execute_code_with_values(js, &args, &ExecuteOptions::default()).map_err(|e| {
+        RuntimeError::ExecuteCodeFailed {
+            from,
+            message: format!("{e:?}"),
+        }
+    })
+}
+
+/// Execute js code with named arguments, the result must match the output type
+pub(crate) fn execute_js(
+    from: ComponentId,
+    js: &str,
+    args: &[(&str, Option<serde_json::Value>)],
+    output: &LinkType,
+) -> Result<LinkValue, RuntimeError> {
+    let result = execute_js_value(from, js, args)?;
+    into_link_value(from, output, &result)
+}
+
+/// Execute js code with typed arguments, the result must match the output type
+///
+/// # Arguments
+///
+/// * `js` - compiled js code
+/// * `args` - The name and value of arguments, the name must be valid variant name
+/// * `output` - The type of result
+pub fn execute_code(
+    js: &str,
+    args: &[(VariantName, LinkValue)],
+    output: &LinkType,
+) -> Result<LinkValue, ExecuteCodeError> {
+    if let Some((name, _)) = args.iter().find(|(name, _)| !is_valid_variant_name(name)) {
+        return Err(ExecuteCodeError::InvalidArgs(format!("invalid variant name: {name}")));
+    }
+    let values = args
+        .iter()
+        .map(|(_, value)| convert::link_value_to_js_value(value))
+        .collect::<Vec<_>>();
+    let args = args
+        .iter()
+        .zip(values.iter())
+        .map(|((name, _), value)| (name.as_str(), Some(value)))
+        .collect::<Vec<_>>();
+
+    let result = jelly_executor::execute_code_with_values(js, &args, &ExecuteOptions::default())?;
+    conform::conform(output, &result).map_err(|mismatch| ExecuteCodeError::WrongOutput(mismatch.to_string()))
+}
+
+/// Convert js value to the output type, the path of mismatch is reported
+pub(crate) fn into_link_value(
+    from: ComponentId,
+    output: &LinkType,
+    value: &serde_json::Value,
+) -> Result<LinkValue, RuntimeError> {
+    conform::conform(output, value).map_err(|mismatch| RuntimeError::NonconformingResult { from, mismatch })
+}
+
+fn execute_output(output: &ComponentOutput, values: &RuntimeValues) -> Result<Option<LinkValue>, RuntimeError> {
+    let Some(value) = values.find_data(output.get_data().map(|d| d.as_slice()).unwrap_or_default()) else {
+        return Ok(None);
+    };
+    check_value(output.id, &output.output, &value)?;
+    Ok(Some(value))
+}
+
+/// Assign the variable, the output has no data
+fn execute_assign(
+    assign: &ComponentVariableAssign,
+    values: &mut RuntimeValues,
+) -> Result<Option<LinkValue>, RuntimeError> {
+    let Some(value) = values.find_input_value(&assign.metadata.value) else {
+        return Ok(None);
+    };
+    values.set_variable(assign.metadata.variable, value);
+    Ok(Some(LinkValue::Bool(true))) // ? Once output, no data
+}
+
+/// Whether all inlets are ready, the back edge of loop is ignored
+fn is_ready(
+    component: &LinkComponent,
+    all_components: &HashMap<ComponentId, &LinkComponent>,
+    values: &RuntimeValues,
+) -> bool {
+    let mut nullable = component.get_nullable_endpoints().unwrap_or_default();
+    if let Some(inlets) = component.get_inlets() {
+        nullable.extend(inlets.iter().filter(|inlet| {
+            all_components
+                .get(&inlet.id)
+                .is_some_and(|c| component.is_loop_back_edge(c))
+        }));
+    }
+    values.is_ready(component.get_inlets(), Some(&nullable))
+}
+
+fn check_value(from: ComponentId, ty: &LinkType, value: &LinkValue) -> Result<(), RuntimeError> {
+    if !ty.is_match(value) {
+        return Err(RuntimeError::MismatchedValue {
+            from,
+            ty: ty.clone(),
+            value: format!("{value:?}"),
+        });
+    }
+    Ok(())
+}
+
+/// Sort components, every component is after its inlets
+fn sort_components(components: &[LinkComponent]) -> Result<Vec<&LinkComponent>, RuntimeError> {
+    let all_components: HashMap<ComponentId, &LinkComponent> = components.iter().map(|c| (c.id(), c)).collect();
+
+    fn visit<'a>(
+        component: &'a LinkComponent,
+        components: &HashMap<ComponentId, &'a LinkComponent>,
+        visited: &mut Vec<ComponentId>,
+        checked: &mut HashSet<ComponentId>,
+        sorted: &mut Vec<&'a LinkComponent>,
+    ) -> Result<(), RuntimeError> {
+        let id = component.id();
+
+        if visited.contains(&id) {
+            return Err(RuntimeError::CircularReference { id }); // ! The component has appeared
+        }
+
+        if checked.contains(&id) {
+            return Ok(()); // Has been sorted
+        }
+
+        visited.push(id);
+        if let Some(inlets) = component.get_inlets() {
+            for inlet in inlets {
+                let c = components.get(&inlet.id).ok_or(RuntimeError::UnknownComponent {
+                    from: Some(id),
+                    id: inlet.id,
+                })?;
+                if component.is_loop_back_edge(c) {
+                    continue; // ? The loop body returns to the loop start
+                }
+                visit(c, components, visited, checked, sorted)?;
+            }
+        }
+        visited.pop();
+
+        checked.insert(id);
+        sorted.push(component);
+        Ok(())
+    }
+
+    let mut visited = Vec::new();
+    let mut checked = HashSet::new();
+    let mut sorted = Vec::with_capacity(components.len());
+    for component in components {
+        visit(component, &all_components, &mut visited, &mut checked, &mut sorted)?;
+    }
+
+    Ok(sorted)
+}
@@ -0,0 +1,162 @@
+use serde_json::Value;
+
+use crate::model::{
+    common::{
+        call_trigger::ComponentCallTrigger,
+        code::CodeContent,
+        identity::ComponentId,
+        lets::Endpoint,
+        refer::{CodeValue, InputValue},
+        types::LinkType,
+        values::LinkValue,
+    },
+    components::call::{CallMetadata, ComponentCall},
+};
+
+use super::{CombinedRuntime, RuntimeError, RuntimeValues, convert, execute_js_value};
+
+/// http call
+mod http;
+
+/// ic call
+mod ic;
+
+/// evm call
+mod evm;
+
+/// mock
+pub mod mock;
+
+pub use http::{HttpCallRequest, HttpCallResponse};
+
+pub use ic::IcCallRequest;
+
+pub use evm::{
+    EvmCallRequest, EvmDeployRequest, EvmDeployResponse, EvmSignRequest, EvmTransactionRequest, EvmTransferRequest,
+};
+
+/// The host which actually sends the calls
+/// The error is the message of failure
+pub trait CallAdapter {
+    /// http request
+    fn http_call(&self, request: &HttpCallRequest) -> Result<HttpCallResponse, String>;
+
+    /// ic call, response is the unwrapped candid value
+    fn ic_call(&self, request: &IcCallRequest) -> Result<Value, String>;
+
+    /// evm call, response is the decoded value
+    fn evm_call(&self, request: &EvmCallRequest) -> Result<Value, String>;
+
+    /// evm sign, response is the signature
+    fn evm_sign(&self, request: &EvmSignRequest) -> Result<String, String>;
+
+    /// evm transaction, response is the transaction hash
+    fn evm_transaction(&self, request: &EvmTransactionRequest) -> Result<String, String>;
+
+    /// evm deploy
+    fn evm_deploy(&self, request: &EvmDeployRequest) -> Result<EvmDeployResponse, String>;
+
+    /// evm transfer, response is the transaction hash
+    fn evm_transfer(&self, request: &EvmTransferRequest) -> Result<String, String>;
+}
+
+impl<A: CallAdapter> CombinedRuntime<'_, A> {
+    /// Execute call component, None if the call is not triggered or missing value
+    pub(crate) fn execute_call(
+        &self,
+        call: &ComponentCall,
+        values: &RuntimeValues,
+    ) -> Result<Option<LinkValue>, RuntimeError> {
+        let (trigger, identity) = match &call.metadata {
+            CallMetadata::Http(metadata) => (&metadata.trigger, metadata.identity),
+            CallMetadata::Ic(metadata) => (&metadata.trigger, metadata.identity),
+            CallMetadata::Evm(metadata) => (&metadata.trigger, metadata.identity),
+        };
+
+        // 1. check trigger
+        if matches!(trigger, ComponentCallTrigger::Click(_)) && self.inputs.trigger != Some(call.id) {
+            return Ok(None); // ? Not clicked
+        }
+
+        // 2. find identity
+        let identity = match identity {
+            Some(identity) => match values.get_output(&Endpoint {
+                id: identity,
+                index: None,
+            }) {
+                Some(value) => Some(value.clone()),
+                None => return Ok(None), // ! Missing identity
+            },
+            None => None, // ? anonymous
+        };
+
+        // 3. call
+        match &call.metadata {
+            CallMetadata::Http(metadata) => self.execute_http_call(call, metadata, identity, values),
+            CallMetadata::Ic(metadata) => self.execute_ic_call(call, metadata, identity, values),
+            CallMetadata::Evm(metadata) => self.execute_evm_call(call, metadata, identity, values),
+        }
+    }
+
+    /// Execute argument code, return the result and the data
+    fn execute_arg_code(
+        &self,
+        from: ComponentId,
+        data: Option<&Vec<CodeValue>>,
+        code: &CodeContent,
+        values: &RuntimeValues,
+    ) -> Result<Option<(Value, Value)>, RuntimeError> {
+        let Some(data) = values.find_data(data.map(|d| d.as_slice()).unwrap_or_default()) else {
+            return Ok(None);
+        };
+        let data = convert::link_value_to_js_value(&data);
+        let js = self.find_js(from, code)?;
+        let result = execute_js_value(from, js, &[("data", Some(data.clone()))])?;
+        Ok(Some((result, data)))
+    }
+}
+
+/// Find the text value, None if missing
+fn find_text(from: ComponentId, input: &InputValue, values: &RuntimeValues) -> Result<Option<String>, RuntimeError> {
+    match values.find_input_value(input) {
+        Some(LinkValue::Text(text)) => Ok(Some(text)),
+        Some(value) => Err(mismatched_value(from, LinkType::Text, &value)),
+        None => Ok(None),
+    }
+}
+
+/// Find the integer value, None if missing
+fn find_integer(from: ComponentId, input: &InputValue, values: &RuntimeValues) -> Result<Option<i64>, RuntimeError> {
+    match values.find_input_value(input) {
+        Some(LinkValue::Integer(integer)) => Ok(Some(integer)),
+        Some(value) => Err(mismatched_value(from, LinkType::Integer, &value)),
+        None => Ok(None),
+    }
+}
+
+/// The value of optional input, Some(None) if not required, None if missing
+fn find_optional<T>(
+    input: Option<&InputValue>,
+    find: impl FnOnce(&InputValue) -> Result<Option<T>, RuntimeError>,
+) -> Result<Option<Option<T>>, RuntimeError> {
+    match input {
+        Some(input) => Ok(find(input)?.map(Some)),
+        None => Ok(Some(None)),
+    }
+}
+
+fn mismatched_value(from: ComponentId, ty: LinkType, value: &LinkValue) -> RuntimeError {
+    RuntimeError::MismatchedValue {
+        from,
+        ty,
+        value: format!("{value:?}"),
+    }
+}
+
+/// The arguments of api after unwrapped, single argument is used directly
+fn unwrap_args(arg: &Value) -> Value {
+    match arg {
+        Value::Array(args) if args.len() == 1 => args[0].clone(),
+        _ => arg.clone(),
+    }
+}
@@ -0,0 +1,400 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    model::{
+        common::{api::evm::EvmCallApi, identity::ComponentId, values::LinkValue},
+        components::call::{
+            ComponentCall,
+            evm::{
+                CallEvmMetadata,
+                action::{
+                    EvmAction,
+                    call::{EvmActionCall, EvmCallArg, arg::EvmCallArgCode, ret::EvmCallRet},
+                    deploy::{
+                        EvmActionDeploy,
+                        initial::{EvmDeployInitial, EvmDeployInitialCode},
+                    },
+                    transaction::EvmActionTransaction,
+                    transfer::EvmActionTransfer,
+                },
+            },
+        },
+        types::evm::EvmChain,
+    },
+    store::api::content::{ApiDataContent, evm::EvmApi},
+};
+
+use super::{
+    super::{CombinedRuntime, RuntimeError, RuntimeValues, execute_js, into_link_value},
+    CallAdapter, find_integer, find_optional, find_text, unwrap_args,
+};
+
+/// evm call request
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EvmCallRequest {
+    /// The value of identity component, None if anonymous
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identity: Option<LinkValue>,
+    /// chain
+    pub chain: EvmChain,
+    /// contract address
+    pub contract: String,
+    /// The api to call
+    pub api: EvmApi,
+    /// The result of arg code, should be encoded by abi of api
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arg: Option<Value>,
+}
+
+/// evm sign request
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EvmSignRequest {
+    /// The value of identity component, None if anonymous
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identity: Option<LinkValue>,
+    /// chain
+    pub chain: EvmChain,
+    /// The message to sign
+    pub message: String,
+}
+
+/// evm transaction request
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EvmTransactionRequest {
+    /// The value of identity component, None if anonymous
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identity: Option<LinkValue>,
+    /// chain
+    pub chain: EvmChain,
+    /// contract address
+    pub contract: String,
+    /// wei unit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pay_value: Option<String>,
+    /// gas limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_limit: Option<i64>,
+    /// gas price
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_price: Option<String>,
+    /// nonce
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<i64>,
+    /// The api to call
+    pub api: EvmApi,
+    /// The result of arg code, should be encoded by abi of api
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arg: Option<Value>,
+}
+
+/// evm deploy request
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EvmDeployRequest {
+    /// The value of identity component, None if anonymous
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identity: Option<LinkValue>,
+    /// chain
+    pub chain: EvmChain,
+    /// gas limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_limit: Option<i64>,
+    /// gas price
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_price: Option<String>,
+    /// nonce
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<i64>,
+    /// contract abi json
+    pub abi: String,
+    /// contract bytecode hex
+    pub bytecode: String,
+    /// The result of initial code, the construction parameters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial: Option<Value>,
+}
+
+/// evm deploy response
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct EvmDeployResponse {
+    /// transaction hash
+    pub tx: String,
+    /// deployed contract address
+    pub address: String,
+}
+
+/// evm transfer request
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EvmTransferRequest {
+    /// The value of identity component, None if anonymous
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identity: Option<LinkValue>,
+    /// chain
+    pub chain: EvmChain,
+    /// receiver address
+    pub transfer_to: String,
+    /// wei unit
+    pub pay_value: String,
+    /// gas price
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_price: Option<String>,
+    /// nonce
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<i64>,
+}
+
+impl<A: CallAdapter> CombinedRuntime<'_, A> {
+    /// Execute evm call
+    pub(super) fn execute_evm_call(
+        &self,
+        call: &ComponentCall,
+        metadata: &CallEvmMetadata,
+        identity: Option<LinkValue>,
+        values: &RuntimeValues,
+    ) -> Result<Option<LinkValue>, RuntimeError> {
+        let chain = metadata.chain.clone();
+        match &metadata.action {
+            EvmAction::Call(action) => self.execute_evm_action_call(call, chain, action, identity, values),
+            EvmAction::Sign(message) => {
+                let Some(message) = find_text(call.id, message, values)? else {
+                    return Ok(None);
+                };
+                let request = EvmSignRequest {
+                    identity,
+                    chain,
+                    message,
+                };
+                let signature = self
+                    .adapter
+                    .evm_sign(&request)
+                    .map_err(|message| RuntimeError::CallFailed { from: call.id, message })?;
+                Ok(Some(LinkValue::Text(signature)))
+            }
+            EvmAction::Transaction(action) => self.execute_evm_transaction(call, chain, action, identity, values),
+            EvmAction::Deploy(action) => self.execute_evm_deploy(call, chain, action, identity, values),
+            EvmAction::Transfer(action) => self.execute_evm_transfer(call, chain, action, identity, values),
+        }
+    }
+
+    fn execute_evm_action_call(
+        &self,
+        call: &ComponentCall,
+        chain: EvmChain,
+        action: &EvmActionCall,
+        identity: Option<LinkValue>,
+        values: &RuntimeValues,
+    ) -> Result<Option<LinkValue>, RuntimeError> {
+        let from = call.id;
+
+        let Some(contract) = find_text(from, &action.contract, values)? else {
+            return Ok(None);
+        };
+        let api = self.find_evm_api(from, &action.api)?;
+        let Some((arg, data_of_arg)) = self.execute_evm_arg(from, action.arg.as_ref(), values)? else {
+            return Ok(None);
+        };
+
+        let request = EvmCallRequest {
+            identity,
+            chain,
+            contract,
+            api,
+            arg,
+        };
+        let response = self
+            .adapter
+            .evm_call(&request)
+            .map_err(|message| RuntimeError::CallFailed { from, message })?;
+
+        let value = match &action.ret {
+            Some(EvmCallRet::Code(code)) => {
+                let js = self.find_js(from, code)?;
+                execute_js(
+                    from,
+                    js,
+                    &[
+                        ("data", Some(response)),
+                        ("args", request.arg.as_ref().map(unwrap_args)),
+                        ("data_of_args", data_of_arg),
+                    ],
+                    &call.output,
+                )?
+            }
+            None => into_link_value(from, &call.output, &response)?,
+        };
+
+        Ok(Some(value))
+    }
+
+    fn execute_evm_transaction(
+        &self,
+        call: &ComponentCall,
+        chain: EvmChain,
+        action: &EvmActionTransaction,
+        identity: Option<LinkValue>,
+        values: &RuntimeValues,
+    ) -> Result<Option<LinkValue>, RuntimeError> {
+        let from = call.id;
+
+        let Some(contract) = find_text(from, &action.contract, values)? else {
+            return Ok(None);
+        };
+        let Some(pay_value) = find_optional(action.pay_value.as_ref(), |v| find_text(from, v, values))? else {
+            return Ok(None);
+        };
+        let Some(gas_limit) = find_optional(action.gas_limit.as_ref(), |v| find_integer(from, v, values))? else {
+            return Ok(None);
+        };
+        let Some(gas_price) = find_optional(action.gas_price.as_ref(), |v| find_text(from, v, values))? else {
+            return Ok(None);
+        };
+        let Some(nonce) = find_optional(action.nonce.as_ref(), |v| find_integer(from, v, values))? else {
+            return Ok(None);
+        };
+        let api = self.find_evm_api(from, &action.api)?;
+        let Some((arg, _)) = self.execute_evm_arg(from, action.arg.as_ref(), values)? else {
+            return Ok(None);
+        };
+
+        let request = EvmTransactionRequest {
+            identity,
+            chain,
+            contract,
+            pay_value,
+            gas_limit,
+            gas_price,
+            nonce,
+            api,
+            arg,
+        };
+        let tx = self
+            .adapter
+            .evm_transaction(&request)
+            .map_err(|message| RuntimeError::CallFailed { from, message })?;
+
+        Ok(Some(LinkValue::Text(tx)))
+    }
+
+    fn execute_evm_deploy(
+        &self,
+        call: &ComponentCall,
+        chain: EvmChain,
+        action: &EvmActionDeploy,
+        identity: Option<LinkValue>,
+        values: &RuntimeValues,
+    ) -> Result<Option<LinkValue>, RuntimeError> {
+        let from = call.id;
+
+        let Some(gas_limit) = find_optional(action.gas_limit.as_ref(), |v| find_integer(from, v, values))? else {
+            return Ok(None);
+        };
+        let Some(gas_price) = find_optional(action.gas_price.as_ref(), |v| find_text(from, v, values))? else {
+            return Ok(None);
+        };
+        let Some(nonce) = find_optional(action.nonce.as_ref(), |v| find_integer(from, v, values))? else {
+            return Ok(None);
+        };
+        let Some(abi) = find_text(from, &action.abi, values)? else {
+            return Ok(None);
+        };
+        let Some(bytecode) = find_text(from, &action.bytecode, values)? else {
+            return Ok(None);
+        };
+        let initial = match &action.initial {
+            Some(EvmDeployInitial::Code(EvmDeployInitialCode { data, code })) => {
+                let Some((initial, _)) = self.execute_arg_code(from, data.as_ref(), code, values)? else {
+                    return Ok(None);
+                };
+                Some(initial)
+            }
+            None => None,
+        };
+
+        let request = EvmDeployRequest {
+            identity,
+            chain,
+            gas_limit,
+            gas_price,
+            nonce,
+            abi,
+            bytecode,
+            initial,
+        };
+        let EvmDeployResponse { tx, address } = self
+            .adapter
+            .evm_deploy(&request)
+            .map_err(|message| RuntimeError::CallFailed { from, message })?;
+
+        let value = serde_json::json!({ "tx": tx, "address": address });
+        into_link_value(from, &call.output, &value).map(Some)
+    }
+
+    fn execute_evm_transfer(
+        &self,
+        call: &ComponentCall,
+        chain: EvmChain,
+        action: &EvmActionTransfer,
+        identity: Option<LinkValue>,
+        values: &RuntimeValues,
+    ) -> Result<Option<LinkValue>, RuntimeError> {
+        let from = call.id;
+
+        let Some(transfer_to) = find_text(from, &action.transfer_to, values)? else {
+            return Ok(None);
+        };
+        let Some(pay_value) = find_text(from, &action.pay_value, values)? else {
+            return Ok(None);
+        };
+        let Some(gas_price) = find_optional(action.gas_price.as_ref(), |v| find_text(from, v, values))? else {
+            return Ok(None);
+        };
+        let Some(nonce) = find_optional(action.nonce.as_ref(), |v| find_integer(from, v, values))? else {
+            return Ok(None);
+        };
+
+        let request = EvmTransferRequest {
+            identity,
+            chain,
+            transfer_to,
+            pay_value,
+            gas_price,
+            nonce,
+        };
+        let tx = self
+            .adapter
+            .evm_transfer(&request)
+            .map_err(|message| RuntimeError::CallFailed { from, message })?;
+
+        Ok(Some(LinkValue::Text(tx)))
+    }
+
+    /// Execute arg code, Some((None, None)) if no arg, None if missing value
+    #[allow(clippy::type_complexity)]
+    fn execute_evm_arg(
+        &self,
+        from: ComponentId,
+        arg: Option<&EvmCallArg>,
+        values: &RuntimeValues,
+    ) -> Result<Option<(Option<Value>, Option<Value>)>, RuntimeError> {
+        match arg {
+            Some(EvmCallArg::Code(EvmCallArgCode { data, code })) => Ok(self
+                .execute_arg_code(from, data.as_ref(), code, values)?
+                .map(|(arg, data)| (Some(arg), Some(data)))),
+            None => Ok(Some((None, None))),
+        }
+    }
+
+    /// Find the api, the anchor must be stored in checked combined
+    fn find_evm_api(&self, from: ComponentId, api: &EvmCallApi) -> Result<EvmApi, RuntimeError> {
+        match api {
+            EvmCallApi::Api(api) => Ok(api.clone()),
+            EvmCallApi::Anchor(anchor) => match self.combined.apis.get(anchor).map(|api| &api.content) {
+                Some(ApiDataContent::Evm(api)) => Ok(api.clone()),
+                _ => Err(RuntimeError::MissingApi {
+                    from,
+                    anchor: anchor.clone(),
+                }),
+            },
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::model::{
+    common::values::LinkValue,
+    components::call::{
+        ComponentCall,
+        http::{CallHttpMetadata, HttpBody, HttpBodyCode, HttpBodyPlain, HttpMethod, ParsedWay},
+    },
+};
+
+use super::{
+    super::{CombinedRuntime, RuntimeError, RuntimeValues, convert, execute_js, into_link_value},
+    CallAdapter, find_text,
+};
+
+/// http request
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct HttpCallRequest {
+    /// The value of identity component, None if anonymous
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identity: Option<LinkValue>,
+    /// url
+    pub url: String,
+    /// method
+    pub method: HttpMethod,
+    /// headers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<Vec<(String, String)>>,
+    /// body
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+    /// Analytical method of response
+    pub parsed: ParsedWay,
+}
+
+/// http response
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct HttpCallResponse {
+    /// The parsed body
+    pub body: Value,
+    /// response headers
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+}
+
+impl<A: CallAdapter> CombinedRuntime<'_, A> {
+    /// Execute http call
+    pub(super) fn execute_http_call(
+        &self,
+        call: &ComponentCall,
+        metadata: &CallHttpMetadata,
+        identity: Option<LinkValue>,
+        values: &RuntimeValues,
+    ) -> Result<Option<LinkValue>, RuntimeError> {
+        let from = call.id;
+
+        // 1. url
+        let Some(url) = find_text(from, &metadata.url, values)? else {
+            return Ok(None);
+        };
+
+        // 2. headers
+        let headers = match &metadata.headers {
+            Some(named) => {
+                let mut headers = Vec::with_capacity(named.len());
+                for header in named {
+                    let Some(value) = find_text(from, &header.value, values)? else {
+                        return Ok(None);
+                    };
+                    headers.push((header.name.clone(), value));
+                }
+                Some(headers)
+            }
+            None => None,
+        };
+
+        // 3. body
+        let (body, data_of_body) = match &metadata.body {
+            Some(HttpBody::Plain(HttpBodyPlain { data })) => {
+                let mut body = Map::new();
+                for named in data.iter().flatten() {
+                    let Some(value) = values.find_input_value(&named.value) else {
+                        return Ok(None);
+                    };
+                    body.insert(named.name.clone(), convert::link_value_to_js_value(&value));
+                }
+                (Some(Value::Object(body)), None)
+            }
+            Some(HttpBody::Code(HttpBodyCode { data, code })) => {
+                let Some((body, data)) = self.execute_arg_code(from, data.as_ref(), code, values)? else {
+                    return Ok(None);
+                };
+                (Some(body), Some(data))
+            }
+            None => (None, None),
+        };
+
+        // 4. request
+        let request = HttpCallRequest {
+            identity,
+            url,
+            method: metadata.method.clone(),
+            headers,
+            body,
+            parsed: metadata.parsed.clone(),
+        };
+        let response = self
+            .adapter
+            .http_call(&request)
+            .map_err(|message| RuntimeError::CallFailed { from, message })?;
+
+        // 5. post
+        let value = match &metadata.post {
+            Some(post) => {
+                let js = self.find_js(from, post)?;
+                let headers = |headers: &Vec<(String, String)>| serde_json::json!(headers);
+                execute_js(
+                    from,
+                    js,
+                    &[
+                        ("data", Some(response.body)),
+                        ("response_headers", Some(headers(&response.headers))),
+                        ("request_url", Some(Value::String(request.url))),
+                        ("request_method", Some(serde_json::json!(request.method))),
+                        ("request_headers", request.headers.as_ref().map(headers)),
+                        ("request_body", request.body),
+                        ("data_of_request_body", data_of_body),
+                    ],
+                    &call.output,
+                )?
+            }
+            None => into_link_value(from, &call.output, &response.body)?,
+        };
+
+        Ok(Some(value))
+    }
+}
@@ -0,0 +1,172 @@
+use ic_canister_kit::types::WrappedCandidType;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    model::{
+        common::{
+            api::ic::{IcCallApi, candid::parse_candid_for_data_and_output},
+            identity::ComponentId,
+            values::LinkValue,
+        },
+        components::call::{
+            ComponentCall,
+            ic::{
+                CallIcMetadata,
+                action::{
+                    IcAction,
+                    call::{
+                        IcActionCall,
+                        arg::{IcCallArg, IcCallArgCode},
+                        ret::IcCallRet,
+                    },
+                },
+            },
+        },
+    },
+    store::api::content::{
+        ApiDataContent,
+        ic::{InternetComputerApi, OriginInternetComputerApi, SingleInternetComputerApi},
+    },
+};
+
+use super::{
+    super::{CombinedRuntime, RuntimeError, RuntimeValues, execute_js, into_link_value},
+    CallAdapter, find_text, unwrap_args,
+};
+
+/// ic call request
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct IcCallRequest {
+    /// The value of identity component, None if anonymous
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identity: Option<LinkValue>,
+    /// canister id
+    pub canister_id: String,
+    /// The api to call
+    pub api: InternetComputerApi,
+    /// The result of arg code, should be encoded by candid of api
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arg: Option<Value>,
+    /// The arg built from candid type, in candid text format
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub candid: Option<String>,
+}
+
+impl<A: CallAdapter> CombinedRuntime<'_, A> {
+    /// Execute ic call
+    pub(super) fn execute_ic_call(
+        &self,
+        call: &ComponentCall,
+        metadata: &CallIcMetadata,
+        identity: Option<LinkValue>,
+        values: &RuntimeValues,
+    ) -> Result<Option<LinkValue>, RuntimeError> {
+        match &metadata.action {
+            IcAction::Call(action) => self.execute_ic_action_call(call, action, identity, values),
+        }
+    }
+
+    fn execute_ic_action_call(
+        &self,
+        call: &ComponentCall,
+        action: &IcActionCall,
+        identity: Option<LinkValue>,
+        values: &RuntimeValues,
+    ) -> Result<Option<LinkValue>, RuntimeError> {
+        let from = call.id;
+
+        // 1. canister id
+        let Some(canister_id) = find_text(from, &action.canister_id, values)? else {
+            return Ok(None);
+        };
+
+        // 2. api
+        let api = self.find_ic_api(from, &action.api)?;
+
+        // 3. arg
+        let (arg, data_of_arg, candid) = match &action.arg {
+            Some(IcCallArg::Code(IcCallArgCode { data, code })) => {
+                let Some((arg, data)) = self.execute_arg_code(from, data.as_ref(), code, values)? else {
+                    return Ok(None);
+                };
+                (Some(arg), Some(data), None)
+            }
+            Some(IcCallArg::Candid(candid)) => {
+                let args = find_ic_api_args(from, &api)?;
+                let Some(candid) = candid
+                    .to_idl_args(&args, values)
+                    .map_err(|message| RuntimeError::BuildArgFailed { from, message })?
+                else {
+                    return Ok(None);
+                };
+                (None, None, Some(candid.to_string()))
+            }
+            None => (None, None, None),
+        };
+
+        // 4. call
+        let request = IcCallRequest {
+            identity,
+            canister_id,
+            api,
+            arg,
+            candid,
+        };
+        let response = self
+            .adapter
+            .ic_call(&request)
+            .map_err(|message| RuntimeError::CallFailed { from, message })?;
+
+        // 5. ret
+        let value = match &action.ret {
+            Some(IcCallRet::Code(code)) => {
+                let js = self.find_js(from, code)?;
+                execute_js(
+                    from,
+                    js,
+                    &[
+                        ("data", Some(response)),
+                        ("args", request.arg.as_ref().map(unwrap_args)),
+                        ("data_of_args", data_of_arg),
+                    ],
+                    &call.output,
+                )?
+            }
+            None => into_link_value(from, &call.output, &response)?,
+        };
+
+        Ok(Some(value))
+    }
+
+    /// Find the api, the anchor must be stored in checked combined
+    fn find_ic_api(&self, from: ComponentId, api: &IcCallApi) -> Result<InternetComputerApi, RuntimeError> {
+        match api {
+            IcCallApi::Api(api) => Ok(api.clone()),
+            IcCallApi::Anchor(anchor) => match self.combined.apis.get(anchor).map(|api| &api.content) {
+                Some(ApiDataContent::InternetComputer(api)) => Ok(api.clone()),
+                _ => Err(RuntimeError::MissingApi {
+                    from,
+                    anchor: anchor.clone(),
+                }),
+            },
+        }
+    }
+}
+
+/// The parameters of api
+fn find_ic_api_args(from: ComponentId, api: &InternetComputerApi) -> Result<Vec<WrappedCandidType>, RuntimeError> {
+    let func = match api {
+        InternetComputerApi::Single(SingleInternetComputerApi { api }) => {
+            parse_candid_for_data_and_output(&format!("service: {{ {api} }}"), None, from)
+        }
+        InternetComputerApi::Origin(OriginInternetComputerApi { candid, method }) => {
+            parse_candid_for_data_and_output(candid, Some(method), from)
+        }
+    };
+    func.map(|func| func.args)
+        .map_err(|error| RuntimeError::BuildArgFailed {
+            from,
+            message: error.to_string(),
+        })
+}
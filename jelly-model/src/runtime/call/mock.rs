@@ -0,0 +1,126 @@
+use std::cell::RefCell;
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use super::{
+    CallAdapter, EvmCallRequest, EvmDeployRequest, EvmDeployResponse, EvmSignRequest, EvmTransactionRequest,
+    EvmTransferRequest, HttpCallRequest, HttpCallResponse, IcCallRequest,
+};
+
+/// The request sent to adapter
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum CallRequest {
+    /// http
+    #[serde(rename = "http")]
+    Http(HttpCallRequest),
+    /// ic
+    #[serde(rename = "ic")]
+    Ic(IcCallRequest),
+    /// evm call
+    #[serde(rename = "evm_call")]
+    EvmCall(EvmCallRequest),
+    /// evm sign
+    #[serde(rename = "evm_sign")]
+    EvmSign(EvmSignRequest),
+    /// evm transaction
+    #[serde(rename = "evm_transaction")]
+    EvmTransaction(EvmTransactionRequest),
+    /// evm deploy
+    #[serde(rename = "evm_deploy")]
+    EvmDeploy(EvmDeployRequest),
+    /// evm transfer
+    #[serde(rename = "evm_transfer")]
+    EvmTransfer(EvmTransferRequest),
+}
+
+/// The recorded response
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum CallResponse {
+    /// Success, the value is decoded as the response of request
+    #[serde(rename = "ok")]
+    Ok(Value),
+    /// Failed message
+    #[serde(rename = "err")]
+    Err(String),
+}
+
+/// Recorded call
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CallFixture {
+    /// request
+    pub request: CallRequest,
+    /// response
+    pub response: CallResponse,
+}
+
+/// Mock adapter, respond the recorded fixtures by request
+#[derive(Debug, Default)]
+pub struct MockCallAdapter {
+    /// fixtures
+    fixtures: Vec<CallFixture>,
+    /// All requests received
+    calls: RefCell<Vec<CallRequest>>,
+}
+
+impl MockCallAdapter {
+    /// new
+    pub fn new(fixtures: Vec<CallFixture>) -> Self {
+        Self {
+            fixtures,
+            calls: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Load fixtures from json array
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let fixtures = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        Ok(Self::new(fixtures))
+    }
+
+    /// All requests received in order
+    pub fn calls(&self) -> Vec<CallRequest> {
+        self.calls.borrow().clone()
+    }
+
+    fn respond<T: DeserializeOwned>(&self, request: CallRequest) -> Result<T, String> {
+        let fixture = self.fixtures.iter().find(|fixture| fixture.request == request);
+        let message = format!("can not find fixture: {}", serde_json::json!(request));
+        self.calls.borrow_mut().push(request);
+        match fixture.map(|fixture| &fixture.response) {
+            Some(CallResponse::Ok(value)) => serde_json::from_value(value.clone()).map_err(|e| e.to_string()),
+            Some(CallResponse::Err(message)) => Err(message.clone()),
+            None => Err(message),
+        }
+    }
+}
+
+impl CallAdapter for MockCallAdapter {
+    fn http_call(&self, request: &HttpCallRequest) -> Result<HttpCallResponse, String> {
+        self.respond(CallRequest::Http(request.clone()))
+    }
+
+    fn ic_call(&self, request: &IcCallRequest) -> Result<Value, String> {
+        self.respond(CallRequest::Ic(request.clone()))
+    }
+
+    fn evm_call(&self, request: &EvmCallRequest) -> Result<Value, String> {
+        self.respond(CallRequest::EvmCall(request.clone()))
+    }
+
+    fn evm_sign(&self, request: &EvmSignRequest) -> Result<String, String> {
+        self.respond(CallRequest::EvmSign(request.clone()))
+    }
+
+    fn evm_transaction(&self, request: &EvmTransactionRequest) -> Result<String, String> {
+        self.respond(CallRequest::EvmTransaction(request.clone()))
+    }
+
+    fn evm_deploy(&self, request: &EvmDeployRequest) -> Result<EvmDeployResponse, String> {
+        self.respond(CallRequest::EvmDeploy(request.clone()))
+    }
+
+    fn evm_transfer(&self, request: &EvmTransferRequest) -> Result<String, String> {
+        self.respond(CallRequest::EvmTransfer(request.clone()))
+    }
+}
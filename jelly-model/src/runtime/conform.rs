@@ -0,0 +1,153 @@
+use std::fmt::Write;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::model::common::{
+    types::{LinkType, ObjectSubitem},
+    values::{ArrayLinkValue, LinkValue, ObjectSubitemValue},
+};
+
+use super::convert::OPEN_TYPE;
+
+/// The root of path
+const ROOT: &str = "result";
+
+/// 2^63, the integer must be in [-2^63, 2^63)
+/// ! i64::MAX as f64 is rounded up to 2^63, which is out of range
+const INTEGER_LIMIT: f64 = 9_223_372_036_854_775_808.0;
+
+/// The value is not conform to the type
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ConformanceMismatch {
+    /// The path of mismatched value, such as result.rows[3][1]
+    pub path: String,
+    /// The type required
+    pub expected: String,
+    /// The kind of value got
+    pub got: String,
+}
+
+impl core::fmt::Display for ConformanceMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} expected {} got {}", self.path, self.expected, self.got)
+    }
+}
+
+/// Check the js value by the type, the matched link value is returned
+pub fn conform(ty: &LinkType, value: &Value) -> Result<LinkValue, ConformanceMismatch> {
+    conform_path(ty, value, &mut ROOT.to_string())
+}
+
+fn conform_path(ty: &LinkType, value: &Value, path: &mut String) -> Result<LinkValue, ConformanceMismatch> {
+    let mismatch = |path: &String, got: String| ConformanceMismatch {
+        path: path.clone(),
+        expected: type_name(ty).into(),
+        got,
+    };
+
+    let value = match (ty, value) {
+        (LinkType::Text, Value::String(text)) => LinkValue::Text(text.clone()),
+        (LinkType::Bool, Value::Bool(bool)) => LinkValue::Bool(*bool),
+        (LinkType::Integer, Value::Number(number)) => match number.as_i64() {
+            Some(integer) => LinkValue::Integer(integer),
+            None => {
+                let number = number.as_f64().unwrap_or(f64::NAN);
+                if number.fract() != 0.0 {
+                    return Err(mismatch(path, kind(value)));
+                }
+                if !(-INTEGER_LIMIT..INTEGER_LIMIT).contains(&number) {
+                    return Err(mismatch(path, "number out of range".into()));
+                }
+                LinkValue::Integer(number as i64)
+            }
+        },
+        (LinkType::Integer, Value::Object(object)) if open_type(object) == Some("bigint") => {
+            // ? bigint is encoded as open type
+            let integer = object
+                .get("value")
+                .and_then(|v| v.as_str())
+                .and_then(|v| v.parse().ok());
+            match integer {
+                Some(integer) => LinkValue::Integer(integer),
+                None => return Err(mismatch(path, "bigint out of range".into())),
+            }
+        }
+        (LinkType::Number, Value::Number(number)) => match number.as_f64() {
+            Some(number) => LinkValue::Number(number),
+            None => return Err(mismatch(path, kind(value))),
+        },
+        (LinkType::Array(subtype), Value::Array(values)) => {
+            let mut items = Vec::with_capacity(values.len());
+            for (i, value) in values.iter().enumerate() {
+                let len = path.len();
+                let _ = write!(path, "[{i}]");
+                items.push(conform_path(subtype, value, path)?);
+                path.truncate(len);
+            }
+            LinkValue::Array(ArrayLinkValue {
+                ty: subtype.as_ref().clone(),
+                values: items,
+            })
+        }
+        (LinkType::Array(subtype), Value::Object(object))
+            if subtype.is_integer() && open_type(object) == Some("Uint8Array") =>
+        {
+            // ? Uint8Array is encoded as open type
+            return match object.get("value") {
+                Some(value) => conform_path(ty, value, path),
+                None => Err(mismatch(path, "Uint8Array".into())),
+            };
+        }
+        (LinkType::Object(subitems), Value::Object(object)) => {
+            let mut items = Vec::with_capacity(subitems.len());
+            for ObjectSubitem { key, ty } in subitems {
+                let len = path.len();
+                path.push('.');
+                path.push_str(key);
+                let Some(value) = object.get(key) else {
+                    return Err(ConformanceMismatch {
+                        path: path.clone(),
+                        expected: type_name(ty).into(),
+                        got: "undefined".into(),
+                    });
+                };
+                items.push(ObjectSubitemValue {
+                    key: key.clone(),
+                    value: conform_path(ty, value, path)?,
+                });
+                path.truncate(len);
+            }
+            LinkValue::Object(items)
+        }
+        _ => return Err(mismatch(path, kind(value))),
+    };
+    Ok(value)
+}
+
+fn open_type(object: &Map<String, Value>) -> Option<&str> {
+    object.get(OPEN_TYPE).and_then(|t| t.as_str())
+}
+
+fn type_name(ty: &LinkType) -> &'static str {
+    match ty {
+        LinkType::Text => "text",
+        LinkType::Bool => "bool",
+        LinkType::Integer => "integer",
+        LinkType::Number => "number",
+        LinkType::Array(_) => "array",
+        LinkType::Object(_) => "object",
+    }
+}
+
+/// The kind of js value, the open type is named by itself
+fn kind(value: &Value) -> String {
+    match value {
+        Value::Null => "null".into(),
+        Value::Bool(_) => "bool".into(),
+        Value::Number(_) => "number".into(),
+        Value::String(_) => "text".into(),
+        Value::Array(_) => "array".into(),
+        Value::Object(object) => open_type(object).unwrap_or("object").into(),
+    }
+}
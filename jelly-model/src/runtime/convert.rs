@@ -0,0 +1,27 @@
+use serde_json::{Map, Value};
+
+use crate::model::common::values::{ArrayLinkValue, LinkValue, ObjectSubitemValue};
+
+/// Open type mark of js value
+pub(crate) const OPEN_TYPE: &str = "__open_type__";
+
+/// Convert link value to js value, same as `link_value_to_js_value` in jelly-types
+pub(crate) fn link_value_to_js_value(value: &LinkValue) -> Value {
+    match value {
+        LinkValue::Text(text) => Value::String(text.clone()),
+        LinkValue::Bool(bool) => Value::Bool(*bool),
+        LinkValue::Integer(integer) => Value::from(*integer),
+        LinkValue::Number(number) => serde_json::Number::from_f64(*number)
+            .map(Value::Number)
+            .unwrap_or(Value::Null), // ! NaN and Infinity can not be json
+        LinkValue::Array(ArrayLinkValue { values, .. }) => {
+            Value::Array(values.iter().map(link_value_to_js_value).collect())
+        }
+        LinkValue::Object(values) => Value::Object(
+            values
+                .iter()
+                .map(|ObjectSubitemValue { key, value }| (key.clone(), link_value_to_js_value(value)))
+                .collect::<Map<_, _>>(),
+        ),
+    }
+}
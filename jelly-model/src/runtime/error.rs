@@ -0,0 +1,107 @@
+use serde::Serialize;
+
+use crate::{
+    model::common::{identity::ComponentId, types::LinkType},
+    store::{api::anchor::ApiDataAnchor, code::anchor::CodeDataAnchor},
+};
+
+use super::conform::ConformanceMismatch;
+
+/// Error of running
+#[derive(Debug, Serialize)]
+pub enum RuntimeError {
+    /// Unknown component
+    UnknownComponent {
+        /// The required component
+        #[serde(skip_serializing_if = "Option::is_none")]
+        from: Option<ComponentId>,
+        /// Can't find a component
+        id: ComponentId,
+    },
+
+    /// Cyclic reference
+    CircularReference {
+        /// referenced ID
+        id: ComponentId,
+    },
+
+    /// The component is not supported by the runtime yet
+    UnsupportedComponent {
+        /// The required component
+        from: ComponentId,
+    },
+
+    /// Can not find the stored code
+    MissingCode {
+        /// The required component
+        from: ComponentId,
+        /// code anchor
+        anchor: CodeDataAnchor,
+    },
+
+    /// Execute code failed
+    ExecuteCodeFailed {
+        /// The required component
+        from: ComponentId,
+        /// error message
+        message: String,
+    },
+
+    /// Evaluate condition failed
+    ConditionFailed {
+        /// The required component
+        from: ComponentId,
+        /// error message
+        message: String,
+    },
+
+    /// Can not find the stored api
+    MissingApi {
+        /// The required component
+        from: ComponentId,
+        /// api anchor
+        anchor: ApiDataAnchor,
+    },
+
+    /// Build the arg of call failed
+    BuildArgFailed {
+        /// The required component
+        from: ComponentId,
+        /// error message
+        message: String,
+    },
+
+    /// The call is failed
+    CallFailed {
+        /// The required component
+        from: ComponentId,
+        /// error message
+        message: String,
+    },
+
+    /// The infinite loop is not stopped
+    LoopRoundsExceeded {
+        /// The loop start component
+        from: ComponentId,
+        /// max rounds
+        max: usize,
+    },
+
+    /// The value is not match the type
+    MismatchedValue {
+        /// The required component
+        from: ComponentId,
+        /// The type required
+        ty: LinkType,
+        /// The value got
+        value: String,
+    },
+
+    /// The result of code or call is not conform to the output type
+    NonconformingResult {
+        /// The required component
+        from: ComponentId,
+        /// The path of mismatch
+        mismatch: ConformanceMismatch,
+    },
+}
@@ -0,0 +1,151 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::model::{
+    LinkComponent,
+    common::{
+        identity::ComponentId,
+        lets::Endpoint,
+        types::LinkType,
+        values::{ArrayLinkValue, LinkValue},
+    },
+    components::loops::{ComponentLoopEnd, ComponentLoopStart, LoopWay},
+};
+
+use super::{CallAdapter, CombinedRuntime, RuntimeError, RuntimeValues};
+
+/// The max rounds of infinite loop
+const MAX_LOOP_ROUNDS: usize = 10_000;
+
+/// The jump of loop body
+pub(crate) enum LoopJump {
+    /// LoopContinue of the loop start
+    Continue(ComponentId),
+    /// LoopBreak of the loop start
+    Break(ComponentId),
+}
+
+/// Find the loop body from the sorted components after loop start
+/// The body depends on the loop start, and the loop end is the last of body
+pub(crate) fn find_loop_body<'a>(start: ComponentId, components: &[&'a LinkComponent]) -> Vec<&'a LinkComponent> {
+    let mut inner = HashSet::from([start]); // Depends on loop start
+    let mut outer = HashSet::new(); // Depends on loop end
+    let mut body = Vec::new();
+    for component in components {
+        let id = component.id();
+        let inlets = component
+            .get_inlets()
+            .map(|inlets| inlets.as_slice())
+            .unwrap_or_default();
+        if inlets.iter().any(|inlet| outer.contains(&inlet.id)) {
+            outer.insert(id);
+            continue;
+        }
+        if !inlets.iter().any(|inlet| inner.contains(&inlet.id)) {
+            continue;
+        }
+        match component {
+            LinkComponent::LoopEnd(end) if end.metadata.start == start => {
+                outer.insert(id); // ? The output of loop end is out of body
+            }
+            _ => {
+                inner.insert(id);
+            }
+        }
+        body.push(*component);
+    }
+    body
+}
+
+impl<A: CallAdapter> CombinedRuntime<'_, A> {
+    /// Execute the loop body by rounds, the loop ends collect the value of each round
+    pub(super) fn execute_loop(
+        &self,
+        start: &ComponentLoopStart,
+        body: &[&LinkComponent],
+        all_components: &HashMap<ComponentId, &LinkComponent>,
+        values: &mut RuntimeValues,
+    ) -> Result<Option<LoopJump>, RuntimeError> {
+        // 1. items
+        let items = match &start.metadata.way {
+            LoopWay::Iteration(refer) => match values.find_refer_value(refer) {
+                Some(LinkValue::Array(array)) => Some(array.values.clone()),
+                Some(value) => {
+                    return Err(RuntimeError::MismatchedValue {
+                        from: start.id,
+                        ty: LinkType::Array(Box::new(start.output.clone())),
+                        value: format!("{value:?}"),
+                    });
+                }
+                None => return Ok(None),
+            },
+            LoopWay::Infinite => None,
+        };
+
+        // 2. ends
+        let ends = body
+            .iter()
+            .filter_map(|c| match c {
+                LinkComponent::LoopEnd(end) if end.metadata.start == start.id => Some(end),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let mut collected: Vec<Vec<LinkValue>> = vec![Vec::new(); ends.len()];
+
+        // 3. rounds
+        let mut round = 0;
+        loop {
+            let item = match &items {
+                Some(items) => match items.get(round) {
+                    Some(item) => item.clone(),
+                    None => break,
+                },
+                None => {
+                    if MAX_LOOP_ROUNDS <= round {
+                        return Err(RuntimeError::LoopRoundsExceeded {
+                            from: start.id,
+                            max: MAX_LOOP_ROUNDS,
+                        });
+                    }
+                    LinkValue::Integer(round as i64)
+                }
+            };
+            round += 1;
+
+            let mut round_values = values.clone();
+            round_values.set(start.id, 0, item);
+            let jump = self.execute_components(body, all_components, &mut round_values)?;
+            values.merge_variables(&round_values); // ? The variables are shared by rounds
+            match jump {
+                Some(LoopJump::Continue(id)) if id == start.id => continue,
+                Some(LoopJump::Break(id)) if id == start.id => break,
+                Some(jump) => return Ok(Some(jump)),
+                None => {}
+            }
+
+            for (end, collected) in ends.iter().zip(collected.iter_mut()) {
+                let endpoint = Endpoint {
+                    id: end.id,
+                    index: None,
+                };
+                if let Some(value) = round_values.get_output(&endpoint) {
+                    collected.push(value.clone());
+                }
+            }
+        }
+
+        // 4. output of ends
+        for (end, collected) in ends.into_iter().zip(collected) {
+            values.set(end.id, 0, into_array(end, collected));
+        }
+
+        Ok(None)
+    }
+}
+
+fn into_array(end: &ComponentLoopEnd, values: Vec<LinkValue>) -> LinkValue {
+    let ty = match &end.output {
+        LinkType::Array(ty) => ty.as_ref().clone(),
+        ty => ty.clone(), // ! Checked, must be array
+    };
+    LinkValue::Array(ArrayLinkValue { ty, values })
+}
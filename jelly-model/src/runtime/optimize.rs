@@ -0,0 +1,261 @@
+use std::collections::{HashMap, HashSet};
+
+use jelly_executor::{executor::Executor, options::ExecuteOptions};
+use serde::Serialize;
+
+use crate::{
+    model::{
+        LinkComponent,
+        check::check,
+        common::{
+            code::{CodeContent, OriginCodeContent},
+            error::LinkError,
+            identity::ComponentId,
+            lets::Endpoint,
+        },
+        components::{
+            code::ComponentCode,
+            constant::{ComponentConst, ConstMetadata},
+        },
+        types::check::{CheckFunction, CheckedCombined},
+    },
+    store::code::{CodeData, anchor::CodeDataAnchor},
+};
+
+use super::{conform, convert, value::RuntimeValues};
+
+/// Remove the sources of time and random before the folded code
+/// ! Only the own executor of optimizer runs it, the shared executor of thread is never changed
+const IMPURE_REMOVED: &str = "delete globalThis.Date; delete Math.random;\n";
+
+/// What the optimizer changed
+#[derive(Debug, Default, Clone, Serialize, PartialEq, Eq)]
+pub struct OptimizeReport {
+    /// The code components folded into constants
+    pub folded: Vec<ComponentId>,
+    /// The components on the statically unreachable branches
+    pub pruned: Vec<ComponentId>,
+    /// The components whose outputs are never consumed
+    pub unused: Vec<ComponentId>,
+}
+
+impl OptimizeReport {
+    /// Whether nothing is changed
+    pub fn is_empty(&self) -> bool {
+        self.folded.is_empty() && self.pruned.is_empty() && self.unused.is_empty()
+    }
+}
+
+/// Optimize the checked combined, the optimized components are checked again
+/// 1. The code whose inputs are all constants is executed and replaced by constant, unless it reads time or random
+/// 2. The branches of condition and switch that can never be output are removed
+/// 3. The components without side effect whose outputs are never consumed are removed
+pub fn optimize<F: CheckFunction>(
+    combined: &CheckedCombined,
+    fetch: &F,
+) -> Result<(CheckedCombined, OptimizeReport), LinkError> {
+    let mut components = combined.components.clone();
+    let mut report = OptimizeReport::default();
+    let mut executor = Executor::default(); // ? The context is created when the first code is folded
+
+    loop {
+        let folded = fold_constants(&mut components, &combined.codes, &mut executor, &mut report);
+        let pruned = prune_branches(&mut components, &mut report);
+        let unused = remove_unused(&mut components, &mut report);
+        if !folded && !pruned && !unused {
+            break;
+        }
+    }
+
+    let optimized = check(&components, fetch)?;
+    Ok((optimized, report))
+}
+
+/// The outputs of all constants
+fn constant_values(components: &[LinkComponent]) -> RuntimeValues {
+    let mut values = RuntimeValues::default();
+    for component in components {
+        if let LinkComponent::Const(constant) = component {
+            values.set(constant.id, 0, constant.metadata.value.clone());
+        }
+    }
+    values
+}
+
+/// Whether all inlets are the output of constants
+fn is_constant_inlets(component: &LinkComponent, constants: &RuntimeValues) -> bool {
+    component
+        .get_inlets()
+        .into_iter()
+        .flatten()
+        .all(|inlet| inlet.index.unwrap_or_default() == 0 && constants.has_component(&inlet.id))
+}
+
+fn fold_constants(
+    components: &mut [LinkComponent],
+    codes: &HashMap<CodeDataAnchor, CodeData>,
+    executor: &mut Executor,
+    report: &mut OptimizeReport,
+) -> bool {
+    let constants = constant_values(components);
+    let mut changed = false;
+    for component in components.iter_mut() {
+        let folded = match &*component {
+            LinkComponent::Code(code) if is_constant_inlets(component, &constants) => {
+                fold_code(code, codes, &constants, executor)
+            }
+            _ => None,
+        };
+        if let Some(folded) = folded {
+            report.folded.push(folded.id);
+            *component = LinkComponent::Const(folded);
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Execute the code, None if it can not be folded
+fn fold_code(
+    code: &ComponentCode,
+    codes: &HashMap<CodeDataAnchor, CodeData>,
+    constants: &RuntimeValues,
+    executor: &mut Executor,
+) -> Option<ComponentConst> {
+    let data = constants.find_data(code.metadata.data.as_deref().unwrap_or_default())?;
+    let js = match &code.metadata.code {
+        CodeContent::Code(OriginCodeContent { js, .. }) => js,
+        CodeContent::Anchor(anchor) => &codes.get(anchor)?.js,
+    };
+    // ? The failed code is kept, it fails at runtime
+    // ? The code which reads time or random is not pure, it fails without them and is kept
+    let data = convert::link_value_to_js_value(&data);
+    let value = executor
+        .execute_code_with_values(
+            &format!("{IMPURE_REMOVED}{js}"),
+            &[("data", Some(&data))],
+            &ExecuteOptions::default(),
+        )
+        .ok()?;
+    let value = conform::conform(&code.output, &value).ok()?;
+    Some(ComponentConst {
+        id: code.id,
+        metadata: ConstMetadata { value },
+        output: code.output.clone(),
+    })
+}
+
+/// The output index of condition or switch whose inputs are all constants
+/// Some(None) means nothing is output
+fn static_output(component: &LinkComponent, constants: &RuntimeValues) -> Option<Option<u32>> {
+    if !is_constant_inlets(component, constants) {
+        return None;
+    }
+    match component {
+        LinkComponent::Condition(condition) => condition.evaluate(constants).ok(),
+        LinkComponent::Switch(switch) => Some(switch.evaluate(constants.find_refer_value(&switch.metadata.value))),
+        _ => None,
+    }
+}
+
+fn prune_branches(components: &mut Vec<LinkComponent>, report: &mut OptimizeReport) -> bool {
+    let constants = constant_values(components);
+    let all_components: HashMap<ComponentId, &LinkComponent> = components.iter().map(|c| (c.id(), c)).collect();
+    let dead = components.iter().find_map(|component| {
+        let live = static_output(component, &constants)?;
+        let dead = find_dead_components(components, &all_components, component.id(), live);
+        // ! If the kept component refers the dead, nothing is removed
+        (!dead.is_empty() && !is_referred(components, &dead)).then_some(dead)
+    });
+    let Some(dead) = dead else {
+        return false;
+    };
+
+    components.retain(|c| !dead.contains(&c.id()));
+    let mut dead = dead.into_iter().collect::<Vec<_>>();
+    dead.sort();
+    report.pruned.extend(dead);
+    true
+}
+
+/// The components can never be ready, when the branch component only outputs the live index
+fn find_dead_components(
+    components: &[LinkComponent],
+    all_components: &HashMap<ComponentId, &LinkComponent>,
+    branch: ComponentId,
+    live: Option<u32>,
+) -> HashSet<ComponentId> {
+    let mut dead = HashSet::new();
+    loop {
+        let mut changed = false;
+        for component in components {
+            let id = component.id();
+            if id == branch || dead.contains(&id) {
+                continue;
+            }
+            let nullable = component.get_nullable_endpoints().unwrap_or_default();
+            let is_dead_endpoint =
+                |inlet: &Endpoint| dead.contains(&inlet.id) || (inlet.id == branch && inlet.index.or(Some(0)) != live);
+
+            // ? The component is ready if any endpoint of each inlet component is output
+            let mut grouped: HashMap<ComponentId, bool> = HashMap::new();
+            for inlet in component.get_inlets().into_iter().flatten() {
+                if nullable.contains(inlet)
+                    || all_components
+                        .get(&inlet.id)
+                        .is_some_and(|c| component.is_loop_back_edge(c))
+                {
+                    continue;
+                }
+                let all_dead = grouped.entry(inlet.id).or_insert(true);
+                *all_dead = *all_dead && is_dead_endpoint(inlet);
+            }
+            if grouped.values().any(|all_dead| *all_dead) {
+                dead.insert(id);
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    dead
+}
+
+/// Whether any kept component refers the removed
+fn is_referred(components: &[LinkComponent], removed: &HashSet<ComponentId>) -> bool {
+    components
+        .iter()
+        .filter(|c| !removed.contains(&c.id()))
+        .any(|c| c.get_references().iter().any(|id| removed.contains(id)))
+}
+
+/// Whether the component can be removed without changing anything if no one refers it
+/// ! The param is the interface of combined, it is never removed
+fn is_pure(component: &LinkComponent) -> bool {
+    matches!(
+        component,
+        LinkComponent::Const(_) | LinkComponent::Code(_) | LinkComponent::Condition(_) | LinkComponent::Switch(_)
+    )
+}
+
+fn remove_unused(components: &mut Vec<LinkComponent>, report: &mut OptimizeReport) -> bool {
+    let referred = components
+        .iter()
+        .flat_map(LinkComponent::get_references)
+        .collect::<HashSet<_>>();
+    let unused = components
+        .iter()
+        .filter(|c| is_pure(c) && !referred.contains(&c.id()))
+        .map(|c| c.id())
+        .collect::<HashSet<_>>();
+    if unused.is_empty() || unused.len() == components.len() {
+        return false; // ? At least one component is kept
+    }
+
+    components.retain(|c| !unused.contains(&c.id()));
+    let mut unused = unused.into_iter().collect::<Vec<_>>();
+    unused.sort();
+    report.unused.extend(unused);
+    true
+}
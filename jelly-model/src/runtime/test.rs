@@ -0,0 +1,1087 @@
+use std::collections::HashMap;
+
+use crate::{
+    model::{
+        check::check,
+        common::{
+            call_trigger::{CallTriggerClick, CallTriggerLoading, ComponentCallTrigger},
+            code::{CodeContent, OriginCodeContent},
+            lets::Endpoint,
+            refer::{CodeValue, InputValue, KeyRefer, NamedValue, ReferValue},
+            types::{LinkType, ObjectSubitem},
+            values::{ArrayLinkValue, LinkValue, ObjectSubitemValue},
+        },
+        components::{
+            LinkComponent,
+            call::{
+                CallMetadata, ComponentCall,
+                http::{CallHttpMetadata, HttpBody, HttpBodyPlain, HttpMethod, ParsedWay},
+            },
+            code::{CodeMetadata, ComponentCode},
+            condition::{
+                ComponentCondition, Condition, ConditionItem, ConditionMatches, ConditionMetadata,
+                number::ConditionNumberCompare, text::ConditionTextCompare,
+            },
+            constant::{ComponentConst, ConstMetadata},
+            form::ComponentForm,
+            loops::{ComponentLoopEnd, ComponentLoopStart, LoopEndMetadata, LoopStartMetadata, LoopWay},
+            param::{ComponentParam, ParamMetadata},
+            switch::{ComponentSwitch, SwitchCase, SwitchMetadata, SwitchPattern},
+            variable::{
+                ComponentVariableAssign, ComponentVariableValue, VariableAssignMetadata, VariableValueMetadata,
+            },
+        },
+        types::check::{CheckFunction, CheckedCombined},
+    },
+    store::code::item::CodeItem,
+};
+
+use super::{
+    CombinedRuntime, RuntimeInputs,
+    call::mock::{CallRequest, MockCallAdapter},
+    conform::conform,
+    error::RuntimeError,
+    execute_code,
+    optimize::{OptimizeReport, optimize},
+};
+
+/// The compiled js is the code itself
+struct MockCallFunction {
+    compiled: HashMap<String, String>,
+}
+
+impl CheckFunction for MockCallFunction {
+    fn canister_id(&self) -> Result<&str, String> {
+        Ok("aaaaa-aa")
+    }
+
+    fn fetch_code(
+        &self,
+        _code_anchor: &crate::store::code::anchor::CodeDataAnchor,
+    ) -> Result<&crate::store::code::CodeData, String> {
+        unreachable!()
+    }
+
+    fn fetch_api(
+        &self,
+        _api_anchor: &crate::store::api::anchor::ApiDataAnchor,
+    ) -> Result<&crate::store::api::ApiData, String> {
+        unreachable!()
+    }
+
+    fn fetch_combined(
+        &self,
+        _combined_anchor: &crate::store::combined::anchor::CombinedAnchor,
+    ) -> Result<&crate::store::combined::Combined, String> {
+        unreachable!()
+    }
+
+    fn fetch_origin_api<'a, 'b: 'a>(&'a self, _key: &'b str) -> Result<&'a str, String> {
+        unreachable!()
+    }
+
+    fn compile_code(&self, item: &CodeItem) -> Result<&str, String> {
+        self.compiled
+            .get(&item.code)
+            .map(|js| js.as_str())
+            .ok_or_else(|| format!("can not find code: {}", item.code))
+    }
+}
+
+fn refer(id: u32, refer: Option<&str>) -> InputValue {
+    InputValue::Refer(ReferValue {
+        endpoint: Endpoint {
+            id: id.into(),
+            index: None,
+        },
+        #[allow(clippy::unwrap_used)] // ? checked
+        refer: refer.map(|r| serde_json::from_str::<KeyRefer>(r).unwrap()),
+    })
+}
+
+fn inlets(inlets: &[u32]) -> Option<Vec<Endpoint>> {
+    Some(
+        inlets
+            .iter()
+            .map(|id| Endpoint {
+                id: (*id).into(),
+                index: None,
+            })
+            .collect(),
+    )
+}
+
+fn origin_code(js: &str) -> CodeContent {
+    CodeContent::Code(OriginCodeContent {
+        code: CodeItem {
+            code: js.into(),
+            args: None,
+            ret: None,
+        },
+        js: "".into(),
+    })
+}
+
+fn code(id: u32, inlets: &[u32], data: Vec<CodeValue>, js: &str, output: LinkType) -> LinkComponent {
+    LinkComponent::Code(ComponentCode {
+        id: id.into(),
+        inlets: self::inlets(inlets),
+        metadata: CodeMetadata {
+            data: Some(data),
+            code: origin_code(js),
+        },
+        output,
+    })
+}
+
+fn check_function(components: &[LinkComponent]) -> MockCallFunction {
+    let compiled = components
+        .iter()
+        .filter_map(|c| match c {
+            LinkComponent::Code(code) => code.metadata.code.get_origin_code(),
+            LinkComponent::Call(ComponentCall {
+                metadata: CallMetadata::Http(http),
+                ..
+            }) => http.post.as_ref().and_then(|post| post.get_origin_code()),
+            _ => None,
+        })
+        .map(|code| (code.clone(), code))
+        .collect();
+    MockCallFunction { compiled }
+}
+
+fn checked(components: &[LinkComponent]) -> CheckedCombined {
+    let checked = check(components, &check_function(components));
+    assert!(checked.is_ok(), "{checked:?}");
+    #[allow(clippy::unwrap_used)] // ? checked
+    checked.unwrap()
+}
+
+fn output(components: Vec<LinkComponent>, data: Vec<CodeValue>, ty: LinkType) -> Vec<LinkComponent> {
+    // ? The output introduces the referred components
+    let inlets = data
+        .iter()
+        .filter_map(|d| match &d.value {
+            InputValue::Refer(refer) => Some(refer.endpoint),
+            InputValue::Const(_) => None,
+        })
+        .collect::<Vec<_>>();
+    let output = serde_json::json!({
+        "id": 100,
+        "inlets": inlets,
+        "metadata": { "data": data },
+        "output": ty,
+    });
+    let mut components = components;
+    #[allow(clippy::unwrap_used)] // ? checked
+    components.push(LinkComponent::Output(serde_json::from_value(output).unwrap()));
+    components
+}
+
+#[test]
+fn test_param_code_output() {
+    let components = output(
+        vec![
+            LinkComponent::Param(ComponentParam {
+                id: 1.into(),
+                metadata: ParamMetadata {
+                    name: "name".into(),
+                    default: Some("Bob".into()),
+                },
+            }),
+            LinkComponent::Const(ComponentConst {
+                id: 2.into(),
+                metadata: ConstMetadata {
+                    value: LinkValue::Integer(3),
+                },
+                output: LinkType::Integer,
+            }),
+            code(
+                3,
+                &[1, 2],
+                vec![
+                    CodeValue {
+                        key: "name".into(),
+                        value: refer(1, None),
+                    },
+                    CodeValue {
+                        key: "times".into(),
+                        value: refer(2, None),
+                    },
+                ],
+                "result = { greeting: `hello ${data.name}`, length: data.name.length * data.times };",
+                LinkType::object_builder()
+                    .push("greeting", LinkType::Text)
+                    .push("length", LinkType::Integer)
+                    .build(),
+            ),
+        ],
+        vec![
+            CodeValue {
+                key: "greeting".into(),
+                value: refer(3, Some(r#"{"key":"greeting"}"#)),
+            },
+            CodeValue {
+                key: "length".into(),
+                value: refer(3, Some(r#"{"key":"length"}"#)),
+            },
+        ],
+        LinkType::Object(vec![
+            ObjectSubitem {
+                key: "greeting".into(),
+                ty: LinkType::Text,
+            },
+            ObjectSubitem {
+                key: "length".into(),
+                ty: LinkType::Integer,
+            },
+        ]),
+    );
+    let checked = checked(&components);
+
+    // default param
+    let result = CombinedRuntime::new(&checked, RuntimeInputs::default(), &MockCallAdapter::default()).execute();
+    assert!(matches!(
+        result.map(|r| r.output),
+        Ok(Some(LinkValue::Object(values))) if values == vec![
+            ObjectSubitemValue { key: "greeting".into(), value: LinkValue::Text("hello Bob".into()) },
+            ObjectSubitemValue { key: "length".into(), value: LinkValue::Integer(9) },
+        ]
+    ));
+
+    // given param
+    let inputs = RuntimeInputs {
+        params: [("name".to_string(), "Anubis".to_string())].into_iter().collect(),
+        ..Default::default()
+    };
+    let result = CombinedRuntime::new(&checked, inputs, &MockCallAdapter::default()).execute();
+    assert!(matches!(
+        result.map(|r| r.output),
+        Ok(Some(LinkValue::Object(values))) if values[1].value == LinkValue::Integer(18)
+    ));
+}
+
+#[test]
+fn test_form_and_mismatched_output() {
+    let components = output(
+        vec![
+            LinkComponent::Form(ComponentForm {
+                id: 1.into(),
+                inlets: None,
+                metadata: None,
+                output: LinkType::Text,
+            }),
+            code(
+                2,
+                &[1],
+                vec![CodeValue {
+                    key: "text".into(),
+                    value: refer(1, None),
+                }],
+                "result = data.text.length;",
+                LinkType::Text,
+            ),
+        ],
+        vec![CodeValue {
+            key: "text".into(),
+            value: refer(2, None),
+        }],
+        LinkType::object_builder().push("text", LinkType::Text).build(),
+    );
+    let checked = checked(&components);
+
+    // The form is not filled, nothing is executed
+    let result = CombinedRuntime::new(&checked, RuntimeInputs::default(), &MockCallAdapter::default()).execute();
+    assert!(matches!(result.map(|r| r.output), Ok(None)));
+
+    // The code output a number, but text is required
+    let inputs = RuntimeInputs {
+        values: [(1.into(), LinkValue::Text("abc".into()))].into_iter().collect(),
+        ..Default::default()
+    };
+    let result = CombinedRuntime::new(&checked, inputs, &MockCallAdapter::default()).execute();
+    assert!(matches!(
+        result,
+        Err(RuntimeError::NonconformingResult { mismatch, .. }) if mismatch.to_string() == "result expected text got number"
+    ));
+}
+
+fn http_call(trigger: ComponentCallTrigger) -> Vec<LinkComponent> {
+    output(
+        vec![
+            LinkComponent::Param(ComponentParam {
+                id: 1.into(),
+                metadata: ParamMetadata {
+                    name: "name".into(),
+                    default: Some("Bob".into()),
+                },
+            }),
+            LinkComponent::Call(ComponentCall {
+                id: 2.into(),
+                inlets: inlets(&[1]),
+                metadata: CallMetadata::Http(CallHttpMetadata {
+                    trigger,
+                    identity: None,
+                    url: InputValue::Const(LinkValue::Text("https://example.com/api".into())),
+                    method: HttpMethod::Post,
+                    headers: None,
+                    body: Some(HttpBody::Plain(HttpBodyPlain {
+                        data: Some(vec![NamedValue {
+                            name: "name".into(),
+                            value: refer(1, None),
+                        }]),
+                    })),
+                    parsed: ParsedWay::Json,
+                    post: Some(origin_code("result = data.count + request_body.name.length;")),
+                }),
+                output: LinkType::Integer,
+            }),
+        ],
+        vec![CodeValue {
+            key: "count".into(),
+            value: refer(2, None),
+        }],
+        LinkType::object_builder().push("count", LinkType::Integer).build(),
+    )
+}
+
+#[test]
+fn test_http_call() {
+    let checked = checked(&http_call(ComponentCallTrigger::Loading(CallTriggerLoading {
+        alive: None,
+    })));
+
+    #[allow(clippy::unwrap_used)] // ? checked
+    let adapter = MockCallAdapter::from_json(
+        r#"[{
+            "request": {"http": {"url": "https://example.com/api", "method": "POST", "body": {"name": "Bob"}, "parsed": "json"}},
+            "response": {"ok": {"body": {"count": 2}, "headers": [["content-type", "application/json"]]}}
+        }]"#,
+    )
+    .unwrap();
+
+    // recorded request
+    let result = CombinedRuntime::new(&checked, RuntimeInputs::default(), &adapter).execute();
+    assert!(matches!(
+        result.map(|r| r.output),
+        Ok(Some(LinkValue::Object(values))) if values[0].value == LinkValue::Integer(5)
+    ));
+    assert!(matches!(&adapter.calls()[..], [CallRequest::Http(request)] if request.url == "https://example.com/api"));
+
+    // unknown request
+    let inputs = RuntimeInputs {
+        params: [("name".to_string(), "Anubis".to_string())].into_iter().collect(),
+        ..Default::default()
+    };
+    let result = CombinedRuntime::new(&checked, inputs, &adapter).execute();
+    assert!(matches!(result, Err(RuntimeError::CallFailed { .. })));
+}
+
+#[test]
+fn test_click_call() {
+    let checked = checked(&http_call(ComponentCallTrigger::Click(CallTriggerClick { text: None })));
+    let adapter = MockCallAdapter::default();
+
+    // Not clicked, nothing is called
+    let result = CombinedRuntime::new(&checked, RuntimeInputs::default(), &adapter).execute();
+    assert!(matches!(result.map(|r| r.output), Ok(None)));
+    assert!(adapter.calls().is_empty());
+
+    // Clicked, the request is sent
+    let inputs = RuntimeInputs {
+        trigger: Some(2.into()),
+        ..Default::default()
+    };
+    let result = CombinedRuntime::new(&checked, inputs, &adapter).execute();
+    assert!(matches!(result, Err(RuntimeError::CallFailed { .. })));
+    assert_eq!(adapter.calls().len(), 1);
+}
+
+#[test]
+fn test_condition() {
+    let mut welcome = code(
+        3,
+        &[1],
+        vec![CodeValue {
+            key: "name".into(),
+            value: refer(1, None),
+        }],
+        "result = `welcome ${data.name}`;",
+        LinkType::Text,
+    );
+    if let LinkComponent::Code(code) = &mut welcome {
+        code.inlets = Some(vec![
+            Endpoint {
+                id: 1.into(),
+                index: None,
+            },
+            Endpoint {
+                id: 2.into(),
+                index: Some(0), // ? The first branch
+            },
+        ]);
+    }
+    let components = output(
+        vec![
+            LinkComponent::Param(ComponentParam {
+                id: 1.into(),
+                metadata: ParamMetadata {
+                    name: "name".into(),
+                    default: Some("Bob".into()),
+                },
+            }),
+            LinkComponent::Condition(ComponentCondition {
+                id: 2.into(),
+                inlets: inlets(&[1]),
+                metadata: ConditionMetadata {
+                    conditions: vec![Condition::Required(ConditionItem {
+                        value: ReferValue {
+                            endpoint: Endpoint {
+                                id: 1.into(),
+                                index: None,
+                            },
+                            refer: None,
+                        },
+                        matches: ConditionMatches::Text(ConditionTextCompare::Equal(InputValue::Const(
+                            LinkValue::Text("Anubis".into()),
+                        ))),
+                    })],
+                },
+            }),
+            welcome,
+        ],
+        vec![CodeValue {
+            key: "welcome".into(),
+            value: refer(3, None),
+        }],
+        LinkType::object_builder().push("welcome", LinkType::Text).build(),
+    );
+    let checked = checked(&components);
+
+    // ELSE branch, nothing is output
+    let result = CombinedRuntime::new(&checked, RuntimeInputs::default(), &MockCallAdapter::default()).execute();
+    assert!(matches!(result.map(|r| r.output), Ok(None)));
+
+    // matched branch
+    let inputs = RuntimeInputs {
+        params: [("name".to_string(), "Anubis".to_string())].into_iter().collect(),
+        ..Default::default()
+    };
+    let result = CombinedRuntime::new(&checked, inputs, &MockCallAdapter::default()).execute();
+    assert!(matches!(
+        result.map(|r| r.output),
+        Ok(Some(LinkValue::Object(values))) if values[0].value == LinkValue::Text("welcome Anubis".into())
+    ));
+}
+
+#[test]
+fn test_switch() {
+    let switch = ComponentSwitch {
+        id: 2.into(),
+        inlets: inlets(&[1]),
+        metadata: SwitchMetadata {
+            value: ReferValue {
+                endpoint: Endpoint {
+                    id: 1.into(),
+                    index: None,
+                },
+                refer: None,
+            },
+            cases: vec![
+                SwitchCase {
+                    name: "admin".into(),
+                    pattern: SwitchPattern::Text(vec!["admin".into(), "root".into()]),
+                },
+                SwitchCase {
+                    name: "user".into(),
+                    pattern: SwitchPattern::Text(vec!["Bob".into()]),
+                },
+            ],
+            default: Some("guest".into()),
+        },
+    };
+    let mut welcome = code(
+        3,
+        &[1],
+        vec![CodeValue {
+            key: "name".into(),
+            value: refer(1, None),
+        }],
+        "result = `welcome ${data.name}`;",
+        LinkType::Text,
+    );
+    if let LinkComponent::Code(code) = &mut welcome {
+        code.inlets = Some(vec![
+            Endpoint {
+                id: 1.into(),
+                index: None,
+            },
+            Endpoint {
+                id: 2.into(),
+                index: Some(2), // ? guest
+            },
+        ]);
+    }
+    let components = output(
+        vec![
+            LinkComponent::Param(ComponentParam {
+                id: 1.into(),
+                metadata: ParamMetadata {
+                    name: "name".into(),
+                    default: Some("Bob".into()),
+                },
+            }),
+            LinkComponent::Switch(switch),
+            welcome,
+        ],
+        vec![CodeValue {
+            key: "welcome".into(),
+            value: refer(3, None),
+        }],
+        LinkType::object_builder().push("welcome", LinkType::Text).build(),
+    );
+    let checked = checked(&components);
+
+    // The case user is matched
+    let result = CombinedRuntime::new(&checked, RuntimeInputs::default(), &MockCallAdapter::default()).execute();
+    assert!(matches!(result.map(|r| r.output), Ok(None)));
+
+    // default
+    let inputs = RuntimeInputs {
+        params: [("name".to_string(), "Anubis".to_string())].into_iter().collect(),
+        ..Default::default()
+    };
+    let result = CombinedRuntime::new(&checked, inputs, &MockCallAdapter::default()).execute();
+    assert!(matches!(
+        result.map(|r| r.output),
+        Ok(Some(LinkValue::Object(values))) if values[0].value == LinkValue::Text("welcome Anubis".into())
+    ));
+}
+
+#[test]
+fn test_optimize() {
+    let data = |id: u32| {
+        vec![CodeValue {
+            key: "x".into(),
+            value: refer(id, None),
+        }]
+    };
+    let branch = |id: u32, index: u32| {
+        let mut component = code(id, &[2], data(2), "result = data.x + 1;", LinkType::Integer);
+        if let LinkComponent::Code(code) = &mut component {
+            code.inlets = Some(vec![
+                Endpoint {
+                    id: 2.into(),
+                    index: None,
+                },
+                Endpoint {
+                    id: 3.into(),
+                    index: Some(index),
+                },
+            ]);
+        }
+        component
+    };
+    let components = output(
+        vec![
+            LinkComponent::Const(ComponentConst {
+                id: 1.into(),
+                metadata: ConstMetadata {
+                    value: LinkValue::Integer(2),
+                },
+                output: LinkType::Integer,
+            }),
+            code(2, &[1], data(1), "result = data.x * 10;", LinkType::Integer),
+            LinkComponent::Condition(ComponentCondition {
+                id: 3.into(),
+                inlets: inlets(&[2]),
+                metadata: ConditionMetadata {
+                    conditions: vec![Condition::Required(ConditionItem {
+                        value: ReferValue {
+                            endpoint: Endpoint {
+                                id: 2.into(),
+                                index: None,
+                            },
+                            refer: None,
+                        },
+                        matches: ConditionMatches::Integer(ConditionNumberCompare::Greater(InputValue::Const(
+                            LinkValue::Integer(5),
+                        ))),
+                    })],
+                },
+            }),
+            branch(4, 0),
+            branch(5, 1), // ? The else branch is never output
+            LinkComponent::Param(ComponentParam {
+                id: 6.into(),
+                metadata: ParamMetadata {
+                    name: "unused".into(),
+                    default: None,
+                },
+            }),
+        ],
+        data(4),
+        LinkType::object_builder().push("x", LinkType::Integer).build(),
+    );
+    let checked = checked(&components);
+
+    let optimized = optimize(&checked, &check_function(&components));
+    assert!(optimized.is_ok(), "{optimized:?}");
+    #[allow(clippy::unwrap_used)] // ? checked
+    let (optimized, report) = optimized.unwrap();
+    assert_eq!(
+        report,
+        OptimizeReport {
+            folded: vec![2.into()],
+            pruned: vec![5.into()],
+            unused: vec![1.into()], // ? The folded constant is unused, the param is kept
+        }
+    );
+    assert_eq!(optimized.components.len(), 5);
+    assert!(matches!(optimized.components[0], LinkComponent::Const(_)));
+
+    // The result is not changed
+    for checked in [&checked, &optimized] {
+        let result = CombinedRuntime::new(checked, RuntimeInputs::default(), &MockCallAdapter::default()).execute();
+        assert!(matches!(
+            result.map(|r| r.output),
+            Ok(Some(LinkValue::Object(values))) if values[0].value == LinkValue::Integer(21)
+        ));
+    }
+
+    // Nothing to optimize
+    let again = optimize(&optimized, &check_function(&optimized.components));
+    assert!(matches!(again, Ok((_, report)) if report.is_empty()));
+}
+
+#[test]
+fn test_optimize_impure() {
+    let data = |id: u32| {
+        vec![CodeValue {
+            key: "x".into(),
+            value: refer(id, None),
+        }]
+    };
+    // The code which reads time or random is not folded
+    let components = output(
+        vec![
+            LinkComponent::Const(ComponentConst {
+                id: 1.into(),
+                metadata: ConstMetadata {
+                    value: LinkValue::Integer(2),
+                },
+                output: LinkType::Integer,
+            }),
+            code(2, &[1], data(1), "result = data.x + Date.now();", LinkType::Integer),
+            code(3, &[1], data(1), "result = data.x + Math.random();", LinkType::Number),
+            code(
+                4,
+                &[1],
+                data(1),
+                "result = data.x + new Date().getTime();",
+                LinkType::Integer,
+            ),
+        ],
+        vec![
+            CodeValue {
+                key: "a".into(),
+                value: refer(2, None),
+            },
+            CodeValue {
+                key: "b".into(),
+                value: refer(3, None),
+            },
+            CodeValue {
+                key: "c".into(),
+                value: refer(4, None),
+            },
+        ],
+        LinkType::object_builder()
+            .push("a", LinkType::Integer)
+            .push("b", LinkType::Number)
+            .push("c", LinkType::Integer)
+            .build(),
+    );
+    let checked = checked(&components);
+    let optimized = optimize(&checked, &check_function(&components));
+    assert!(matches!(optimized, Ok((_, report)) if report.folded.is_empty()));
+
+    // The shared executor still has time and random
+    assert_eq!(
+        jelly_executor::execute_code(r#"result = [typeof Date, typeof Math.random];"#, "[]"),
+        Ok(r#"["function","function"]"#.into())
+    );
+}
+
+#[test]
+fn test_loop() {
+    let array = |values: &[i64]| {
+        LinkValue::Array(ArrayLinkValue {
+            ty: LinkType::Integer,
+            values: values.iter().map(|v| LinkValue::Integer(*v)).collect(),
+        })
+    };
+    let components = output(
+        vec![
+            LinkComponent::Const(ComponentConst {
+                id: 1.into(),
+                metadata: ConstMetadata {
+                    value: array(&[1, 2, 3]),
+                },
+                output: LinkType::Array(Box::new(LinkType::Integer)),
+            }),
+            LinkComponent::LoopStart(ComponentLoopStart {
+                id: 2.into(),
+                inlets: inlets(&[1, 4]), // ? 4 is the back edge
+                metadata: LoopStartMetadata {
+                    way: LoopWay::Iteration(ReferValue {
+                        endpoint: Endpoint {
+                            id: 1.into(),
+                            index: None,
+                        },
+                        refer: None,
+                    }),
+                },
+                output: LinkType::Integer,
+            }),
+            code(
+                3,
+                &[2],
+                vec![CodeValue {
+                    key: "item".into(),
+                    value: refer(2, None),
+                }],
+                "result = data.item * 2;",
+                LinkType::Integer,
+            ),
+            LinkComponent::LoopEnd(ComponentLoopEnd {
+                id: 4.into(),
+                inlets: inlets(&[3]),
+                metadata: LoopEndMetadata {
+                    start: 2.into(),
+                    value: refer(3, None),
+                },
+                output: LinkType::Array(Box::new(LinkType::Integer)),
+            }),
+        ],
+        vec![CodeValue {
+            key: "doubled".into(),
+            value: refer(4, None),
+        }],
+        LinkType::object_builder()
+            .push("doubled", LinkType::Array(Box::new(LinkType::Integer)))
+            .build(),
+    );
+    let checked = checked(&components);
+
+    let result = CombinedRuntime::new(&checked, RuntimeInputs::default(), &MockCallAdapter::default()).execute();
+    assert!(matches!(
+        result.map(|r| r.output),
+        Ok(Some(LinkValue::Object(values))) if values[0].value == array(&[2, 4, 6])
+    ));
+}
+
+#[test]
+fn test_variable() {
+    let array = |values: &[i64]| {
+        LinkValue::Array(ArrayLinkValue {
+            ty: LinkType::Integer,
+            values: values.iter().map(|v| LinkValue::Integer(*v)).collect(),
+        })
+    };
+    let components = output(
+        vec![
+            LinkComponent::Const(ComponentConst {
+                id: 1.into(),
+                metadata: ConstMetadata {
+                    value: array(&[1, 2, 3]),
+                },
+                output: LinkType::Array(Box::new(LinkType::Integer)),
+            }),
+            LinkComponent::LoopStart(ComponentLoopStart {
+                id: 2.into(),
+                inlets: inlets(&[1, 6]), // ? 6 is the back edge
+                metadata: LoopStartMetadata {
+                    way: LoopWay::Iteration(ReferValue {
+                        endpoint: Endpoint {
+                            id: 1.into(),
+                            index: None,
+                        },
+                        refer: None,
+                    }),
+                },
+                output: LinkType::Integer,
+            }),
+            LinkComponent::VariableValue(ComponentVariableValue {
+                id: 3.into(),
+                inlets: inlets(&[2]), // ? Read the variable in each round
+                metadata: VariableValueMetadata {
+                    name: "sum".into(),
+                    default: Some(LinkValue::Integer(0)),
+                },
+                output: LinkType::Integer,
+            }),
+            code(
+                4,
+                &[2, 3],
+                vec![
+                    CodeValue {
+                        key: "item".into(),
+                        value: refer(2, None),
+                    },
+                    CodeValue {
+                        key: "sum".into(),
+                        value: refer(3, None),
+                    },
+                ],
+                "result = data.sum + data.item;",
+                LinkType::Integer,
+            ),
+            LinkComponent::VariableAssign(ComponentVariableAssign {
+                id: 5.into(),
+                inlets: inlets(&[3, 4]),
+                metadata: VariableAssignMetadata {
+                    variable: 3.into(),
+                    value: refer(4, None),
+                },
+            }),
+            LinkComponent::LoopEnd(ComponentLoopEnd {
+                id: 6.into(),
+                inlets: inlets(&[4, 5]),
+                metadata: LoopEndMetadata {
+                    start: 2.into(),
+                    value: refer(4, None),
+                },
+                output: LinkType::Array(Box::new(LinkType::Integer)),
+            }),
+        ],
+        vec![CodeValue {
+            key: "sums".into(),
+            value: refer(6, None),
+        }],
+        LinkType::object_builder()
+            .push("sums", LinkType::Array(Box::new(LinkType::Integer)))
+            .build(),
+    );
+    let checked = checked(&components);
+
+    let result = CombinedRuntime::new(&checked, RuntimeInputs::default(), &MockCallAdapter::default()).execute();
+    assert!(matches!(
+        result.as_ref().map(|r| &r.output),
+        Ok(Some(LinkValue::Object(values))) if values[0].value == array(&[1, 3, 6])
+    ));
+    assert!(matches!(
+        result.as_ref().map(|r| r.values.get_variable(&3.into())),
+        Ok(Some(LinkValue::Integer(6)))
+    ));
+}
+
+#[test]
+fn test_variable_assigned() {
+    let components = output(
+        vec![
+            LinkComponent::Const(ComponentConst {
+                id: 1.into(),
+                metadata: ConstMetadata {
+                    value: LinkValue::Integer(5),
+                },
+                output: LinkType::Integer,
+            }),
+            LinkComponent::VariableValue(ComponentVariableValue {
+                id: 2.into(),
+                inlets: None,
+                metadata: VariableValueMetadata {
+                    name: "value".into(),
+                    default: Some(LinkValue::Integer(0)),
+                },
+                output: LinkType::Integer,
+            }),
+            LinkComponent::VariableAssign(ComponentVariableAssign {
+                id: 3.into(),
+                inlets: inlets(&[1, 2]),
+                metadata: VariableAssignMetadata {
+                    variable: 2.into(),
+                    value: refer(1, None),
+                },
+            }),
+            code(
+                4,
+                &[2, 3],
+                vec![CodeValue {
+                    key: "value".into(),
+                    value: refer(2, None),
+                }],
+                "result = data.value;",
+                LinkType::Integer,
+            ),
+        ],
+        vec![CodeValue {
+            key: "value".into(),
+            value: refer(4, None),
+        }],
+        LinkType::object_builder().push("value", LinkType::Integer).build(),
+    );
+    let checked = checked(&components);
+
+    let result = CombinedRuntime::new(&checked, RuntimeInputs::default(), &MockCallAdapter::default()).execute();
+    assert!(matches!(
+        result.map(|r| r.output),
+        Ok(Some(LinkValue::Object(values))) if values[0].value == LinkValue::Integer(5)
+    ));
+}
+
+#[test]
+fn test_variable_unordered() {
+    let components = output(
+        vec![
+            LinkComponent::Const(ComponentConst {
+                id: 1.into(),
+                metadata: ConstMetadata {
+                    value: LinkValue::Integer(5),
+                },
+                output: LinkType::Integer,
+            }),
+            LinkComponent::VariableValue(ComponentVariableValue {
+                id: 2.into(),
+                inlets: None,
+                metadata: VariableValueMetadata {
+                    name: "value".into(),
+                    default: Some(LinkValue::Integer(0)),
+                },
+                output: LinkType::Integer,
+            }),
+            code(
+                3,
+                &[2], // ? Not ordered after the assign
+                vec![CodeValue {
+                    key: "value".into(),
+                    value: refer(2, None),
+                }],
+                "result = data.value;",
+                LinkType::Integer,
+            ),
+            LinkComponent::VariableAssign(ComponentVariableAssign {
+                id: 4.into(),
+                inlets: inlets(&[1, 2]),
+                metadata: VariableAssignMetadata {
+                    variable: 2.into(),
+                    value: refer(1, None),
+                },
+            }),
+        ],
+        vec![CodeValue {
+            key: "value".into(),
+            value: refer(3, None),
+        }],
+        LinkType::object_builder().push("value", LinkType::Integer).build(),
+    );
+    let checked = checked(&components);
+
+    let result = CombinedRuntime::new(&checked, RuntimeInputs::default(), &MockCallAdapter::default()).execute();
+    assert!(matches!(
+        result.as_ref().map(|r| &r.output),
+        Ok(Some(LinkValue::Object(values))) if values[0].value == LinkValue::Integer(0)
+    ));
+    assert!(matches!(
+        result.as_ref().map(|r| r.values.get_variable(&2.into())),
+        Ok(Some(LinkValue::Integer(5)))
+    ));
+}
+
+#[test]
+fn test_execute_code() {
+    use jelly_executor::error::ExecuteCodeError;
+
+    let args = vec![
+        ("count".to_string(), LinkValue::Integer(2)),
+        ("name".to_string(), LinkValue::Text("a\"b".into())),
+        (
+            "values".to_string(),
+            LinkValue::Array(ArrayLinkValue {
+                ty: LinkType::Integer,
+                values: vec![LinkValue::Integer(1), LinkValue::Integer(2), LinkValue::Integer(3)],
+            }),
+        ),
+    ];
+    let output = LinkType::object_builder()
+        .push("text", LinkType::Text)
+        .push("total", LinkType::Integer)
+        .build();
+    let result = execute_code(
+        "result = { text: name.repeat(count), total: BigInt(values.reduce((a, b) => a + b, 0)) };",
+        &args,
+        &output,
+    );
+    assert!(matches!(
+        &result,
+        Ok(LinkValue::Object(values))
+            if values[0].value == LinkValue::Text("a\"ba\"b".into()) && values[1].value == LinkValue::Integer(6)
+    ));
+
+    // The name is checked
+    let args = vec![("a) => {}; (b".to_string(), LinkValue::Integer(1))];
+    let result = execute_code("result = 1;", &args, &LinkType::Integer);
+    assert!(matches!(result, Err(ExecuteCodeError::InvalidArgs(_))));
+    let args = vec![("class".to_string(), LinkValue::Integer(1))];
+    let result = execute_code("result = 1;", &args, &LinkType::Integer);
+    assert!(matches!(result, Err(ExecuteCodeError::InvalidArgs(_))));
+
+    // The output is checked
+    let result = execute_code("result = 'text';", &[], &LinkType::Integer);
+    assert!(matches!(result, Err(ExecuteCodeError::WrongOutput(_))));
+}
+
+#[test]
+fn test_conform() {
+    let ty = LinkType::object_builder()
+        .push("name", LinkType::Text)
+        .push(
+            "rows",
+            LinkType::Array(Box::new(LinkType::Array(Box::new(LinkType::Text)))),
+        )
+        .push("count", LinkType::Integer)
+        .build();
+    let mismatch = |value: serde_json::Value| conform(&ty, &value).map_err(|e| e.to_string());
+
+    assert!(mismatch(serde_json::json!({ "name": "a", "rows": [["b"]], "count": 1 })).is_ok());
+    assert!(
+        mismatch(serde_json::json!({ "name": "a", "rows": [], "count": { "__open_type__": "bigint", "value": "2" } }))
+            .is_ok()
+    );
+    assert_eq!(
+        mismatch(serde_json::json!({ "name": "a", "rows": [[], [], [], ["b", 1]], "count": 1 })),
+        Err("result.rows[3][1] expected text got number".into())
+    );
+    assert_eq!(
+        mismatch(serde_json::json!({ "name": "a", "rows": [] })),
+        Err("result.count expected integer got undefined".into())
+    );
+    assert_eq!(
+        mismatch(serde_json::json!({ "name": "a", "rows": [], "count": 1.5 })),
+        Err("result.count expected integer got number".into())
+    );
+    assert_eq!(
+        mismatch(serde_json::json!({ "name": "a", "rows": {}, "count": 1 })),
+        Err("result.rows expected array got object".into())
+    );
+    assert_eq!(
+        mismatch(serde_json::json!(null)),
+        Err("result expected object got null".into())
+    );
+
+    // The boundary of integer
+    let integer = |number: f64| conform(&LinkType::Integer, &serde_json::json!(number)).map_err(|e| e.to_string());
+    assert_eq!(
+        integer(9_223_372_036_854_775_808.0),
+        Err("result expected integer got number out of range".into())
+    );
+    assert_eq!(integer(-9_223_372_036_854_775_808.0), Ok(LinkValue::Integer(i64::MIN)));
+    assert_eq!(
+        integer(-9_223_372_036_854_777_856.0),
+        Err("result expected integer got number out of range".into())
+    );
+    assert_eq!(
+        integer(9_223_372_036_854_774_784.0),
+        Ok(LinkValue::Integer(9_223_372_036_854_774_784))
+    );
+}
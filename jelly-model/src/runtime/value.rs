@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use crate::model::{
+    common::{
+        identity::ComponentId,
+        lets::Endpoint,
+        refer::{CodeValue, InputValue, ReferValue},
+        values::{LinkValue, ObjectSubitemValue},
+    },
+    components::condition::ConditionValues,
+};
+
+/// The value of all nodes running
+#[derive(Debug, Default, Clone)]
+pub struct RuntimeValues {
+    /// component id -> output index -> value
+    outputs: HashMap<ComponentId, HashMap<u32, LinkValue>>,
+    /// variable value component id -> assigned value
+    variables: HashMap<ComponentId, LinkValue>,
+}
+
+impl RuntimeValues {
+    /// Whether the component has any output
+    pub fn has_component(&self, id: &ComponentId) -> bool {
+        self.outputs.get(id).is_some_and(|outputs| !outputs.is_empty())
+    }
+
+    /// Whether the endpoint has output
+    pub fn has_output(&self, endpoint: &Endpoint) -> bool {
+        self.get_output(endpoint).is_some()
+    }
+
+    /// Get the output of the endpoint
+    /// The output of variable value is the current value, it may be assigned after it is read
+    pub fn get_output(&self, endpoint: &Endpoint) -> Option<&LinkValue> {
+        let value = self
+            .outputs
+            .get(&endpoint.id)
+            .and_then(|outputs| outputs.get(&endpoint.index.unwrap_or_default()))?;
+        Some(self.variables.get(&endpoint.id).unwrap_or(value))
+    }
+
+    /// Find the value of refer, additional key needs to recursively traversing search
+    pub fn find_refer_value(&self, refer: &ReferValue) -> Option<&LinkValue> {
+        let value = self.get_output(&refer.endpoint)?;
+        match &refer.refer {
+            Some(refer) => refer.get_value(value),
+            None => Some(value),
+        }
+    }
+
+    /// Find the value of input
+    pub fn find_input_value(&self, input: &InputValue) -> Option<LinkValue> {
+        match input {
+            InputValue::Const(constant) => Some(constant.clone()),
+            InputValue::Refer(refer) => self.find_refer_value(refer).cloned(),
+        }
+    }
+
+    /// Find the data object, if any value is missing, the data is missing
+    pub fn find_data(&self, data: &[CodeValue]) -> Option<LinkValue> {
+        let mut values = Vec::with_capacity(data.len());
+        for CodeValue { key, value } in data {
+            values.push(ObjectSubitemValue {
+                key: key.clone(),
+                value: self.find_input_value(value)?,
+            });
+        }
+        Some(LinkValue::Object(values))
+    }
+
+    /// Whether all introduction points are ready
+    /// If a component is introduced with multiple points, any one of them is enough
+    pub fn is_ready(&self, inlets: Option<&Vec<Endpoint>>, nullable: Option<&Vec<Endpoint>>) -> bool {
+        let mut grouped: HashMap<ComponentId, Vec<&Endpoint>> = HashMap::new();
+        for inlet in inlets.into_iter().flatten() {
+            if nullable.is_some_and(|nullable| nullable.contains(inlet)) {
+                continue; // ? Can be empty
+            }
+            grouped.entry(inlet.id).or_default().push(inlet);
+        }
+        grouped
+            .values()
+            .all(|endpoints| endpoints.iter().any(|endpoint| self.has_output(endpoint)))
+    }
+
+    /// Set the output of the component
+    pub(crate) fn set(&mut self, id: ComponentId, index: u32, value: LinkValue) {
+        self.outputs.entry(id).or_default().insert(index, value);
+    }
+
+    /// Get the assigned value of variable
+    pub fn get_variable(&self, id: &ComponentId) -> Option<&LinkValue> {
+        self.variables.get(id)
+    }
+
+    /// Assign the variable
+    pub(crate) fn set_variable(&mut self, id: ComponentId, value: LinkValue) {
+        self.variables.insert(id, value);
+    }
+
+    /// Keep the variables assigned in the loop body
+    pub(crate) fn merge_variables(&mut self, other: &Self) {
+        self.variables
+            .extend(other.variables.iter().map(|(id, value)| (*id, value.clone())));
+    }
+}
+
+impl ConditionValues for RuntimeValues {
+    fn refer_value(&self, refer: &ReferValue) -> Option<LinkValue> {
+        self.find_refer_value(refer).cloned()
+    }
+}
@@ -78,4 +78,9 @@ impl Combined {
             metadata,
         }
     }
+
+    /// The schema version of components
+    pub fn version(&self) -> &str {
+        &self.version
+    }
 }
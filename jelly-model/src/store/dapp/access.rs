@@ -18,25 +18,44 @@ pub mod token_balance;
 /// nft owner
 pub mod nft_owner;
 
+/// signature verification
+pub mod signature;
+
+/// on-chain oracle
+pub mod oracle;
+
+/// times usage
+pub mod times;
+
+/// explain
+pub mod explain;
+
+/// replay protected challenge
+pub mod challenge;
+
+/// test
+#[cfg(test)]
+mod test;
+
 /// Single access permission
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum DappAccessItem {
     /// Restriction visit time
     #[serde(rename = "duration")]
     Duration(duration::AccessDuration),
-    /// Limit the maximum number of visits // ! Don’t know how to achieve it for the time being
+    /// Limit the maximum number of visits
     #[serde(rename = "times")]
     Times(u64),
     /// Restricted tokens
     #[serde(rename = "token")]
     Token(String),
-    /// You can access the specified identity before accessing // ! Don’t know how to achieve it for the time being
+    /// You can access the specified identity before accessing
     #[serde(rename = "chain_identity")]
     ChainIdentity(chain_identity::ChainIdentity),
-    /// Limited to own the amount of tokens to access // ! Don’t know how to achieve it for the time being
+    /// Limited to own the amount of tokens to access
     #[serde(rename = "token_balance")]
     TokenBalance(token_balance::TokenBalance),
-    /// Limited to have NFT to access // ! Don’t know how to achieve it for the time being
+    /// Limited to have NFT to access
     #[serde(rename = "nft_owner")]
     NftOwner(nft_owner::NFTOwner),
 }
@@ -44,20 +63,17 @@ pub enum DappAccessItem {
 impl DappAccessItem {
     /// Simple access request
     pub fn access_by_timestamp_and_token(&self, now: TimestampMills, verified: &DappVerifiedItem) -> Option<bool> {
-        match (self, verified) {
-            (DappAccessItem::Duration(duration), DappVerifiedItem::Duration(verified)) => {
-                if !duration.is_same(verified) {
-                    return None;
-                }
-                Some(duration.access(now))
-            }
-            (DappAccessItem::Times(_), DappVerifiedItem::Times) => None, // ! Don’t know how to achieve it for the time being
-            (DappAccessItem::Token(token), DappVerifiedItem::Token(verified)) => Some(token == verified),
-            (DappAccessItem::ChainIdentity(_), DappVerifiedItem::ChainIdentity(_)) => None, // ! Don’t know how to achieve it for the time being
-            (DappAccessItem::TokenBalance(_), DappVerifiedItem::TokenBalance(_)) => None, // ! Don’t know how to achieve it for the time being
-            (DappAccessItem::NftOwner(_), DappVerifiedItem::NftOwner(_)) => None, // ! Don’t know how to achieve it for the time being
-            _ => None, // ! The type is not right, it is wrong
-        }
+        self.access_with_oracle(now, verified, &oracle::NoOracle)
+    }
+
+    /// Access request with the on-chain facts
+    pub fn access_with_oracle<O: oracle::BalanceOracle + oracle::OwnershipOracle>(
+        &self,
+        now: TimestampMills,
+        verified: &DappVerifiedItem,
+        oracle: &O,
+    ) -> Option<bool> {
+        self.explain(now, verified, oracle).result()
     }
 }
 
@@ -104,83 +120,210 @@ impl Storable for DappAccess {
 }
 
 impl DappAccess {
-    fn inner_access_by_timestamp_and_token(
+    /// The result and what is consumed if the result grants the access
+    fn inner_access<O: oracle::BalanceOracle + oracle::OwnershipOracle>(
         &self,
-        now: TimestampMills,
         verified: Option<&DappVerified>,
-    ) -> Option<bool> {
+        context: &explain::AccessContext<'_, O>,
+    ) -> (Option<bool>, explain::AccessConsumption) {
+        let nothing = |result: Option<bool>| (result, explain::AccessConsumption::default());
+
         // No permission, all successful
         if matches!(self, DappAccess::None) {
-            return Some(true);
+            return nothing(Some(true));
         }
 
         // Complete restrictions on access, all failure
         if matches!(self, DappAccess::Exclusive) {
-            return Some(false);
+            return nothing(Some(false));
         }
 
         // Remove the verification
         let verified = match verified {
             Some(verified) => verified,
-            None => return Some(false),
+            None => return nothing(Some(false)),
         };
 
         match (self, verified) {
             (DappAccess::Required(item), DappVerified::Required(verified)) => {
-                item.access_by_timestamp_and_token(now, verified)
+                inner_access_item(item, verified, context)
             }
             (DappAccess::Deny(item), DappVerified::Deny(verified)) => {
-                item.access_by_timestamp_and_token(now, verified).map(|v| !v)
+                let (result, consumption) = inner_access_item(item, verified, context);
+                (result.map(|v| !v), consumption)
             }
             (DappAccess::All(items), DappVerified::All(verified)) => {
-                'OUTER: for item in items.iter() {
-                    for v in verified.iter() {
-                        if item
-                            .inner_access_by_timestamp_and_token(now, Some(v))
-                            .is_some_and(|v| v)
-                        {
-                            continue 'OUTER;
-                        }
-                    }
-                    return Some(false);
-                }
-                Some(true)
+                let results = items.iter().map(|item| item.inner_access_by_best(verified, context));
+                all_of(results.collect())
             }
             (DappAccess::Any(items), DappVerified::Any(verified)) => {
-                for item in items.iter() {
-                    for v in verified.iter() {
-                        if item
-                            .inner_access_by_timestamp_and_token(now, Some(v))
-                            .is_some_and(|v| v)
-                        {
-                            return Some(true);
-                        }
-                    }
-                }
-                Some(false)
+                let results = items.iter().map(|item| item.inner_access_by_best(verified, context));
+                any_of(results.collect())
             }
             (DappAccess::Not(items), DappVerified::Not(verified)) => {
-                for item in items.iter() {
-                    for v in verified.iter() {
-                        if item
-                            .inner_access_by_timestamp_and_token(now, Some(v))
-                            .is_some_and(|v| v)
-                        {
-                            return Some(false);
-                        }
-                    }
-                }
-                Some(true)
+                let results = items.iter().map(|item| item.inner_access_by_best(verified, context));
+                let (result, consumption) = any_of(results.collect());
+                (result.map(|v| !v), consumption)
+            }
+            _ => nothing(None), // ! The type is not right, it is wrong
+        }
+    }
+
+    /// Satisfied by the best verification of the same shape, passed > failed > unverifiable
+    /// Failed if no verification is for it
+    fn inner_access_by_best<O: oracle::BalanceOracle + oracle::OwnershipOracle>(
+        &self,
+        verified: &[DappVerified],
+        context: &explain::AccessContext<'_, O>,
+    ) -> (Option<bool>, explain::AccessConsumption) {
+        let mut best = None;
+        let mut found = false;
+        for v in verified.iter().filter(|v| self.validate_verified(v).is_ok()) {
+            found = true;
+            match self.inner_access(Some(v), context) {
+                (Some(true), consumption) => return (Some(true), consumption),
+                (Some(false), consumption) if best.is_none() => best = Some(consumption),
+                _ => {}
             }
-            _ => None, // ! The type is not right, it is wrong
+        }
+        match best {
+            Some(consumption) => (Some(false), consumption),
+            None if found => (None, explain::AccessConsumption::default()),
+            None => (Some(false), explain::AccessConsumption::default()),
         }
     }
 
     /// Simple access request
     pub fn access_by_timestamp_and_token(&self, now: TimestampMills, verified: Option<&DappVerified>) -> bool {
-        self.inner_access_by_timestamp_and_token(now, verified)
-            .is_some_and(|v| v)
+        self.access_with_oracle(now, verified, &oracle::NoOracle)
+    }
+
+    /// Access request with the on-chain facts
+    /// The signed items are unverifiable without the challenge, use access_with_challenge instead
+    pub fn access_with_oracle<O: oracle::BalanceOracle + oracle::OwnershipOracle>(
+        &self,
+        now: TimestampMills,
+        verified: Option<&DappVerified>,
+        oracle: &O,
+    ) -> bool {
+        let context = explain::AccessContext::new(now, oracle);
+        self.inner_access(verified, &context).0.is_some_and(|v| v)
+    }
+
+    /// Access request with the on-chain facts and the used times of caller
+    /// If the access is granted by times, it is consumed once
+    /// The signed items are unverifiable without the challenge, use access_with_challenge instead
+    pub fn access_with_usage<O: oracle::BalanceOracle + oracle::OwnershipOracle, S: times::AccessUsageStore>(
+        &self,
+        now: TimestampMills,
+        verified: Option<&DappVerified>,
+        oracle: &O,
+        usage: &mut S,
+        key: &times::AccessUsageKey,
+    ) -> bool {
+        let mut context = explain::AccessContext::new(now, oracle);
+        context.key = Some(key);
+        context.usage = Some(&*usage);
+        let (result, consumption) = self.inner_access(verified, &context);
+        if !result.is_some_and(|v| v) {
+            return false;
+        }
+        match consumption.times.into_iter().min() {
+            // ? Check again when consumed, another request may consume it after checked
+            Some(limit) => usage.consume(key, limit),
+            None => true,
+        }
+    }
+
+    /// Access request with the on-chain facts, the used times of caller and the signed challenges
+    /// The signed message of verification must be the challenge for the dapp and caller,
+    /// the nonce is consumed if the access is granted
+    pub fn access_with_challenge<
+        O: oracle::BalanceOracle + oracle::OwnershipOracle,
+        S: times::AccessUsageStore,
+        N: challenge::ChallengeNonceStore,
+    >(
+        &self,
+        now: TimestampMills,
+        verified: Option<&DappVerified>,
+        oracle: &O,
+        usage: &mut S,
+        key: &times::AccessUsageKey,
+        nonces: &mut N,
+    ) -> bool {
+        let mut context = explain::AccessContext::new(now, oracle);
+        context.key = Some(key);
+        context.usage = Some(&*usage);
+        context.nonces = Some(&*nonces);
+        let (result, consumption) = self.inner_access(verified, &context);
+        if !result.is_some_and(|v| v) {
+            return false;
+        }
+        let (times, challenges) = (consumption.times, consumption.challenges);
+
+        // ? Check again when consumed, another request may consume them after checked
+        // ? The nonces are consumed before the times, the free times is not wasted if it is replayed
+        let mut used = Vec::with_capacity(challenges.len());
+        for challenge in challenges.iter() {
+            if !used.iter().any(|(nonce, _)| *nonce == challenge.nonce.as_str()) {
+                used.push((challenge.nonce.as_str(), challenge.expiry));
+            }
+        }
+        if !nonces.use_nonces(&used) {
+            return false;
+        }
+        match times.into_iter().min() {
+            Some(limit) if !usage.consume(key, limit) => {
+                // ? The access is refused, the nonces can be used again
+                nonces.release_nonces(&used.iter().map(|(nonce, _)| *nonce).collect::<Vec<_>>());
+                false
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Three-valued and, the unverifiable is neither satisfied nor dissatisfied
+/// The consumption is kept only from the children which decide the result
+fn all_of(results: Vec<(Option<bool>, explain::AccessConsumption)>) -> (Option<bool>, explain::AccessConsumption) {
+    if results.iter().any(|(result, _)| result == &Some(false)) {
+        let found = results.into_iter().find(|(result, _)| result == &Some(false));
+        return found.unwrap_or_default();
+    }
+    if results.iter().any(|(result, _)| result.is_none()) {
+        return (None, explain::AccessConsumption::default());
+    }
+    let mut consumption = explain::AccessConsumption::default();
+    for (_, c) in results {
+        consumption.merge(c);
+    }
+    (Some(true), consumption)
+}
+
+/// Three-valued or, the unverifiable is neither satisfied nor dissatisfied
+/// The consumption is kept only from the children which decide the result
+fn any_of(results: Vec<(Option<bool>, explain::AccessConsumption)>) -> (Option<bool>, explain::AccessConsumption) {
+    let (result, consumption) = all_of(results.into_iter().map(|(r, c)| (r.map(|v| !v), c)).collect());
+    (result.map(|v| !v), consumption)
+}
+
+fn inner_access_item<O: oracle::BalanceOracle + oracle::OwnershipOracle>(
+    item: &DappAccessItem,
+    verified: &DappVerifiedItem,
+    context: &explain::AccessContext<'_, O>,
+) -> (Option<bool>, explain::AccessConsumption) {
+    let decision = item.explain_with(verified, context);
+    let mut consumption = explain::AccessConsumption::default();
+    if let (true, DappAccessItem::Times(limit)) = (decision.is_passed(), item) {
+        consumption.times.push(*limit);
+    }
+    // ? The checked challenge is consumed even if it is dissatisfied, it may be denied into a grant
+    if let (Some(_), Some((_, message))) = (decision.result(), verified.signed()) {
+        if let Ok(challenge) = challenge::AccessChallenge::parse(message) {
+            consumption.challenges.push(challenge);
+        }
     }
+    (decision.result(), consumption)
 }
 
 // ================== view ==================
@@ -191,19 +334,19 @@ pub enum DappAccessItemView {
     /// Restriction visit time
     #[serde(rename = "duration")]
     Duration(duration::AccessDuration),
-    /// Limit the maximum number of visits // ! Don’t know how to achieve it for the time being
+    /// Limit the maximum number of visits
     #[serde(rename = "times")]
     Times(u64),
     /// Restricted tokens
     #[serde(rename = "token")]
     Token,
-    /// You can access the specified identity before accessing // ! Don’t know how to achieve it for the time being
+    /// You can access the specified identity before accessing
     #[serde(rename = "chain_identity")]
     ChainIdentity(chain_identity::ChainIdentity),
-    /// Limited to own the amount of tokens to access // ! Don’t know how to achieve it for the time being
+    /// Limited to own the amount of tokens to access
     #[serde(rename = "token_balance")]
     TokenBalance(token_balance::TokenBalance),
-    /// Limited to have NFT to access // ! Don’t know how to achieve it for the time being
+    /// Limited to have NFT to access
     #[serde(rename = "nft_owner")]
     NftOwner(nft_owner::NFTOwner),
 }
@@ -269,19 +412,19 @@ pub enum DappVerifiedItem {
     /// Restriction visit time
     #[serde(rename = "duration")]
     Duration(duration::VerifiedAccessDuration),
-    /// Limit the maximum number of visits // ! Don’t know how to achieve it for the time being
+    /// Limit the maximum number of visits
     #[serde(rename = "times")]
     Times, // No data is required
     /// Restricted tokens
     #[serde(rename = "token")]
     Token(String),
-    /// You can access the specified identity before accessing // ! Don’t know how to achieve it for the time being
+    /// You can access the specified identity before accessing
     #[serde(rename = "chain_identity")]
     ChainIdentity(chain_identity::VerifiedChainIdentity),
-    /// Limited to own the amount of tokens to access // ! Don’t know how to achieve it for the time being
+    /// Limited to own the amount of tokens to access
     #[serde(rename = "token_balance")]
     TokenBalance(token_balance::VerifiedTokenBalance),
-    /// Limited to have NFT to access // ! Don’t know how to achieve it for the time being
+    /// Limited to have NFT to access
     #[serde(rename = "nft_owner")]
     NftOwner(nft_owner::VerifiedNFTOwner),
 }
@@ -1,5 +1,12 @@
 use serde::{Deserialize, Serialize};
 
+use crate::{store::dapp::anchor::DappAnchor, types::TimestampMills};
+
+use super::{
+    challenge::{AccessChallenge, ChallengeError, ChallengeNonceStore},
+    explain::AccessDecision,
+};
+
 /// Chain identity
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct ChainIdentity {
@@ -23,3 +30,42 @@ pub struct VerifiedChainIdentity {
     /// signature
     pub signature: String,
 }
+
+impl ChainIdentity {
+    /// Judgment authority, None if the verification is not for this
+    pub fn access(&self, verified: &VerifiedChainIdentity) -> Option<bool> {
+        self.explain(verified).result()
+    }
+
+    /// Explain the access
+    pub fn explain(&self, verified: &VerifiedChainIdentity) -> AccessDecision {
+        if self.chain != verified.chain
+            || !super::signature::is_same_identity(&self.chain, &self.identity, &verified.identity)
+        {
+            return AccessDecision::unverifiable("chain_identity", "verified identity does not match");
+        }
+        match verified.verify() {
+            Ok(()) => AccessDecision::passed("chain_identity", format!("signed by {}", verified.identity)),
+            // ! The forged signature must not be reversed into a grant by deny or not
+            Err(e) => AccessDecision::unverifiable("chain_identity", e.to_string()),
+        }
+    }
+}
+
+impl VerifiedChainIdentity {
+    /// Verify the message is signed by the identity
+    pub fn verify(&self) -> Result<(), super::signature::SignatureError> {
+        super::signature::verify_signature(&self.chain, &self.identity, &self.message, &self.signature)
+    }
+
+    /// Check the signed message is the challenge for the dapp and caller, the nonce is not consumed
+    pub fn check_challenge(
+        &self,
+        dapp: &DappAnchor,
+        caller: &str,
+        now: TimestampMills,
+        nonces: &dyn ChallengeNonceStore,
+    ) -> Result<AccessChallenge, ChallengeError> {
+        super::challenge::check_challenge(&self.message, dapp, caller, &self.chain, now, nonces)
+    }
+}
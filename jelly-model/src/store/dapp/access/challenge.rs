@@ -0,0 +1,363 @@
+use std::collections::BTreeMap;
+
+use ic_stable_structures::{Memory, StableBTreeMap};
+use serde::{Deserialize, Serialize};
+
+use crate::{store::dapp::anchor::DappAnchor, types::TimestampMills};
+
+use super::chain::Chain;
+
+/// The first line of challenge message
+const HEADER: &str = "jelly access challenge";
+
+/// The fields of challenge message in order
+const FIELDS: [&str; 6] = ["dapp", "caller", "chain", "nonce", "issued", "expiry"];
+
+/// The max valid millis of challenge, the used nonce is kept no longer than it
+pub const MAX_CHALLENGE_TTL: i64 = 10 * 60 * 1000;
+
+/// The challenge signed by identity, the signature can only be used once before expiry
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct AccessChallenge {
+    /// dapp
+    pub dapp: DappAnchor,
+    /// caller who requests the access
+    pub caller: String,
+    /// chain of the identity
+    pub chain: Chain,
+    /// random nonce
+    pub nonce: String,
+    /// Issued time, including
+    pub issued: TimestampMills,
+    /// Expiry time, not included
+    pub expiry: TimestampMills,
+}
+
+/// Error of challenge
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum ChallengeError {
+    /// The message is not canonical challenge
+    InvalidMessage(String),
+    /// The challenge is for another dapp
+    MismatchedDapp(String),
+    /// The challenge is for another caller
+    MismatchedCaller(String),
+    /// The challenge is for another chain
+    MismatchedChain(Chain),
+    /// The challenge is not issued yet
+    NotIssued {
+        /// issued time
+        issued: TimestampMills,
+        /// now
+        now: TimestampMills,
+    },
+    /// The challenge is expired
+    Expired {
+        /// expiry time
+        expiry: TimestampMills,
+        /// now
+        now: TimestampMills,
+    },
+    /// The challenge is valid longer than max ttl
+    InvalidTtl {
+        /// issued time
+        issued: TimestampMills,
+        /// expiry time
+        expiry: TimestampMills,
+    },
+    /// The nonce is used
+    NonceReused(String),
+}
+
+impl core::fmt::Display for ChallengeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChallengeError::InvalidMessage(message) => write!(f, "invalid challenge: {message}"),
+            ChallengeError::MismatchedDapp(dapp) => write!(f, "challenge is for another dapp: {dapp}"),
+            ChallengeError::MismatchedCaller(caller) => write!(f, "challenge is for another caller: {caller}"),
+            ChallengeError::MismatchedChain(chain) => write!(f, "challenge is for another chain: {chain:?}"),
+            ChallengeError::NotIssued { issued, now } => {
+                write!(f, "challenge is issued at {issued}, now is {now}")
+            }
+            ChallengeError::Expired { expiry, now } => write!(f, "challenge expired at {expiry}, now is {now}"),
+            ChallengeError::InvalidTtl { issued, expiry } => {
+                write!(f, "challenge ttl exceeds {MAX_CHALLENGE_TTL}: {issued} to {expiry}")
+            }
+            ChallengeError::NonceReused(nonce) => write!(f, "nonce {nonce} is used"),
+        }
+    }
+}
+
+impl std::error::Error for ChallengeError {}
+
+impl AccessChallenge {
+    /// Generate the challenge which is valid for ttl millis, no more than MAX_CHALLENGE_TTL
+    /// ! The random bytes must be unpredictable, such as raw_rand of management canister
+    pub fn generate(
+        dapp: DappAnchor,
+        caller: String,
+        chain: Chain,
+        now: TimestampMills,
+        ttl: i64,
+        random: &[u8],
+    ) -> Self {
+        Self {
+            dapp,
+            caller,
+            chain,
+            nonce: hex::encode(random),
+            issued: now,
+            expiry: (now + ttl.min(MAX_CHALLENGE_TTL)).into(),
+        }
+    }
+
+    /// Canonical message to be signed
+    pub fn to_message(&self) -> String {
+        let values = [
+            self.dapp.as_ref().clone(),
+            self.caller.clone(),
+            chain_name(&self.chain),
+            self.nonce.clone(),
+            self.issued.to_string(),
+            self.expiry.to_string(),
+        ];
+        let mut message = HEADER.to_string();
+        for (field, value) in FIELDS.iter().zip(values) {
+            message.push_str(&format!("\n{field}: {value}"));
+        }
+        message
+    }
+
+    /// Parse the canonical message
+    pub fn parse(message: &str) -> Result<Self, ChallengeError> {
+        let invalid = |message: &str| ChallengeError::InvalidMessage(message.into());
+        let mut lines = message.split('\n');
+        if lines.next() != Some(HEADER) {
+            return Err(invalid("wrong header"));
+        }
+        let mut values = Vec::with_capacity(FIELDS.len());
+        for field in FIELDS {
+            let value = lines
+                .next()
+                .and_then(|line| line.strip_prefix(field))
+                .and_then(|line| line.strip_prefix(": "))
+                .ok_or_else(|| invalid(&format!("missing {field}")))?;
+            values.push(value);
+        }
+        if lines.next().is_some() {
+            return Err(invalid("unknown line"));
+        }
+        let time = |value: &str| {
+            value
+                .parse::<i64>()
+                .map(TimestampMills::from)
+                .map_err(|_| invalid(&format!("wrong time: {value}")))
+        };
+        let challenge = Self {
+            dapp: DappAnchor::from(values[0].to_string()),
+            caller: values[1].to_string(),
+            chain: serde_json::from_value(serde_json::Value::String(values[2].to_string()))
+                .map_err(|_| invalid(&format!("wrong chain: {}", values[2])))?,
+            nonce: values[3].to_string(),
+            issued: time(values[4])?,
+            expiry: time(values[5])?,
+        };
+        if challenge.nonce.is_empty() {
+            return Err(invalid("empty nonce"));
+        }
+        // ? Only one message for one challenge
+        if challenge.to_message() != message {
+            return Err(invalid("not canonical"));
+        }
+        Ok(challenge)
+    }
+
+    /// Check the challenge is for the request and not expired
+    /// ? The expiry is chosen by the signer, so the long-lived challenge is refused
+    pub fn check(
+        &self,
+        dapp: &DappAnchor,
+        caller: &str,
+        chain: &Chain,
+        now: TimestampMills,
+    ) -> Result<(), ChallengeError> {
+        if &self.dapp != dapp {
+            return Err(ChallengeError::MismatchedDapp(self.dapp.as_ref().clone()));
+        }
+        if self.caller != caller {
+            return Err(ChallengeError::MismatchedCaller(self.caller.clone()));
+        }
+        if &self.chain != chain {
+            return Err(ChallengeError::MismatchedChain(self.chain.clone()));
+        }
+        if self.expiry.as_ref().saturating_sub(*self.issued.as_ref()) > MAX_CHALLENGE_TTL {
+            return Err(ChallengeError::InvalidTtl {
+                issued: self.issued,
+                expiry: self.expiry,
+            });
+        }
+        if now < self.issued {
+            return Err(ChallengeError::NotIssued {
+                issued: self.issued,
+                now,
+            });
+        }
+        if self.expiry <= now {
+            return Err(ChallengeError::Expired {
+                expiry: self.expiry,
+                now,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Parse and check the signed message, the nonce is not consumed
+pub(super) fn check_challenge(
+    message: &str,
+    dapp: &DappAnchor,
+    caller: &str,
+    chain: &Chain,
+    now: TimestampMills,
+    nonces: &dyn ChallengeNonceStore,
+) -> Result<AccessChallenge, ChallengeError> {
+    let challenge = AccessChallenge::parse(message)?;
+    challenge.check(dapp, caller, chain, now)?;
+    if nonces.is_used(&challenge.nonce) {
+        return Err(ChallengeError::NonceReused(challenge.nonce));
+    }
+    Ok(challenge)
+}
+
+/// Verify the signed challenge and consume the nonce
+/// ! The signature of message is not verified here
+pub fn verify_challenge(
+    message: &str,
+    dapp: &DappAnchor,
+    caller: &str,
+    chain: &Chain,
+    now: TimestampMills,
+    nonces: &mut dyn ChallengeNonceStore,
+) -> Result<AccessChallenge, ChallengeError> {
+    let challenge = check_challenge(message, dapp, caller, chain, now, nonces)?;
+    if !nonces.use_nonce(&challenge.nonce, challenge.expiry) {
+        return Err(ChallengeError::NonceReused(challenge.nonce));
+    }
+    Ok(challenge)
+}
+
+/// The store of used nonces, supplied by the caller
+pub trait ChallengeNonceStore {
+    /// Whether the nonce is used
+    fn is_used(&self, nonce: &str) -> bool;
+
+    /// Mark the nonce used until expiry, return false if it is used
+    fn use_nonce(&mut self, nonce: &str, expiry: TimestampMills) -> bool {
+        self.use_nonces(&[(nonce, expiry)])
+    }
+
+    /// Mark all nonces used until expiry, return false and mark nothing if any of them is used
+    /// ! Check and mark must be atomic
+    fn use_nonces(&mut self, nonces: &[(&str, TimestampMills)]) -> bool;
+
+    /// Forget the nonces marked by the refused access
+    fn release_nonces(&mut self, nonces: &[&str]);
+
+    /// Forget the expired nonces, the expired challenge can not be used anyway
+    fn prune(&mut self, now: TimestampMills);
+}
+
+impl ChallengeNonceStore for BTreeMap<String, TimestampMills> {
+    fn is_used(&self, nonce: &str) -> bool {
+        self.contains_key(nonce)
+    }
+
+    fn use_nonces(&mut self, nonces: &[(&str, TimestampMills)]) -> bool {
+        if nonces.iter().any(|(nonce, _)| self.contains_key(*nonce)) {
+            return false;
+        }
+        for (nonce, expiry) in nonces {
+            self.insert(nonce.to_string(), *expiry);
+        }
+        true
+    }
+
+    fn release_nonces(&mut self, nonces: &[&str]) {
+        for nonce in nonces {
+            self.remove(*nonce);
+        }
+    }
+
+    fn prune(&mut self, now: TimestampMills) {
+        self.retain(|_, expiry| now < *expiry);
+    }
+}
+
+/// The store of used nonces in stable memory
+pub struct StableChallengeNonceStore<M: Memory> {
+    /// nonce -> expiry
+    nonces: StableBTreeMap<String, u64, M>,
+}
+
+impl<M: Memory> StableChallengeNonceStore<M> {
+    /// Load or create the store in memory
+    pub fn init(memory: M) -> Self {
+        Self {
+            nonces: StableBTreeMap::init(memory),
+        }
+    }
+
+    /// The number of used nonces
+    pub fn len(&self) -> u64 {
+        self.nonces.len()
+    }
+
+    /// Whether no nonce is used
+    pub fn is_empty(&self) -> bool {
+        self.nonces.is_empty()
+    }
+}
+
+impl<M: Memory> ChallengeNonceStore for StableChallengeNonceStore<M> {
+    fn is_used(&self, nonce: &str) -> bool {
+        self.nonces.contains_key(&nonce.to_string())
+    }
+
+    // ? The canister handles one message at a time, and there is no await between check and insert
+    fn use_nonces(&mut self, nonces: &[(&str, TimestampMills)]) -> bool {
+        if nonces.iter().any(|(nonce, _)| self.is_used(nonce)) {
+            return false;
+        }
+        for (nonce, expiry) in nonces {
+            self.nonces.insert(nonce.to_string(), (*expiry.as_ref()).max(0) as u64);
+        }
+        true
+    }
+
+    fn release_nonces(&mut self, nonces: &[&str]) {
+        for nonce in nonces {
+            self.nonces.remove(&nonce.to_string());
+        }
+    }
+
+    fn prune(&mut self, now: TimestampMills) {
+        let now = (*now.as_ref()).max(0) as u64;
+        let expired = self
+            .nonces
+            .iter()
+            .filter(|(_, expiry)| *expiry <= now)
+            .map(|(nonce, _)| nonce)
+            .collect::<Vec<_>>();
+        for nonce in expired {
+            self.nonces.remove(&nonce);
+        }
+    }
+}
+
+/// The serialized name of chain
+fn chain_name(chain: &Chain) -> String {
+    match serde_json::to_value(chain) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => format!("{chain:?}"),
+    }
+}
@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::types::TimestampMills;
 
+use super::explain::AccessDecision;
+
 /// Visit time
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AccessDuration {
@@ -29,6 +31,24 @@ impl AccessDuration {
         true
     }
 
+    /// Explain the access
+    pub fn explain(&self, now: TimestampMills, verified: &VerifiedAccessDuration) -> AccessDecision {
+        if !self.is_same(verified) {
+            return AccessDecision::unverifiable("duration", "verified duration does not match");
+        }
+        if let Some(start) = self.start {
+            if now < start {
+                return AccessDecision::failed("duration", format!("duration starts at {start}, now is {now}"));
+            }
+        }
+        if let Some(end) = self.end {
+            if end <= now {
+                return AccessDecision::failed("duration", format!("duration ends at {end}, now is {now}"));
+            }
+        }
+        AccessDecision::passed("duration", format!("now is {now}, in duration"))
+    }
+
     /// Whether
     pub fn is_same(&self, verified: &VerifiedAccessDuration) -> bool {
         if self.start != verified.start {
@@ -0,0 +1,527 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::TimestampMills;
+
+use super::{
+    DappAccess, DappAccessItem, DappVerified, DappVerifiedItem,
+    chain::Chain,
+    challenge::{AccessChallenge, ChallengeNonceStore, check_challenge},
+    oracle::{BalanceOracle, NoOracle, OwnershipOracle},
+    times::{AccessUsageKey, AccessUsageStore},
+};
+
+/// The state of access request
+pub(super) struct AccessContext<'a, O> {
+    pub(super) now: TimestampMills,
+    pub(super) oracle: &'a O,
+    /// The dapp and caller of request
+    pub(super) key: Option<&'a AccessUsageKey>,
+    /// The used times of caller
+    pub(super) usage: Option<&'a dyn AccessUsageStore>,
+    /// The used nonces, the signed challenge is checked if it is present
+    pub(super) nonces: Option<&'a dyn ChallengeNonceStore>,
+}
+
+/// What a branch consumes if it decides the access
+#[derive(Debug, Default)]
+pub(super) struct AccessConsumption {
+    /// The limits of satisfied times
+    pub(super) times: Vec<u64>,
+    /// The satisfied challenges
+    pub(super) challenges: Vec<AccessChallenge>,
+}
+
+impl AccessConsumption {
+    pub(super) fn merge(&mut self, other: Self) {
+        self.times.extend(other.times);
+        self.challenges.extend(other.challenges);
+    }
+}
+
+impl<'a, O> AccessContext<'a, O> {
+    pub(super) fn new(now: TimestampMills, oracle: &'a O) -> Self {
+        Self {
+            now,
+            oracle,
+            key: None,
+            usage: None,
+            nonces: None,
+        }
+    }
+}
+
+/// Outcome of access
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AccessOutcome {
+    /// Satisfied
+    #[serde(rename = "passed")]
+    Passed,
+    /// Dissatisfied
+    #[serde(rename = "failed")]
+    Failed,
+    /// Can not be verified, the verification is wrong or the on-chain facts are unknown
+    #[serde(rename = "unverifiable")]
+    Unverifiable,
+}
+
+impl From<Option<bool>> for AccessOutcome {
+    fn from(value: Option<bool>) -> Self {
+        match value {
+            Some(true) => AccessOutcome::Passed,
+            Some(false) => AccessOutcome::Failed,
+            None => AccessOutcome::Unverifiable,
+        }
+    }
+}
+
+/// Decision tree of access
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct AccessDecision {
+    /// The kind of access, same as the serialized name
+    pub node: String,
+    /// outcome
+    pub outcome: AccessOutcome,
+    /// Why
+    pub reason: String,
+    /// The decisions of children
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<AccessDecision>,
+}
+
+impl AccessDecision {
+    /// New leaf decision
+    pub fn new(node: &str, outcome: AccessOutcome, reason: impl Into<String>) -> Self {
+        Self {
+            node: node.into(),
+            outcome,
+            reason: reason.into(),
+            children: vec![],
+        }
+    }
+
+    /// Passed
+    pub fn passed(node: &str, reason: impl Into<String>) -> Self {
+        Self::new(node, AccessOutcome::Passed, reason)
+    }
+
+    /// Failed
+    pub fn failed(node: &str, reason: impl Into<String>) -> Self {
+        Self::new(node, AccessOutcome::Failed, reason)
+    }
+
+    /// Unverifiable
+    pub fn unverifiable(node: &str, reason: impl Into<String>) -> Self {
+        Self::new(node, AccessOutcome::Unverifiable, reason)
+    }
+
+    /// Whether it is passed
+    pub fn is_passed(&self) -> bool {
+        self.outcome == AccessOutcome::Passed
+    }
+
+    /// Same as the result of access request
+    pub fn result(&self) -> Option<bool> {
+        match self.outcome {
+            AccessOutcome::Passed => Some(true),
+            AccessOutcome::Failed => Some(false),
+            AccessOutcome::Unverifiable => None,
+        }
+    }
+}
+
+/// The shape of verification does not match access
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct AccessMismatch {
+    /// The path of verification, such as /all/1/required
+    pub path: String,
+    /// The expected kinds
+    pub expected: Vec<String>,
+    /// The kind of verification
+    pub found: String,
+}
+
+impl core::fmt::Display for AccessMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "verification at {} should be {}, but found {}",
+            if self.path.is_empty() { "/" } else { &self.path },
+            self.expected.join(" or "),
+            self.found
+        )
+    }
+}
+
+impl std::error::Error for AccessMismatch {}
+
+impl DappAccessItem {
+    /// The serialized name
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DappAccessItem::Duration(_) => "duration",
+            DappAccessItem::Times(_) => "times",
+            DappAccessItem::Token(_) => "token",
+            DappAccessItem::ChainIdentity(_) => "chain_identity",
+            DappAccessItem::TokenBalance(_) => "token_balance",
+            DappAccessItem::NftOwner(_) => "nft_owner",
+        }
+    }
+
+    /// Explain the access request with the on-chain facts
+    pub fn explain<O: BalanceOracle + OwnershipOracle>(
+        &self,
+        now: TimestampMills,
+        verified: &DappVerifiedItem,
+        oracle: &O,
+    ) -> AccessDecision {
+        self.explain_with(verified, &AccessContext::new(now, oracle))
+    }
+
+    pub(super) fn explain_with<O: BalanceOracle + OwnershipOracle>(
+        &self,
+        verified: &DappVerifiedItem,
+        context: &AccessContext<'_, O>,
+    ) -> AccessDecision {
+        let (now, oracle) = (context.now, context.oracle);
+        let node = self.kind();
+        let decision = match (self, verified) {
+            (DappAccessItem::Duration(duration), DappVerifiedItem::Duration(verified)) => {
+                duration.explain(now, verified)
+            }
+            (DappAccessItem::Times(limit), DappVerifiedItem::Times) => match (context.usage, context.key) {
+                (Some(usage), Some(key)) => {
+                    let used = usage.used(key);
+                    if used < *limit {
+                        AccessDecision::passed(node, format!("used {used} of {limit} times"))
+                    } else {
+                        AccessDecision::failed(node, format!("used up {limit} times"))
+                    }
+                }
+                _ => AccessDecision::unverifiable(node, "used times is unknown"),
+            },
+            (DappAccessItem::Token(token), DappVerifiedItem::Token(verified)) => {
+                if token == verified {
+                    AccessDecision::passed(node, "token matched")
+                } else {
+                    AccessDecision::failed(node, "token mismatched")
+                }
+            }
+            (DappAccessItem::ChainIdentity(identity), DappVerifiedItem::ChainIdentity(verified)) => {
+                identity.explain(verified)
+            }
+            (DappAccessItem::TokenBalance(balance), DappVerifiedItem::TokenBalance(verified)) => {
+                balance.explain(verified, oracle)
+            }
+            (DappAccessItem::NftOwner(owner), DappVerifiedItem::NftOwner(verified)) => owner.explain(verified, oracle),
+            _ => AccessDecision::unverifiable(node, format!("verified {} does not match {}", verified.kind(), node)),
+        };
+
+        // ? The signature is wrong or the facts are unknown, nothing to check
+        let Some((chain, message)) = verified.signed() else {
+            return decision;
+        };
+        if decision.outcome == AccessOutcome::Unverifiable {
+            return decision;
+        }
+        // ! The signed message may be replayed without the nonces, it proves nothing
+        // ! The wrong challenge must not be reversed into a grant by deny or not
+        match (context.nonces, context.key) {
+            (Some(nonces), Some(key)) => match check_challenge(message, &key.dapp, &key.caller, chain, now, nonces) {
+                Ok(_) => decision,
+                Err(e) => AccessDecision::unverifiable(node, e.to_string()),
+            },
+            _ => AccessDecision::unverifiable(node, "signed challenge is not checked"),
+        }
+    }
+}
+
+impl DappVerifiedItem {
+    /// The serialized name
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DappVerifiedItem::Duration(_) => "duration",
+            DappVerifiedItem::Times => "times",
+            DappVerifiedItem::Token(_) => "token",
+            DappVerifiedItem::ChainIdentity(_) => "chain_identity",
+            DappVerifiedItem::TokenBalance(_) => "token_balance",
+            DappVerifiedItem::NftOwner(_) => "nft_owner",
+        }
+    }
+
+    /// The chain and signed message
+    pub fn signed(&self) -> Option<(&Chain, &str)> {
+        match self {
+            DappVerifiedItem::ChainIdentity(verified) => Some((&verified.chain, &verified.message)),
+            DappVerifiedItem::TokenBalance(verified) => Some((&verified.chain, &verified.message)),
+            DappVerifiedItem::NftOwner(verified) => Some((&verified.chain, &verified.message)),
+            _ => None,
+        }
+    }
+}
+
+impl DappVerified {
+    /// The serialized name
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DappVerified::None => "none",
+            DappVerified::Exclusive => "exclusive",
+            DappVerified::Required(_) => "required",
+            DappVerified::Deny(_) => "deny",
+            DappVerified::All(_) => "all",
+            DappVerified::Any(_) => "any",
+            DappVerified::Not(_) => "not",
+        }
+    }
+}
+
+impl DappAccess {
+    /// The serialized name
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DappAccess::None => "none",
+            DappAccess::Exclusive => "exclusive",
+            DappAccess::Required(_) => "required",
+            DappAccess::Deny(_) => "deny",
+            DappAccess::All(_) => "all",
+            DappAccess::Any(_) => "any",
+            DappAccess::Not(_) => "not",
+        }
+    }
+
+    /// Explain the simple access request
+    pub fn explain_by_timestamp_and_token(
+        &self,
+        now: TimestampMills,
+        verified: Option<&DappVerified>,
+    ) -> AccessDecision {
+        self.inner_explain(verified, &AccessContext::new(now, &NoOracle))
+    }
+
+    /// Explain the access request with the on-chain facts
+    pub fn explain_with_oracle<O: BalanceOracle + OwnershipOracle>(
+        &self,
+        now: TimestampMills,
+        verified: Option<&DappVerified>,
+        oracle: &O,
+    ) -> AccessDecision {
+        self.inner_explain(verified, &AccessContext::new(now, oracle))
+    }
+
+    /// Explain the access request with the on-chain facts and the used times of caller
+    /// ! Nothing is consumed
+    pub fn explain_with_usage<O: BalanceOracle + OwnershipOracle, S: AccessUsageStore>(
+        &self,
+        now: TimestampMills,
+        verified: Option<&DappVerified>,
+        oracle: &O,
+        usage: &S,
+        key: &AccessUsageKey,
+    ) -> AccessDecision {
+        let mut context = AccessContext::new(now, oracle);
+        context.key = Some(key);
+        context.usage = Some(usage);
+        self.inner_explain(verified, &context)
+    }
+
+    /// Explain the access request with the on-chain facts, the used times of caller and the signed challenges
+    /// ! Nothing is consumed
+    pub fn explain_with_challenge<O: BalanceOracle + OwnershipOracle, S: AccessUsageStore, N: ChallengeNonceStore>(
+        &self,
+        now: TimestampMills,
+        verified: Option<&DappVerified>,
+        oracle: &O,
+        usage: &S,
+        key: &AccessUsageKey,
+        nonces: &N,
+    ) -> AccessDecision {
+        let mut context = AccessContext::new(now, oracle);
+        context.key = Some(key);
+        context.usage = Some(usage);
+        context.nonces = Some(nonces);
+        self.inner_explain(verified, &context)
+    }
+
+    fn inner_explain<O: BalanceOracle + OwnershipOracle>(
+        &self,
+        verified: Option<&DappVerified>,
+        context: &AccessContext<'_, O>,
+    ) -> AccessDecision {
+        let node = self.kind();
+        let verified = match (self, verified) {
+            (DappAccess::None, _) => return AccessDecision::passed(node, "unlimited access"),
+            (DappAccess::Exclusive, _) => return AccessDecision::failed(node, "only accessible by admin"),
+            (_, None) => return AccessDecision::failed(node, "verification is missing"),
+            (_, Some(verified)) => verified,
+        };
+
+        let with_children = |mut decision: AccessDecision, children: Vec<AccessDecision>| {
+            decision.children = children;
+            decision
+        };
+        // ? Each item is satisfied by any verification of the same shape, passed > failed > unverifiable
+        let explain_items = |items: &[DappAccess], verified: &[DappVerified]| {
+            items
+                .iter()
+                .map(|item| {
+                    let mut decisions = verified
+                        .iter()
+                        .filter(|v| item.validate_verified(v).is_ok())
+                        .map(|v| item.inner_explain(Some(v), context))
+                        .collect::<Vec<_>>();
+                    let chosen = [
+                        AccessOutcome::Passed,
+                        AccessOutcome::Failed,
+                        AccessOutcome::Unverifiable,
+                    ]
+                    .iter()
+                    .find_map(|outcome| decisions.iter().position(|d| d.outcome == *outcome));
+                    match chosen {
+                        Some(chosen) => decisions.swap_remove(chosen),
+                        None => AccessDecision::failed(item.kind(), "verification is missing"),
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+        let count = |children: &[AccessDecision], outcome: AccessOutcome| {
+            children.iter().filter(|c| c.outcome == outcome).count()
+        };
+
+        match (self, verified) {
+            (DappAccess::Required(item), DappVerified::Required(verified)) => {
+                let decision = item.explain_with(verified, context);
+                with_children(
+                    AccessDecision::new(node, decision.outcome, "must be satisfied"),
+                    vec![decision],
+                )
+            }
+            (DappAccess::Deny(item), DappVerified::Deny(verified)) => {
+                let decision = item.explain_with(verified, context);
+                let outcome = AccessOutcome::from(decision.result().map(|v| !v));
+                with_children(
+                    AccessDecision::new(node, outcome, "must be dissatisfied"),
+                    vec![decision],
+                )
+            }
+            (DappAccess::All(items), DappVerified::All(verified)) => {
+                let children = explain_items(items, verified);
+                let passed = count(&children, AccessOutcome::Passed);
+                let unverifiable = count(&children, AccessOutcome::Unverifiable);
+                let decision = if passed == children.len() {
+                    AccessDecision::passed(node, format!("all {passed} satisfied"))
+                } else if passed + unverifiable < children.len() {
+                    AccessDecision::failed(node, format!("{passed} of {} satisfied, all required", children.len()))
+                } else {
+                    AccessDecision::unverifiable(
+                        node,
+                        format!("{unverifiable} of {} unverifiable, all required", children.len()),
+                    )
+                };
+                with_children(decision, children)
+            }
+            (DappAccess::Any(items), DappVerified::Any(verified)) => {
+                let children = explain_items(items, verified);
+                let passed = count(&children, AccessOutcome::Passed);
+                let unverifiable = count(&children, AccessOutcome::Unverifiable);
+                let decision = if 0 < passed {
+                    AccessDecision::passed(node, format!("{passed} of {} satisfied", children.len()))
+                } else if 0 < unverifiable {
+                    AccessDecision::unverifiable(
+                        node,
+                        format!("{unverifiable} of {} unverifiable, any required", children.len()),
+                    )
+                } else {
+                    AccessDecision::failed(node, "none satisfied, any required")
+                };
+                with_children(decision, children)
+            }
+            (DappAccess::Not(items), DappVerified::Not(verified)) => {
+                let children = explain_items(items, verified);
+                let passed = count(&children, AccessOutcome::Passed);
+                let unverifiable = count(&children, AccessOutcome::Unverifiable);
+                // ? The unverifiable is not dissatisfied, or the forged verification is reversed into a grant
+                let decision = if 0 < passed {
+                    AccessDecision::failed(node, format!("{passed} of {} satisfied, none allowed", children.len()))
+                } else if 0 < unverifiable {
+                    AccessDecision::unverifiable(
+                        node,
+                        format!("{unverifiable} of {} unverifiable, none allowed", children.len()),
+                    )
+                } else {
+                    AccessDecision::passed(node, "none satisfied")
+                };
+                with_children(decision, children)
+            }
+            _ => AccessDecision::unverifiable(node, format!("verified {} does not match {}", verified.kind(), node)),
+        }
+    }
+
+    /// Check the shape of verification matches the access tree
+    pub fn validate_verified(&self, verified: &DappVerified) -> Result<(), AccessMismatch> {
+        self.inner_validate_verified(verified, String::new())
+    }
+
+    fn inner_validate_verified(&self, verified: &DappVerified, path: String) -> Result<(), AccessMismatch> {
+        let mismatch = |path: String, expected: Vec<&str>, found: &str| AccessMismatch {
+            path,
+            expected: expected.into_iter().map(|e| e.to_string()).collect(),
+            found: found.into(),
+        };
+        let node = self.kind();
+        if node != verified.kind() {
+            return Err(mismatch(path, vec![node], verified.kind()));
+        }
+        let path = format!("{path}/{node}");
+        match (self, verified) {
+            (DappAccess::Required(item), DappVerified::Required(verified))
+            | (DappAccess::Deny(item), DappVerified::Deny(verified)) => {
+                if item.kind() != verified.kind() {
+                    return Err(mismatch(path, vec![item.kind()], verified.kind()));
+                }
+                Ok(())
+            }
+            (DappAccess::All(items), DappVerified::All(verified))
+            | (DappAccess::Any(items), DappVerified::Any(verified))
+            | (DappAccess::Not(items), DappVerified::Not(verified)) => {
+                for (i, v) in verified.iter().enumerate() {
+                    let path = format!("{path}/{i}");
+                    let results = items
+                        .iter()
+                        .map(|item| item.inner_validate_verified(v, path.clone()))
+                        .collect::<Vec<_>>();
+                    if results.iter().any(|r| r.is_ok()) {
+                        continue;
+                    }
+                    // ? Report the deeper mismatch if the items of the same kind are mismatched at the same place
+                    let deeper = items
+                        .iter()
+                        .zip(results)
+                        .filter(|(item, _)| item.kind() == v.kind())
+                        .filter_map(|(_, r)| r.err())
+                        .reduce(|mut merged, e| {
+                            if merged.path != e.path || merged.found != e.found {
+                                merged.path.clear(); // ? different places
+                            }
+                            for expected in e.expected {
+                                if !merged.expected.contains(&expected) {
+                                    merged.expected.push(expected);
+                                }
+                            }
+                            merged
+                        });
+                    if let Some(deeper) = deeper.filter(|e| !e.path.is_empty()) {
+                        return Err(deeper);
+                    }
+                    let mut expected = vec![];
+                    for item in items {
+                        if !expected.contains(&item.kind()) {
+                            expected.push(item.kind());
+                        }
+                    }
+                    return Err(mismatch(path, expected, v.kind()));
+                }
+                Ok(())
+            }
+            _ => Ok(()), // ? none and exclusive
+        }
+    }
+}
@@ -1,5 +1,12 @@
 use serde::{Deserialize, Serialize};
 
+use crate::{store::dapp::anchor::DappAnchor, types::TimestampMills};
+
+use super::{
+    challenge::{AccessChallenge, ChallengeError, ChallengeNonceStore},
+    explain::AccessDecision,
+};
+
 /// NFT owner
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct NFTOwner {
@@ -31,3 +38,52 @@ pub struct VerifiedNFTOwner {
     /// signature
     pub signature: String,
 }
+
+impl NFTOwner {
+    /// Whether
+    pub fn is_same(&self, verified: &VerifiedNFTOwner) -> bool {
+        self.chain == verified.chain && self.address == verified.address && self.token_id == verified.token_id
+    }
+
+    /// Judgment authority, None if the verification is not for this or the owner is unknown
+    pub fn access<O: super::oracle::OwnershipOracle>(&self, verified: &VerifiedNFTOwner, oracle: &O) -> Option<bool> {
+        self.explain(verified, oracle).result()
+    }
+
+    /// Explain the access
+    pub fn explain<O: super::oracle::OwnershipOracle>(
+        &self,
+        verified: &VerifiedNFTOwner,
+        oracle: &O,
+    ) -> AccessDecision {
+        if !self.is_same(verified) {
+            return AccessDecision::unverifiable("nft_owner", "verified NFT does not match");
+        }
+        if let Err(e) = verified.verify() {
+            return AccessDecision::unverifiable("nft_owner", e.to_string());
+        }
+        match oracle.owns(&self.chain, &self.address, self.token_id.as_deref(), &verified.identity) {
+            Some(true) => AccessDecision::passed("nft_owner", format!("{} owns the NFT", verified.identity)),
+            Some(false) => AccessDecision::failed("nft_owner", format!("{} does not own the NFT", verified.identity)),
+            None => AccessDecision::unverifiable("nft_owner", "owner is unknown"),
+        }
+    }
+}
+
+impl VerifiedNFTOwner {
+    /// Verify the message is signed by the identity
+    pub fn verify(&self) -> Result<(), super::signature::SignatureError> {
+        super::signature::verify_signature(&self.chain, &self.identity, &self.message, &self.signature)
+    }
+
+    /// Check the signed message is the challenge for the dapp and caller, the nonce is not consumed
+    pub fn check_challenge(
+        &self,
+        dapp: &DappAnchor,
+        caller: &str,
+        now: TimestampMills,
+        nonces: &dyn ChallengeNonceStore,
+    ) -> Result<AccessChallenge, ChallengeError> {
+        super::challenge::check_challenge(&self.message, dapp, caller, &self.chain, now, nonces)
+    }
+}
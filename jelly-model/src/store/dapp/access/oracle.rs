@@ -0,0 +1,31 @@
+use super::chain::Chain;
+
+/// The on-chain balance of tokens
+pub trait BalanceOracle {
+    /// The balance of the identity, main currency if address is none
+    /// None if it is unknown
+    fn balance(&self, chain: &Chain, address: Option<&str>, identity: &str) -> Option<u64>;
+}
+
+/// The on-chain owner of NFT
+pub trait OwnershipOracle {
+    /// Whether the identity owns the NFT, any NFT of the contract if token_id is none
+    /// None if it is unknown
+    fn owns(&self, chain: &Chain, address: &str, token_id: Option<&str>, identity: &str) -> Option<bool>;
+}
+
+/// Know nothing about the chain
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoOracle;
+
+impl BalanceOracle for NoOracle {
+    fn balance(&self, _chain: &Chain, _address: Option<&str>, _identity: &str) -> Option<u64> {
+        None
+    }
+}
+
+impl OwnershipOracle for NoOracle {
+    fn owns(&self, _chain: &Chain, _address: &str, _token_id: Option<&str>, _identity: &str) -> Option<bool> {
+        None
+    }
+}
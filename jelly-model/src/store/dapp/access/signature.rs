@@ -0,0 +1,171 @@
+use candid::Principal;
+use serde::{Deserialize, Serialize};
+
+use super::chain::Chain;
+
+/// DER prefix of ed25519 public key
+const ED25519_DER_PREFIX: [u8; 12] = [0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00];
+
+/// DER prefix of secp256k1 public key
+const SECP256K1_DER_PREFIX: [u8; 23] = [
+    0x30, 0x56, 0x30, 0x10, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x05, 0x2b, 0x81, 0x04, 0x00,
+    0x0a, 0x03, 0x42, 0x00,
+];
+
+/// Error of signature verification
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum SignatureError {
+    /// The chain is not supported
+    UnsupportedChain(Chain),
+    /// The identity is wrong
+    InvalidIdentity(String),
+    /// The signature can not be parsed
+    InvalidSignature(String),
+    /// The signer is not the identity
+    MismatchedSigner {
+        /// The claimed identity
+        identity: String,
+        /// The real signer
+        signer: String,
+    },
+}
+
+impl core::fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignatureError::UnsupportedChain(chain) => write!(f, "unsupported chain: {chain:?}"),
+            SignatureError::InvalidIdentity(identity) => write!(f, "invalid identity: {identity}"),
+            SignatureError::InvalidSignature(message) => write!(f, "invalid signature: {message}"),
+            SignatureError::MismatchedSigner { identity, signer } => {
+                write!(f, "signer {signer} is not identity {identity}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SignatureError {}
+
+/// Verify the message is signed by the identity of the chain
+/// evm: the signature of personal_sign, 0x prefixed hex of r || s || v
+/// ic: hex of DER public key and hex of signature joined by `:`, the identity must be self-authenticating
+pub fn verify_signature(chain: &Chain, identity: &str, message: &str, signature: &str) -> Result<(), SignatureError> {
+    match chain {
+        Chain::Bitcoin => Err(SignatureError::UnsupportedChain(chain.clone())),
+        Chain::InternetComputer => verify_ic_signature(identity, message, signature),
+        Chain::Ethereum
+        | Chain::EthereumTestnetSepolia
+        | Chain::BinanceSmartChain
+        | Chain::BinanceSmartChainTestnet
+        | Chain::HashKeyChain
+        | Chain::HashKeyChainTestnet
+        | Chain::Polygon
+        | Chain::PolygonTestnetAmoy => {
+            let identity = normalize_evm_address(identity)?;
+            let signer = recover_eip191(message, signature)?;
+            if signer != identity {
+                return Err(SignatureError::MismatchedSigner { identity, signer });
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Whether the identities of the chain are the same, the evm address is case insensitive
+pub fn is_same_identity(chain: &Chain, identity1: &str, identity2: &str) -> bool {
+    match chain {
+        Chain::Bitcoin | Chain::InternetComputer => identity1 == identity2,
+        _ => match (normalize_evm_address(identity1), normalize_evm_address(identity2)) {
+            (Ok(identity1), Ok(identity2)) => identity1 == identity2,
+            _ => false,
+        },
+    }
+}
+
+/// Hash of EIP-191 personal message
+pub fn hash_eip191(message: &str) -> [u8; 32] {
+    use sha3::Digest;
+    let mut hasher = sha3::Keccak256::new();
+    hasher.update(format!("\x19Ethereum Signed Message:\n{}", message.len()).as_bytes());
+    hasher.update(message.as_bytes());
+    hasher.finalize().into()
+}
+
+/// Recover the lowercase 0x address from the signature of personal_sign
+pub fn recover_eip191(message: &str, signature: &str) -> Result<String, SignatureError> {
+    use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+
+    let invalid = |message: &str| SignatureError::InvalidSignature(message.into());
+    let bytes = decode_hex(signature).ok_or_else(|| invalid("signature must be hex"))?;
+    if bytes.len() != 65 {
+        return Err(invalid("signature must be 65 bytes"));
+    }
+    let recovery_id = match bytes[64] {
+        0 | 27 => 0,
+        1 | 28 => 1,
+        _ => return Err(invalid("wrong recovery id")),
+    };
+    let recovery_id = RecoveryId::from_byte(recovery_id).ok_or_else(|| invalid("wrong recovery id"))?;
+    let signature = Signature::from_slice(&bytes[..64]).map_err(|e| invalid(&e.to_string()))?;
+    let key = VerifyingKey::recover_from_prehash(&hash_eip191(message), &signature, recovery_id)
+        .map_err(|e| invalid(&e.to_string()))?;
+    Ok(evm_address(&key))
+}
+
+/// Lowercase 0x address of the public key
+fn evm_address(key: &k256::ecdsa::VerifyingKey) -> String {
+    use sha3::Digest;
+    let point = key.to_encoded_point(false);
+    let hash = sha3::Keccak256::digest(&point.as_bytes()[1..]);
+    format!("0x{}", hex::encode(&hash[12..]))
+}
+
+fn normalize_evm_address(address: &str) -> Result<String, SignatureError> {
+    match decode_hex(address) {
+        Some(bytes) if bytes.len() == 20 => Ok(format!("0x{}", hex::encode(bytes))),
+        _ => Err(SignatureError::InvalidIdentity(address.into())),
+    }
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    hex::decode(value.strip_prefix("0x").unwrap_or(value)).ok()
+}
+
+/// Verify the ic signature of self-authenticating principal, ed25519 and secp256k1 are supported
+pub fn verify_ic_signature(identity: &str, message: &str, signature: &str) -> Result<(), SignatureError> {
+    let invalid = |message: &str| SignatureError::InvalidSignature(message.into());
+    let principal = Principal::from_text(identity).map_err(|_| SignatureError::InvalidIdentity(identity.into()))?;
+    let (public_key, signature) = signature
+        .split_once(':')
+        .ok_or_else(|| invalid("signature must be public key and signature joined by `:`"))?;
+    let public_key = decode_hex(public_key).ok_or_else(|| invalid("public key must be hex"))?;
+    let signature = decode_hex(signature).ok_or_else(|| invalid("signature must be hex"))?;
+
+    let signer = Principal::self_authenticating(&public_key);
+    if signer != principal {
+        return Err(SignatureError::MismatchedSigner {
+            identity: identity.into(),
+            signer: signer.to_text(),
+        });
+    }
+
+    if let Some(key) = public_key.strip_prefix(&ED25519_DER_PREFIX[..]) {
+        use ed25519_dalek::Verifier;
+        let key = <[u8; 32]>::try_from(key).map_err(|_| invalid("wrong ed25519 public key"))?;
+        let key = ed25519_dalek::VerifyingKey::from_bytes(&key).map_err(|e| invalid(&e.to_string()))?;
+        let signature = ed25519_dalek::Signature::from_slice(&signature).map_err(|e| invalid(&e.to_string()))?;
+        return key
+            .verify(message.as_bytes(), &signature)
+            .map_err(|e| invalid(&e.to_string()));
+    }
+
+    if let Some(key) = public_key.strip_prefix(&SECP256K1_DER_PREFIX[..]) {
+        use k256::ecdsa::signature::Verifier;
+        let key = k256::ecdsa::VerifyingKey::from_sec1_bytes(key).map_err(|e| invalid(&e.to_string()))?;
+        let signature = k256::ecdsa::Signature::from_slice(&signature).map_err(|e| invalid(&e.to_string()))?;
+        return key
+            .verify(message.as_bytes(), &signature)
+            .map_err(|e| invalid(&e.to_string()));
+    }
+
+    Err(invalid("unsupported public key"))
+}
@@ -0,0 +1,744 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+};
+
+use candid::Principal;
+
+use crate::{store::dapp::anchor::DappAnchor, types::TimestampMills};
+
+use super::{
+    DappAccess, DappAccessItem, DappVerified, DappVerifiedItem,
+    chain::Chain,
+    chain_identity::{ChainIdentity, VerifiedChainIdentity},
+    challenge::{
+        AccessChallenge, ChallengeError, ChallengeNonceStore, MAX_CHALLENGE_TTL, StableChallengeNonceStore,
+        verify_challenge,
+    },
+    duration::{AccessDuration, VerifiedAccessDuration},
+    explain::{AccessMismatch, AccessOutcome},
+    nft_owner::{NFTOwner, VerifiedNFTOwner},
+    oracle::{BalanceOracle, NoOracle, OwnershipOracle},
+    signature::{SignatureError, hash_eip191, recover_eip191, verify_signature},
+    times::{AccessUsageKey, AccessUsageStore, StableAccessUsageStore},
+    token_balance::{TokenBalance, VerifiedTokenBalance},
+};
+
+const ADDRESS: &str = "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf"; // ? The address of private key 1
+
+#[allow(clippy::unwrap_used)] // ? checked
+fn evm_key() -> k256::ecdsa::SigningKey {
+    let mut key = [0; 32];
+    key[31] = 1;
+    k256::ecdsa::SigningKey::from_slice(&key).unwrap()
+}
+
+#[allow(clippy::unwrap_used)] // ? checked
+fn sign_eip191(message: &str) -> String {
+    let (signature, recovery_id) = evm_key().sign_prehash_recoverable(&hash_eip191(message)).unwrap();
+    let mut bytes = signature.to_bytes().to_vec();
+    bytes.push(27 + recovery_id.to_byte());
+    format!("0x{}", hex::encode(bytes))
+}
+
+/// The principal and the signature of ed25519 identity
+fn sign_ic_ed25519(message: &str) -> (String, String) {
+    use ed25519_dalek::Signer;
+    let key = ed25519_dalek::SigningKey::from_bytes(&[7; 32]);
+    let mut der = hex::decode("302a300506032b6570032100").unwrap_or_default();
+    der.extend(key.verifying_key().to_bytes());
+    let signature = key.sign(message.as_bytes());
+    (
+        Principal::self_authenticating(&der).to_text(),
+        format!("{}:{}", hex::encode(&der), hex::encode(signature.to_bytes())),
+    )
+}
+
+/// The principal and the signature of secp256k1 identity
+fn sign_ic_secp256k1(message: &str) -> (String, String) {
+    use k256::ecdsa::signature::Signer;
+    let key = evm_key();
+    let mut der = hex::decode("3056301006072a8648ce3d020106052b8104000a034200").unwrap_or_default();
+    der.extend(key.verifying_key().to_encoded_point(false).as_bytes());
+    let signature: k256::ecdsa::Signature = key.sign(message.as_bytes());
+    (
+        Principal::self_authenticating(&der).to_text(),
+        format!("{}:{}", hex::encode(&der), hex::encode(signature.to_bytes())),
+    )
+}
+
+#[derive(Default)]
+struct MockOracle {
+    balances: HashMap<String, u64>,
+    owners: HashMap<(String, Option<String>), String>,
+}
+
+impl BalanceOracle for MockOracle {
+    fn balance(&self, _chain: &Chain, _address: Option<&str>, identity: &str) -> Option<u64> {
+        self.balances.get(identity).copied()
+    }
+}
+
+impl OwnershipOracle for MockOracle {
+    fn owns(&self, _chain: &Chain, address: &str, token_id: Option<&str>, identity: &str) -> Option<bool> {
+        let owner = self
+            .owners
+            .get(&(address.to_string(), token_id.map(|t| t.to_string())))?;
+        Some(owner == identity)
+    }
+}
+
+#[test]
+fn test_signature() {
+    let message = "hello jelly";
+    let signature = sign_eip191(message);
+    assert_eq!(
+        recover_eip191(message, &signature),
+        Ok("0x7e5f4552091a69125d5dfcb7b8c2659029395bdf".into())
+    );
+    assert_eq!(verify_signature(&Chain::Ethereum, ADDRESS, message, &signature), Ok(()));
+    assert_eq!(verify_signature(&Chain::Polygon, ADDRESS, message, &signature), Ok(()));
+    assert!(matches!(
+        verify_signature(&Chain::Ethereum, ADDRESS, "hello", &signature),
+        Err(SignatureError::MismatchedSigner { .. })
+    ));
+    assert!(matches!(
+        verify_signature(&Chain::Ethereum, ADDRESS, message, "0x1234"),
+        Err(SignatureError::InvalidSignature(_))
+    ));
+    assert_eq!(
+        verify_signature(&Chain::Bitcoin, ADDRESS, message, &signature),
+        Err(SignatureError::UnsupportedChain(Chain::Bitcoin))
+    );
+
+    let (principal, signature) = sign_ic_ed25519(message);
+    assert_eq!(
+        verify_signature(&Chain::InternetComputer, &principal, message, &signature),
+        Ok(())
+    );
+    assert!(verify_signature(&Chain::InternetComputer, &principal, "hello", &signature).is_err());
+    assert!(matches!(
+        verify_signature(&Chain::InternetComputer, "aaaaa-aa", message, &signature),
+        Err(SignatureError::MismatchedSigner { .. })
+    ));
+
+    let (principal, signature) = sign_ic_secp256k1(message);
+    assert_eq!(
+        verify_signature(&Chain::InternetComputer, &principal, message, &signature),
+        Ok(())
+    );
+    assert!(verify_signature(&Chain::InternetComputer, &principal, "hello", &signature).is_err());
+}
+
+/// Access with fresh stores
+fn grant<O: BalanceOracle + OwnershipOracle>(
+    access: &DappAccess,
+    now: TimestampMills,
+    verified: &DappVerified,
+    oracle: &O,
+) -> bool {
+    let mut usage = BTreeMap::<AccessUsageKey, u64>::new();
+    let mut nonces = BTreeMap::<String, TimestampMills>::new();
+    access.access_with_challenge(
+        now,
+        Some(verified),
+        oracle,
+        &mut usage,
+        &usage_key("alice"),
+        &mut nonces,
+    )
+}
+
+#[test]
+fn test_access() {
+    let now = TimestampMills::from(0);
+    let message = AccessChallenge::generate(
+        DappAnchor::from("in123".into()),
+        "alice".into(),
+        Chain::Ethereum,
+        now,
+        60,
+        &[1],
+    )
+    .to_message();
+    let message = message.as_str();
+    let signature = sign_eip191(message);
+    let identity = ADDRESS.to_lowercase();
+
+    // chain identity
+    let access = DappAccess::Required(DappAccessItem::ChainIdentity(ChainIdentity {
+        chain: Chain::Ethereum,
+        identity: ADDRESS.into(),
+    }));
+    let verified = |signature: &str| {
+        DappVerified::Required(DappVerifiedItem::ChainIdentity(VerifiedChainIdentity {
+            chain: Chain::Ethereum,
+            identity: identity.clone(),
+            message: message.into(),
+            signature: signature.into(),
+        }))
+    };
+    assert!(grant(&access, now, &verified(&signature), &NoOracle));
+    assert!(!grant(&access, now, &verified(&sign_eip191("hello")), &NoOracle));
+    // ! The signed message may be replayed without the challenge
+    assert!(!access.access_by_timestamp_and_token(now, Some(&verified(&signature))));
+    assert_eq!(
+        access
+            .explain_by_timestamp_and_token(now, Some(&verified(&signature)))
+            .outcome,
+        AccessOutcome::Unverifiable
+    );
+
+    // the forged signature is not reversed into a grant
+    let item = DappAccessItem::ChainIdentity(ChainIdentity {
+        chain: Chain::Ethereum,
+        identity: ADDRESS.into(),
+    });
+    let forged = match verified("garbage") {
+        DappVerified::Required(forged) => forged,
+        _ => DappVerifiedItem::Times,
+    };
+    let deny = DappAccess::Deny(item.clone());
+    let denied = DappVerified::Deny(forged.clone());
+    assert!(!grant(&deny, now, &denied, &NoOracle));
+    assert!(!deny.access_by_timestamp_and_token(now, Some(&denied)));
+    assert_eq!(
+        deny.explain_by_timestamp_and_token(now, Some(&denied)).outcome,
+        AccessOutcome::Unverifiable
+    );
+    let not = DappAccess::Not(vec![DappAccess::Required(item.clone())]);
+    let denied = DappVerified::Not(vec![DappVerified::Required(forged.clone())]);
+    assert!(!grant(&not, now, &denied, &NoOracle));
+    assert!(!not.access_by_timestamp_and_token(now, Some(&denied)));
+    assert_eq!(
+        not.explain_by_timestamp_and_token(now, Some(&denied)).outcome,
+        AccessOutcome::Unverifiable
+    );
+    let not = DappAccess::Not(vec![DappAccess::All(vec![DappAccess::Required(item)])]);
+    let denied = DappVerified::Not(vec![DappVerified::All(vec![DappVerified::Required(forged)])]);
+    assert!(!grant(&not, now, &denied, &NoOracle));
+
+    // token balance
+    let access = DappAccess::Required(DappAccessItem::TokenBalance(TokenBalance {
+        chain: Chain::Ethereum,
+        address: None,
+        balance: 100,
+    }));
+    let verified = DappVerified::Required(DappVerifiedItem::TokenBalance(VerifiedTokenBalance {
+        chain: Chain::Ethereum,
+        address: None,
+        balance: 100,
+        identity: identity.clone(),
+        message: message.into(),
+        signature: signature.clone(),
+    }));
+    let mut oracle = MockOracle::default();
+    assert!(!grant(&access, now, &verified, &NoOracle));
+    assert!(!grant(&access, now, &verified, &oracle));
+    oracle.balances.insert(identity.clone(), 99);
+    assert!(!grant(&access, now, &verified, &oracle));
+    oracle.balances.insert(identity.clone(), 100);
+    assert!(grant(&access, now, &verified, &oracle));
+    assert!(!access.access_with_oracle(now, Some(&verified), &oracle));
+
+    // nft owner
+    let access = DappAccess::Deny(DappAccessItem::NftOwner(NFTOwner {
+        chain: Chain::Ethereum,
+        address: "0xnft".into(),
+        token_id: Some("1".into()),
+    }));
+    let verified = DappVerified::Deny(DappVerifiedItem::NftOwner(VerifiedNFTOwner {
+        chain: Chain::Ethereum,
+        address: "0xnft".into(),
+        token_id: Some("1".into()),
+        identity: identity.clone(),
+        message: message.into(),
+        signature,
+    }));
+    assert!(!grant(&access, now, &verified, &oracle));
+    oracle
+        .owners
+        .insert(("0xnft".into(), Some("1".into())), "0xother".into());
+    assert!(grant(&access, now, &verified, &oracle));
+    assert!(!access.access_with_oracle(now, Some(&verified), &oracle));
+    oracle.owners.insert(("0xnft".into(), Some("1".into())), identity);
+    assert!(!grant(&access, now, &verified, &oracle));
+}
+
+fn usage_key(caller: &str) -> AccessUsageKey {
+    AccessUsageKey {
+        dapp: DappAnchor::from("in123".into()),
+        caller: caller.into(),
+    }
+}
+
+/// The used times is read before another request consumed
+struct StaleStore {
+    inner: BTreeMap<AccessUsageKey, u64>,
+    stale: u64,
+}
+
+impl AccessUsageStore for StaleStore {
+    fn used(&self, _key: &AccessUsageKey) -> u64 {
+        self.stale
+    }
+
+    fn consume(&mut self, key: &AccessUsageKey, limit: u64) -> bool {
+        self.inner.consume(key, limit)
+    }
+}
+
+#[test]
+fn test_times() {
+    let now = TimestampMills::from(0);
+    let access = DappAccess::Required(DappAccessItem::Times(3));
+    let verified = DappVerified::Required(DappVerifiedItem::Times);
+    let (alice, bob) = (usage_key("alice"), usage_key("bob"));
+
+    // stable memory
+    let memory = ic_stable_structures::VectorMemory::default();
+    let mut store = StableAccessUsageStore::init(memory.clone());
+    assert!(!access.access_by_timestamp_and_token(now, Some(&verified)));
+    for _ in 0..3 {
+        assert!(access.access_with_usage(now, Some(&verified), &NoOracle, &mut store, &alice));
+    }
+    assert!(!access.access_with_usage(now, Some(&verified), &NoOracle, &mut store, &alice));
+    assert!(access.access_with_usage(now, Some(&verified), &NoOracle, &mut store, &bob));
+    let decision = access.explain_with_usage(now, Some(&verified), &NoOracle, &store, &alice);
+    assert_eq!(decision.children[0].reason, "used up 3 times");
+    let mut store = StableAccessUsageStore::init(memory); // ? reload
+    assert_eq!((store.used(&alice), store.used(&bob)), (3, 1));
+    assert_eq!(store.reset(&alice), Some(3));
+    assert!(access.access_with_usage(now, Some(&verified), &NoOracle, &mut store, &alice));
+
+    // not consumed if the access is refused by others
+    let access = DappAccess::All(vec![access, DappAccess::Required(DappAccessItem::Token("t".into()))]);
+    let verified = |token: &str| {
+        DappVerified::All(vec![
+            DappVerified::Required(DappVerifiedItem::Times),
+            DappVerified::Required(DappVerifiedItem::Token(token.into())),
+        ])
+    };
+    let mut store = BTreeMap::new();
+    assert!(!access.access_with_usage(now, Some(&verified("x")), &NoOracle, &mut store, &alice));
+    assert_eq!(store.used(&alice), 0);
+    assert!(access.access_with_usage(now, Some(&verified("t")), &NoOracle, &mut store, &alice));
+    assert_eq!(store.used(&alice), 1);
+
+    // not consumed if the branch of times does not grant the access
+    let access = DappAccess::Any(vec![
+        DappAccess::All(vec![
+            DappAccess::Required(DappAccessItem::Times(3)),
+            DappAccess::Required(DappAccessItem::Token("secret".into())),
+        ]),
+        DappAccess::Required(DappAccessItem::Token("public".into())),
+    ]);
+    let verified = |secret: &str| {
+        DappVerified::Any(vec![
+            DappVerified::All(vec![
+                DappVerified::Required(DappVerifiedItem::Times),
+                DappVerified::Required(DappVerifiedItem::Token(secret.into())),
+            ]),
+            DappVerified::Required(DappVerifiedItem::Token("public".into())),
+        ])
+    };
+    let carol = usage_key("carol");
+    for _ in 0..5 {
+        assert!(access.access_with_usage(now, Some(&verified("wrong")), &NoOracle, &mut store, &carol));
+    }
+    assert_eq!(store.used(&carol), 0);
+    assert!(access.access_with_usage(now, Some(&verified("secret")), &NoOracle, &mut store, &carol));
+    assert_eq!(store.used(&carol), 1);
+
+    // not consumed if the times is denied
+    let deny = DappAccess::Deny(DappAccessItem::Times(1));
+    let denied = DappVerified::Deny(DappVerifiedItem::Times);
+    assert!(!deny.access_with_usage(now, Some(&denied), &NoOracle, &mut store, &bob));
+    assert_eq!(store.used(&bob), 0);
+}
+
+#[test]
+fn test_times_concurrent() {
+    let now = TimestampMills::from(0);
+    let access = DappAccess::Required(DappAccessItem::Times(3));
+    let verified = DappVerified::Required(DappVerifiedItem::Times);
+    let key = usage_key("alice");
+
+    // both requests checked the last one before consumed
+    let mut store = StaleStore {
+        inner: BTreeMap::from([(key.clone(), 2)]),
+        stale: 2,
+    };
+    let granted = (0..2)
+        .filter(|_| access.access_with_usage(now, Some(&verified), &NoOracle, &mut store, &key))
+        .count();
+    assert_eq!(granted, 1);
+    assert_eq!(store.inner.used(&key), 3);
+
+    // threads
+    let store = Arc::new(Mutex::new(BTreeMap::new()));
+    let handles = (0..8)
+        .map(|_| {
+            let (store, access, verified, key) = (store.clone(), access.clone(), verified.clone(), key.clone());
+            std::thread::spawn(move || {
+                let Ok(mut store) = store.lock() else {
+                    return false;
+                };
+                access.access_with_usage(now, Some(&verified), &NoOracle, &mut *store, &key)
+            })
+        })
+        .collect::<Vec<_>>();
+    let granted = handles
+        .into_iter()
+        .filter_map(|handle| handle.join().ok())
+        .filter(|granted| *granted)
+        .count();
+    assert_eq!(granted, 3);
+}
+
+#[test]
+fn test_explain() {
+    let duration = |start: Option<i64>, end: Option<i64>| {
+        (
+            DappAccess::Required(DappAccessItem::Duration(AccessDuration {
+                start: start.map(TimestampMills::from),
+                end: end.map(TimestampMills::from),
+            })),
+            DappVerified::Required(DappVerifiedItem::Duration(VerifiedAccessDuration {
+                start: start.map(TimestampMills::from),
+                end: end.map(TimestampMills::from),
+            })),
+        )
+    };
+    let token = |token: &str| DappAccess::Required(DappAccessItem::Token(token.into()));
+    let verified_token = |token: &str| DappVerified::Required(DappVerifiedItem::Token(token.into()));
+    let now = TimestampMills::from(105);
+
+    // leaf
+    let (access, verified) = duration(None, Some(100));
+    let decision = access.explain_by_timestamp_and_token(now, Some(&verified));
+    assert_eq!(decision.outcome, AccessOutcome::Failed);
+    assert_eq!(decision.children[0].reason, "duration ends at 100, now is 105");
+
+    // tree
+    let (passed, passed_verified) = duration(Some(100), None);
+    let access = DappAccess::All(vec![
+        passed.clone(),
+        DappAccess::Not(vec![token("banned")]),
+        DappAccess::Any(vec![token("a"), token("b")]),
+    ]);
+    let verified = DappVerified::All(vec![
+        passed_verified.clone(),
+        DappVerified::Not(vec![verified_token("banned")]),
+        DappVerified::Any(vec![verified_token("c")]),
+    ]);
+    let decision = access.explain_by_timestamp_and_token(now, Some(&verified));
+    assert_eq!(decision.outcome, AccessOutcome::Failed);
+    assert_eq!(decision.reason, "1 of 3 satisfied, all required");
+    let outcomes = decision.children.iter().map(|c| c.outcome).collect::<Vec<_>>();
+    assert_eq!(
+        outcomes,
+        vec![AccessOutcome::Passed, AccessOutcome::Failed, AccessOutcome::Failed]
+    );
+    assert_eq!(decision.children[1].children[0].children[0].reason, "token matched");
+    let json = serde_json::to_string(&decision).unwrap_or_default();
+    assert!(json.contains(r#""outcome":"failed""#), "{json}");
+
+    // mismatched verification is unverifiable
+    let decision = passed.explain_by_timestamp_and_token(now, Some(&verified_token("a")));
+    assert_eq!(decision.outcome, AccessOutcome::Unverifiable);
+    assert_eq!(decision.children[0].reason, "verified token does not match duration");
+    let decision = passed.explain_by_timestamp_and_token(now, Some(&DappVerified::Any(vec![])));
+    assert_eq!(decision.reason, "verified any does not match required");
+    assert_eq!(
+        access.explain_by_timestamp_and_token(now, None).reason,
+        "verification is missing"
+    );
+
+    // same as access
+    let cases = vec![
+        (access.clone(), verified.clone()),
+        (
+            access,
+            DappVerified::All(vec![
+                passed_verified.clone(),
+                DappVerified::Not(vec![verified_token("x")]),
+                DappVerified::Any(vec![verified_token("c"), verified_token("b")]),
+            ]),
+        ),
+        (DappAccess::Deny(DappAccessItem::Token("t".into())), verified_token("t")),
+        (DappAccess::None, verified_token("t")),
+        (DappAccess::Exclusive, DappVerified::Exclusive),
+        (DappAccess::Any(vec![passed]), DappVerified::Any(vec![])),
+        duration(Some(110), None),
+    ];
+    for (access, verified) in cases {
+        assert_eq!(
+            access.explain_by_timestamp_and_token(now, Some(&verified)).is_passed(),
+            access.access_by_timestamp_and_token(now, Some(&verified)),
+            "{access:?}"
+        );
+    }
+}
+
+#[test]
+fn test_validate_verified() {
+    let token = |token: &str| DappAccess::Required(DappAccessItem::Token(token.into()));
+    let access = DappAccess::All(vec![
+        DappAccess::Required(DappAccessItem::Times(3)),
+        DappAccess::Any(vec![token("a"), DappAccess::Deny(DappAccessItem::Token("b".into()))]),
+    ]);
+    let verified = |any: Vec<DappVerified>| {
+        DappVerified::All(vec![
+            DappVerified::Required(DappVerifiedItem::Times),
+            DappVerified::Any(any),
+        ])
+    };
+
+    assert_eq!(
+        access.validate_verified(&verified(vec![DappVerified::Required(DappVerifiedItem::Token(
+            "a".into()
+        ))])),
+        Ok(())
+    );
+    assert_eq!(
+        access.validate_verified(&verified(vec![DappVerified::Deny(DappVerifiedItem::Times)])),
+        Err(AccessMismatch {
+            path: "/all/1/any/0/deny".into(),
+            expected: vec!["token".into()],
+            found: "times".into(),
+        })
+    );
+    let mismatch = access
+        .validate_verified(&verified(vec![DappVerified::Not(vec![])]))
+        .err();
+    assert_eq!(
+        mismatch.map(|e| e.to_string()),
+        Some("verification at /all/1/any/0 should be required or deny, but found not".into())
+    );
+    assert_eq!(
+        access.validate_verified(&DappVerified::None).map_err(|e| e.path),
+        Err(String::new())
+    );
+}
+
+#[test]
+fn test_challenge() {
+    let dapp = DappAnchor::from("in123".into());
+    let now = TimestampMills::from(1000);
+    let challenge = AccessChallenge::generate(dapp.clone(), "alice".into(), Chain::Ethereum, now, 60, &[1, 2, 255]);
+    let message = challenge.to_message();
+    assert_eq!(
+        message,
+        "jelly access challenge\ndapp: in123\ncaller: alice\nchain: ethereum\nnonce: 0102ff\nissued: 1000\nexpiry: 1060"
+    );
+    assert_eq!(AccessChallenge::parse(&message), Ok(challenge.clone()));
+    for wrong in [
+        "hello jelly".to_string(),
+        message.replace("\n", "\r\n"),
+        message.replace("nonce: 0102ff", "nonce: "),
+        message.replace("ethereum", "solana"),
+        message.replace("1060", "01060"),
+        format!("{message}\n"),
+    ] {
+        assert!(
+            matches!(AccessChallenge::parse(&wrong), Err(ChallengeError::InvalidMessage(_))),
+            "{wrong}"
+        );
+    }
+
+    // check
+    assert_eq!(challenge.check(&dapp, "alice", &Chain::Ethereum, now), Ok(()));
+    assert_eq!(
+        challenge.check(&DappAnchor::from("in456".into()), "alice", &Chain::Ethereum, now),
+        Err(ChallengeError::MismatchedDapp("in123".into()))
+    );
+    assert_eq!(
+        challenge.check(&dapp, "bob", &Chain::Ethereum, now),
+        Err(ChallengeError::MismatchedCaller("alice".into()))
+    );
+    assert_eq!(
+        challenge.check(&dapp, "alice", &Chain::Polygon, now),
+        Err(ChallengeError::MismatchedChain(Chain::Ethereum))
+    );
+    assert!(matches!(
+        challenge.check(&dapp, "alice", &Chain::Ethereum, TimestampMills::from(999)),
+        Err(ChallengeError::NotIssued { .. })
+    ));
+    assert_eq!(
+        challenge
+            .check(&dapp, "alice", &Chain::Ethereum, TimestampMills::from(1060))
+            .map_err(|e| e.to_string()),
+        Err("challenge expired at 1060, now is 1060".into())
+    );
+    let long = AccessChallenge {
+        expiry: (now + MAX_CHALLENGE_TTL + 1).into(),
+        ..challenge.clone()
+    };
+    assert_eq!(
+        long.check(&dapp, "alice", &Chain::Ethereum, now),
+        Err(ChallengeError::InvalidTtl {
+            issued: now,
+            expiry: long.expiry,
+        })
+    );
+    assert!(matches!(
+        verify_challenge(
+            &long.to_message(),
+            &dapp,
+            "alice",
+            &Chain::Ethereum,
+            now,
+            &mut BTreeMap::new()
+        ),
+        Err(ChallengeError::InvalidTtl { .. })
+    ));
+    let generated = AccessChallenge::generate(dapp.clone(), "alice".into(), Chain::Ethereum, now, i64::MAX, &[1]);
+    assert_eq!(generated.expiry, TimestampMills::from(now + MAX_CHALLENGE_TTL));
+    assert_eq!(generated.check(&dapp, "alice", &Chain::Ethereum, now), Ok(()));
+
+    // nonce
+    let memory = ic_stable_structures::VectorMemory::default();
+    let mut nonces = StableChallengeNonceStore::init(memory.clone());
+    let verify = |nonces: &mut dyn ChallengeNonceStore| {
+        verify_challenge(&message, &dapp, "alice", &Chain::Ethereum, now, nonces)
+    };
+    assert_eq!(verify(&mut nonces), Ok(challenge.clone()));
+    assert_eq!(verify(&mut nonces), Err(ChallengeError::NonceReused("0102ff".into())));
+    let mut nonces = StableChallengeNonceStore::init(memory); // ? reload
+    assert!(nonces.is_used("0102ff"));
+    nonces.prune(TimestampMills::from(1059));
+    assert_eq!(nonces.len(), 1);
+    nonces.prune(TimestampMills::from(1060));
+    assert!(nonces.is_empty());
+
+    let mut nonces = BTreeMap::new();
+    assert_eq!(verify(&mut nonces), Ok(challenge));
+    assert_eq!(verify(&mut nonces), Err(ChallengeError::NonceReused("0102ff".into())));
+}
+
+#[test]
+fn test_access_with_challenge() {
+    let key = usage_key("alice");
+    let now = TimestampMills::from(1000);
+    let access = DappAccess::All(vec![
+        DappAccess::Required(DappAccessItem::ChainIdentity(ChainIdentity {
+            chain: Chain::Ethereum,
+            identity: ADDRESS.into(),
+        })),
+        DappAccess::Required(DappAccessItem::Times(5)),
+    ]);
+    let verified = |message: &str| {
+        DappVerified::All(vec![
+            DappVerified::Required(DappVerifiedItem::ChainIdentity(VerifiedChainIdentity {
+                chain: Chain::Ethereum,
+                identity: ADDRESS.into(),
+                message: message.into(),
+                signature: sign_eip191(message),
+            })),
+            DappVerified::Required(DappVerifiedItem::Times),
+        ])
+    };
+    let challenge = |nonce: u8, caller: &str| {
+        AccessChallenge::generate(key.dapp.clone(), caller.into(), Chain::Ethereum, now, 60, &[nonce]).to_message()
+    };
+    let mut usage = BTreeMap::new();
+    let mut nonces = BTreeMap::new();
+
+    // granted once
+    let first = verified(&challenge(1, "alice"));
+    assert!(access.access_with_challenge(now, Some(&first), &NoOracle, &mut usage, &key, &mut nonces));
+    assert_eq!(usage.used(&key), 1);
+    assert!(nonces.is_used("01"));
+
+    // replayed
+    assert!(!access.access_with_challenge(now, Some(&first), &NoOracle, &mut usage, &key, &mut nonces));
+    let decision = access.explain_with_challenge(now, Some(&first), &NoOracle, &usage, &key, &nonces);
+    assert_eq!(decision.children[0].children[0].reason, "nonce 01 is used");
+    assert_eq!(usage.used(&key), 1);
+
+    // free-form message, another caller or expired
+    let free = verified("hello jelly");
+    assert!(!access.access_with_usage(now, Some(&free), &NoOracle, &mut usage, &key));
+    assert!(!access.access_with_challenge(now, Some(&free), &NoOracle, &mut usage, &key, &mut nonces));
+    let bob = verified(&challenge(2, "bob"));
+    assert!(!access.access_with_challenge(now, Some(&bob), &NoOracle, &mut usage, &key, &mut nonces));
+    let expired = verified(&challenge(3, "alice"));
+    let later = TimestampMills::from(1060);
+    assert!(!access.access_with_challenge(later, Some(&expired), &NoOracle, &mut usage, &key, &mut nonces));
+
+    // not consumed if the access is refused by others
+    let access = DappAccess::All(vec![access, DappAccess::Required(DappAccessItem::Token("t".into()))]);
+    let wrapped = |verified: DappVerified, token: &str| {
+        DappVerified::All(vec![
+            verified,
+            DappVerified::Required(DappVerifiedItem::Token(token.into())),
+        ])
+    };
+    let refused = wrapped(verified(&challenge(4, "alice")), "x");
+    assert!(!access.access_with_challenge(now, Some(&refused), &NoOracle, &mut usage, &key, &mut nonces));
+    assert!(!nonces.is_used("04"));
+    let granted = wrapped(verified(&challenge(4, "alice")), "t");
+    assert!(access.access_with_challenge(now, Some(&granted), &NoOracle, &mut usage, &key, &mut nonces));
+    assert!(nonces.is_used("04"));
+
+    // not consumed by the branch which does not grant
+    let access = DappAccess::Any(vec![
+        DappAccess::All(vec![
+            DappAccess::Required(DappAccessItem::ChainIdentity(ChainIdentity {
+                chain: Chain::Ethereum,
+                identity: ADDRESS.into(),
+            })),
+            DappAccess::Required(DappAccessItem::Token("secret".into())),
+        ]),
+        DappAccess::Required(DappAccessItem::Token("public".into())),
+    ]);
+    let any = |message: &str, secret: &str| {
+        DappVerified::Any(vec![
+            DappVerified::All(vec![
+                DappVerified::Required(DappVerifiedItem::ChainIdentity(VerifiedChainIdentity {
+                    chain: Chain::Ethereum,
+                    identity: ADDRESS.into(),
+                    message: message.into(),
+                    signature: sign_eip191(message),
+                })),
+                DappVerified::Required(DappVerifiedItem::Token(secret.into())),
+            ]),
+            DappVerified::Required(DappVerifiedItem::Token("public".into())),
+        ])
+    };
+    let public = any(&challenge(5, "alice"), "wrong");
+    assert!(access.access_with_challenge(now, Some(&public), &NoOracle, &mut usage, &key, &mut nonces));
+    assert!(!nonces.is_used("05"));
+    let secret = any(&challenge(5, "alice"), "secret");
+    assert!(access.access_with_challenge(now, Some(&secret), &NoOracle, &mut usage, &key, &mut nonces));
+    assert!(nonces.is_used("05"));
+
+    // all or nothing
+    assert!(!nonces.use_nonces(&[("06", now), ("05", now)]));
+    assert!(!nonces.is_used("06"));
+    assert!(nonces.use_nonces(&[("06", now), ("07", now)]));
+    nonces.release_nonces(&["06", "07"]);
+    assert!(!nonces.is_used("06") && !nonces.is_used("07"));
+
+    // released if the times is used up after checked
+    struct UsedUp;
+    impl AccessUsageStore for UsedUp {
+        fn used(&self, _key: &AccessUsageKey) -> u64 {
+            0
+        }
+        fn consume(&mut self, _key: &AccessUsageKey, _limit: u64) -> bool {
+            false
+        }
+    }
+    let access = DappAccess::All(vec![
+        DappAccess::Required(DappAccessItem::ChainIdentity(ChainIdentity {
+            chain: Chain::Ethereum,
+            identity: ADDRESS.into(),
+        })),
+        DappAccess::Required(DappAccessItem::Times(5)),
+    ]);
+    let raced = verified(&challenge(8, "alice"));
+    assert!(!access.access_with_challenge(now, Some(&raced), &NoOracle, &mut UsedUp, &key, &mut nonces));
+    assert!(!nonces.is_used("08"));
+}
@@ -0,0 +1,91 @@
+use std::{borrow::Cow, collections::BTreeMap};
+
+use ic_stable_structures::{Memory, StableBTreeMap, Storable, storable::Bound};
+use serde::{Deserialize, Serialize};
+
+use crate::store::dapp::anchor::DappAnchor;
+
+/// The key of used times
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AccessUsageKey {
+    /// dapp
+    pub dapp: DappAnchor,
+    /// caller identity
+    pub caller: String,
+}
+
+impl Storable for AccessUsageKey {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        let mut bytes = vec![];
+        #[allow(clippy::unwrap_used)] // ? SAFETY
+        ciborium::ser::into_writer(self, &mut bytes).unwrap();
+        Cow::Owned(bytes)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        #[allow(clippy::expect_used)] // ? SAFETY
+        ciborium::de::from_reader(&bytes[..]).expect("deserialization must succeed.")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// The store of used times, supplied by the caller
+pub trait AccessUsageStore {
+    /// The used times
+    fn used(&self, key: &AccessUsageKey) -> u64;
+
+    /// Increase the used times if it is less than limit, return whether it is consumed
+    /// ! Check and increase must be atomic
+    fn consume(&mut self, key: &AccessUsageKey, limit: u64) -> bool;
+}
+
+impl AccessUsageStore for BTreeMap<AccessUsageKey, u64> {
+    fn used(&self, key: &AccessUsageKey) -> u64 {
+        self.get(key).copied().unwrap_or_default()
+    }
+
+    fn consume(&mut self, key: &AccessUsageKey, limit: u64) -> bool {
+        let used = self.entry(key.clone()).or_default();
+        if limit <= *used {
+            return false;
+        }
+        *used += 1;
+        true
+    }
+}
+
+/// The store of used times in stable memory
+pub struct StableAccessUsageStore<M: Memory> {
+    usages: StableBTreeMap<AccessUsageKey, u64, M>,
+}
+
+impl<M: Memory> StableAccessUsageStore<M> {
+    /// Load or create the store in memory
+    pub fn init(memory: M) -> Self {
+        Self {
+            usages: StableBTreeMap::init(memory),
+        }
+    }
+
+    /// Forget the used times of the caller
+    pub fn reset(&mut self, key: &AccessUsageKey) -> Option<u64> {
+        self.usages.remove(key)
+    }
+}
+
+impl<M: Memory> AccessUsageStore for StableAccessUsageStore<M> {
+    fn used(&self, key: &AccessUsageKey) -> u64 {
+        self.usages.get(key).unwrap_or_default()
+    }
+
+    // ? The canister handles one message at a time, and there is no await between check and insert
+    fn consume(&mut self, key: &AccessUsageKey, limit: u64) -> bool {
+        let used = self.used(key);
+        if limit <= used {
+            return false;
+        }
+        self.usages.insert(key.clone(), used + 1);
+        true
+    }
+}
@@ -1,5 +1,12 @@
 use serde::{Deserialize, Serialize};
 
+use crate::{store::dapp::anchor::DappAnchor, types::TimestampMills};
+
+use super::{
+    challenge::{AccessChallenge, ChallengeError, ChallengeNonceStore},
+    explain::AccessDecision,
+};
+
 /// Token balance
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct TokenBalance {
@@ -31,3 +38,58 @@ pub struct VerifiedTokenBalance {
     /// signature
     pub signature: String,
 }
+
+impl TokenBalance {
+    /// Whether
+    pub fn is_same(&self, verified: &VerifiedTokenBalance) -> bool {
+        self.chain == verified.chain && self.address == verified.address && self.balance == verified.balance
+    }
+
+    /// Judgment authority, None if the verification is not for this or the balance is unknown
+    pub fn access<O: super::oracle::BalanceOracle>(&self, verified: &VerifiedTokenBalance, oracle: &O) -> Option<bool> {
+        self.explain(verified, oracle).result()
+    }
+
+    /// Explain the access
+    pub fn explain<O: super::oracle::BalanceOracle>(
+        &self,
+        verified: &VerifiedTokenBalance,
+        oracle: &O,
+    ) -> AccessDecision {
+        if !self.is_same(verified) {
+            return AccessDecision::unverifiable("token_balance", "verified token balance does not match");
+        }
+        if let Err(e) = verified.verify() {
+            return AccessDecision::unverifiable("token_balance", e.to_string());
+        }
+        match oracle.balance(&self.chain, self.address.as_deref(), &verified.identity) {
+            Some(balance) if self.balance <= balance => AccessDecision::passed(
+                "token_balance",
+                format!("balance {balance} is not less than {}", self.balance),
+            ),
+            Some(balance) => AccessDecision::failed(
+                "token_balance",
+                format!("balance {balance} is less than {}", self.balance),
+            ),
+            None => AccessDecision::unverifiable("token_balance", "balance is unknown"),
+        }
+    }
+}
+
+impl VerifiedTokenBalance {
+    /// Verify the message is signed by the identity
+    pub fn verify(&self) -> Result<(), super::signature::SignatureError> {
+        super::signature::verify_signature(&self.chain, &self.identity, &self.message, &self.signature)
+    }
+
+    /// Check the signed message is the challenge for the dapp and caller, the nonce is not consumed
+    pub fn check_challenge(
+        &self,
+        dapp: &DappAnchor,
+        caller: &str,
+        now: TimestampMills,
+        nonces: &dyn ChallengeNonceStore,
+    ) -> Result<AccessChallenge, ChallengeError> {
+        super::challenge::check_challenge(&self.message, dapp, caller, &self.chain, now, nonces)
+    }
+}
@@ -171,6 +171,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::unwrap_used)] // ? checked
     fn test() {
         let id: DappAnchor = (&DappParsedId::from(
             Principal::from_text("fmzms-paaaa-aaaai-qpeka-cai").unwrap(),
@@ -1,3 +1,5 @@
+import { Principal } from '@dfinity/principal';
+
 import { deepClone } from '../../../../../../common/clones';
 import { RuntimeValues } from '../../../../../../runtime/value';
 import { CodeData, CodeDataAnchor } from '../../../../../../store/code';
@@ -6,7 +8,12 @@ import { WrappedCandidType } from '../../../../../../wasm/candid';
 import { CodeContent } from '../../../../../common/code';
 import { ComponentId } from '../../../../../common/identity';
 import { AllEndpoints } from '../../../../../common/lets';
-import { code_value_get_used_component, CodeValue } from '../../../../../common/refer';
+import {
+    code_value_get_used_component,
+    CodeValue,
+    input_value_get_used_component,
+    InputValue,
+} from '../../../../../common/refer';
 import { doFunctionTransformByCodeContent } from '../../../../code';
 
 export interface IcCallArgCode {
@@ -22,20 +29,165 @@ export const ic_call_arg_code_get_used_component = (self: IcCallArgCode): Compon
     return used;
 };
 
-export interface IcCallArg {
-    code: IcCallArgCode;
+export interface CandidArgField {
+    key: string;
+    value: CandidArgValue;
+}
+
+export interface CandidArgVariant {
+    tag: string;
+    value?: CandidArgValue;
+}
+
+export type CandidArgValue =
+    | 'null'
+    | { value: InputValue }
+    | { opt: CandidArgValue | null }
+    | { vec: CandidArgValue[] }
+    | { record: CandidArgField[] }
+    | { variant: CandidArgVariant }
+    | { tuple: CandidArgValue[] };
+
+export interface IcCallArgCandid {
+    args: CandidArgValue[];
 }
 
-export const match_ic_call_arg = <T>(self: IcCallArg, { code }: { code: (code: IcCallArgCode) => T }): T => {
+export const candid_arg_value_get_used_component = (self: CandidArgValue): ComponentId[] => {
+    if (self === 'null') return [];
+    if ('value' in self) return input_value_get_used_component(self.value);
+    if ('opt' in self) return self.opt === null ? [] : candid_arg_value_get_used_component(self.opt);
+    if ('vec' in self) return self.vec.flatMap(candid_arg_value_get_used_component);
+    if ('record' in self) return self.record.flatMap((field) => candid_arg_value_get_used_component(field.value));
+    if ('variant' in self) {
+        return self.variant.value === undefined ? [] : candid_arg_value_get_used_component(self.variant.value);
+    }
+    if ('tuple' in self) return self.tuple.flatMap(candid_arg_value_get_used_component);
+    throw new Error('invalid candid arg value');
+};
+
+export const ic_call_arg_candid_get_used_component = (self: IcCallArgCandid): ComponentId[] => {
+    return self.args.flatMap(candid_arg_value_get_used_component);
+};
+
+// the value if it is omitted, undefined if it is required
+const candid_omitted = (ty: WrappedCandidType): any => {
+    if ('opt' in ty) return [];
+    if ('null' in ty || 'reserved' in ty) return null;
+    if ('rec' in ty) return candid_omitted(ty.rec.ty);
+    return undefined;
+};
+
+const candid_required = (ty: WrappedCandidType): any => {
+    const value = candid_omitted(ty);
+    if (value === undefined) throw new Error('candid value is required');
+    return value;
+};
+
+// convert the js value of link type to candid js value, the type is checked by model
+const js_value_to_candid = (ty: WrappedCandidType, value: any): any => {
+    if ('rec' in ty) return js_value_to_candid(ty.rec.ty, value);
+    if ('nat' in ty || 'int' in ty || 'nat64' in ty || 'int64' in ty) return BigInt(value);
+    if ('principal' in ty) return Principal.fromText(value);
+    if ('reserved' in ty) return null;
+    if ('opt' in ty) return [js_value_to_candid(ty.opt.subtype, value)];
+    if ('vec' in ty) return (value as any[]).map((item) => js_value_to_candid(ty.vec.subtype, item));
+    if ('record' in ty) {
+        const record: Record<string, any> = {};
+        for (const [key, subtype] of ty.record.subitems) {
+            record[key] = value[key] === undefined ? candid_required(subtype) : js_value_to_candid(subtype, value[key]);
+        }
+        return record;
+    }
+    if ('variant' in ty) return { [value]: null };
+    return value; // bool, nat8 ~ int32, float32, float64, text
+};
+
+// undefined if some value is missing
+const candid_arg_value_to_js = (
+    self: CandidArgValue,
+    ty: WrappedCandidType,
+    runtime_values: RuntimeValues,
+): any => {
+    if ('rec' in ty) return candid_arg_value_to_js(self, ty.rec.ty, runtime_values);
+    if (self === 'null') return candid_required(ty);
+    if ('value' in self) {
+        const value = runtime_values.find_input_value<any>(self.value);
+        return value === undefined ? undefined : js_value_to_candid(ty, value);
+    }
+    if ('opt' in self && 'opt' in ty) {
+        if (self.opt === null) return [];
+        const value = candid_arg_value_to_js(self.opt, ty.opt.subtype, runtime_values);
+        return value === undefined ? undefined : [value];
+    }
+    if ('vec' in self && 'vec' in ty) {
+        const values = self.vec.map((item) => candid_arg_value_to_js(item, ty.vec.subtype, runtime_values));
+        return values.some((value) => value === undefined) ? undefined : values;
+    }
+    if ('record' in self && 'record' in ty) {
+        const record: Record<string, any> = {};
+        for (const [key, subtype] of ty.record.subitems) {
+            const field = self.record.find((field) => field.key === key);
+            const value =
+                field === undefined
+                    ? candid_required(subtype)
+                    : candid_arg_value_to_js(field.value, subtype, runtime_values);
+            if (value === undefined) return undefined;
+            record[key] = value;
+        }
+        return record;
+    }
+    if ('variant' in self && 'variant' in ty) {
+        const { tag, value } = self.variant;
+        const subtype = ty.variant.subitems.find(([key]) => key === tag)?.[1];
+        let v: any = null;
+        if (value !== undefined && subtype !== undefined) v = candid_arg_value_to_js(value, subtype, runtime_values);
+        else if (subtype !== undefined) v = candid_required(subtype);
+        if (v === undefined) return undefined;
+        return { [tag]: v };
+    }
+    if ('tuple' in self && 'tuple' in ty) {
+        const values = self.tuple.map((item, i) => candid_arg_value_to_js(item, ty.tuple.subitems[i], runtime_values));
+        return values.some((value) => value === undefined) ? undefined : values;
+    }
+    throw new Error('candid arg value is mismatched');
+};
+
+export const ic_call_arg_candid_to_js = (
+    self: IcCallArgCandid,
+    arg: WrappedCandidType[],
+    runtime_values: RuntimeValues,
+): any[] | undefined => {
+    const values: any[] = [];
+    for (let i = 0; i < arg.length; i++) {
+        const item = self.args[i];
+        const value =
+            item === undefined ? candid_required(arg[i]) : candid_arg_value_to_js(item, arg[i], runtime_values);
+        if (value === undefined) return undefined;
+        values.push(value);
+    }
+    return values;
+};
+
+export type IcCallArg = { code: IcCallArgCode } | { candid: IcCallArgCandid };
+
+export const match_ic_call_arg = <T>(
+    self: IcCallArg,
+    { code, candid }: { code: (code: IcCallArgCode) => T; candid: (candid: IcCallArgCandid) => T },
+): T => {
     if ('code' in self) return code(self.code);
+    if ('candid' in self) return candid(self.candid);
     throw new Error('invalid ic call arg');
 };
 
 export const match_ic_call_arg_async = async <T>(
     self: IcCallArg,
-    { code }: { code: (code: IcCallArgCode) => Promise<T> },
+    {
+        code,
+        candid,
+    }: { code: (code: IcCallArgCode) => Promise<T>; candid: (candid: IcCallArgCandid) => Promise<T> },
 ): Promise<T> => {
     if ('code' in self) return code(self.code);
+    if ('candid' in self) return candid(self.candid);
     throw new Error('invalid ic call arg');
 };
 
@@ -43,6 +195,7 @@ export const ic_call_arg_get_used_component = (self: IcCallArg): ComponentId[] =
     const used: ComponentId[] = [];
     match_ic_call_arg(self, {
         code: (code) => used.push(...ic_call_arg_code_get_used_component(code)),
+        candid: (candid) => used.push(...ic_call_arg_candid_get_used_component(candid)),
     });
     return used;
 };
@@ -80,6 +233,12 @@ export const parse_ic_call_action_arg = async (
                         unwrapped.push(value); // returns directly
                         return unwrapped;
                     },
+                    candid: async (candid) => {
+                        const values = ic_call_arg_candid_to_js(candid, arg, runtime_values);
+                        if (values === undefined) return undefined;
+                        unwrapped.push(...values);
+                        return unwrapped;
+                    },
                 });
                 if (flag === undefined) return undefined; // failed
             } else {
@@ -104,6 +263,12 @@ export const parse_ic_call_action_arg = async (
                         for (const v of value) unwrapped.push(v);
                         return unwrapped;
                     },
+                    candid: async (candid) => {
+                        const values = ic_call_arg_candid_to_js(candid, arg, runtime_values);
+                        if (values === undefined) return undefined;
+                        unwrapped.push(...values);
+                        return unwrapped;
+                    },
                 });
                 if (flag === undefined) return undefined; // failed
             } else {
@@ -1,3 +1,4 @@
+import { ConditionMatches } from '.';
 import { InputValue } from '../../common/refer';
 
 export type ConditionArrayCompare =
@@ -12,7 +13,9 @@ export type ConditionArrayCompare =
     | { length_greater: InputValue }
     | { length_greater_equal: InputValue }
     | { length_less: InputValue }
-    | { length_less_equal: InputValue };
+    | { length_less_equal: InputValue }
+    | { any: ConditionMatches } // subtype of array
+    | { all: ConditionMatches }; // subtype of array
 
 export const match_condition_array_compare = <T>(
     self: ConditionArrayCompare,
@@ -29,6 +32,8 @@ export const match_condition_array_compare = <T>(
         length_greater_equal,
         length_less,
         length_less_equal,
+        any,
+        all,
     }: {
         none: () => T;
         not_null: () => T;
@@ -42,6 +47,8 @@ export const match_condition_array_compare = <T>(
         length_greater_equal: (length_greater_equal: InputValue) => T;
         length_less: (length_less: InputValue) => T;
         length_less_equal: (length_less_equal: InputValue) => T;
+        any: (any: ConditionMatches) => T;
+        all: (all: ConditionMatches) => T;
     },
 ): T => {
     if (self === 'null') return none();
@@ -57,6 +64,8 @@ export const match_condition_array_compare = <T>(
         if ('length_greater_equal' in self) return length_greater_equal(self.length_greater_equal);
         if ('length_less' in self) return length_less(self.length_less);
         if ('length_less_equal' in self) return length_less_equal(self.length_less_equal);
+        if ('any' in self) return any(self.any);
+        if ('all' in self) return all(self.all);
     }
     throw new Error('Invalid condition array compare');
 };
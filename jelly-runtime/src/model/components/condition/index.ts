@@ -7,7 +7,7 @@ import { all_endpoints_find_output_type, AllEndpoints, Endpoint } from '../../co
 import { input_value_get_used_component, InputValue, ReferValue } from '../../common/refer';
 import { ConditionArrayCompare, match_condition_array_compare } from './array';
 import { ConditionBoolCompare, match_condition_bool_compare } from './bool';
-import { ConditionNumberCompare, match_condition_number_compare } from './number';
+import { ConditionNumberCompare, ConditionRange, match_condition_number_compare } from './number';
 import { ConditionObjectCompare, match_condition_object_compare } from './object';
 import { ConditionTextCompare, match_condition_text_compare } from './text';
 
@@ -136,10 +136,26 @@ const condition_get_used_component = (self: Condition): ComponentId[] => {
 const condition_item_get_used_component = (self: ConditionItem): ComponentId[] => {
     const used = [];
     used.push(self.value.endpoint.id);
+    used.push(...condition_matches_get_used_component(self.matches));
+    return used;
+};
+
+const condition_matches_get_used_component = (self: ConditionMatches): ComponentId[] => {
+    const used: ComponentId[] = [];
     const parse_input_value = (value: InputValue) => {
         used.push(...input_value_get_used_component(value));
     };
-    match_condition_matches(self.matches, {
+    const parse_range = (range: ConditionRange) => {
+        parse_input_value(range.min);
+        parse_input_value(range.max);
+    };
+    const parse_input_values = (values: InputValue[]) => {
+        for (const value of values) parse_input_value(value);
+    };
+    const parse_matches = (matches: ConditionMatches) => {
+        used.push(...condition_matches_get_used_component(matches));
+    };
+    match_condition_matches(self, {
         text: (text) =>
             match_condition_text_compare(text, {
                 none: () => {
@@ -196,6 +212,10 @@ const condition_item_get_used_component = (self: ConditionItem): ComponentId[] =
                 greater_equal: parse_input_value,
                 less: parse_input_value,
                 less_equal: parse_input_value,
+                between: parse_range,
+                not_between: parse_range,
+                in_set: parse_input_values,
+                not_in_set: parse_input_values,
             }),
         number: (number) =>
             match_condition_number_compare(number, {
@@ -211,6 +231,10 @@ const condition_item_get_used_component = (self: ConditionItem): ComponentId[] =
                 greater_equal: parse_input_value,
                 less: parse_input_value,
                 less_equal: parse_input_value,
+                between: parse_range,
+                not_between: parse_range,
+                in_set: parse_input_values,
+                not_in_set: parse_input_values,
             }),
         array: (array) =>
             match_condition_array_compare(array, {
@@ -230,6 +254,8 @@ const condition_item_get_used_component = (self: ConditionItem): ComponentId[] =
                 length_greater_equal: parse_input_value,
                 length_less: parse_input_value,
                 length_less_equal: parse_input_value,
+                any: parse_matches,
+                all: parse_matches,
             }),
         object: (object) =>
             match_condition_object_compare(object, {
@@ -338,7 +364,26 @@ const assert_condition_item = (
         console.error('type is mismatch', output, value);
         throw new Error(`type is mismatch`);
     }
-    return match_condition_matches(self.matches, {
+    return assert_condition_matches(self.matches, output, value, runtime_values);
+};
+
+const find_input_values = <T>(runtime_values: RuntimeValues, values: InputValue[], type: LinkType): T[] | undefined => {
+    const found: T[] = [];
+    for (const value of values) {
+        const v = runtime_values.find_input_value<T>(value, type);
+        if (v === undefined) return undefined;
+        found.push(v);
+    }
+    return found;
+};
+
+const assert_condition_matches = (
+    self: ConditionMatches,
+    output: LinkType,
+    value: any,
+    runtime_values: RuntimeValues,
+): boolean | undefined => {
+    return match_condition_matches(self, {
         text: (text) =>
             match_condition_text_compare(text, {
                 none: () => value === undefined,
@@ -530,6 +575,36 @@ const assert_condition_item = (
                         runtime_values.find_input_value<number>(less_equal, 'integer'),
                         (left, right) => left <= right,
                     ),
+                between: (between) =>
+                    check_value_and_execute(
+                        value,
+                        'integer',
+                        find_input_values<number>(runtime_values, [between.min, between.max], 'integer'),
+                        (left, [min, max]) => min <= left && left <= max,
+                    ),
+                not_between: (not_between) =>
+                    check_value_and_execute(
+                        value,
+                        'integer',
+                        find_input_values<number>(runtime_values, [not_between.min, not_between.max], 'integer'),
+                        (left, [min, max]) => min <= left && left <= max,
+                        false,
+                    ),
+                in_set: (in_set) =>
+                    check_value_and_execute(
+                        value,
+                        'integer',
+                        find_input_values<number>(runtime_values, in_set, 'integer'),
+                        (left, right) => right.some((r: number) => same(left, r)),
+                    ),
+                not_in_set: (not_in_set) =>
+                    check_value_and_execute(
+                        value,
+                        'integer',
+                        find_input_values<number>(runtime_values, not_in_set, 'integer'),
+                        (left, right) => right.some((r: number) => same(left, r)),
+                        false,
+                    ),
             }),
         number: (number) =>
             match_condition_number_compare(number, {
@@ -578,11 +653,41 @@ const assert_condition_item = (
                         runtime_values.find_input_value<number>(less_equal, 'number'),
                         (left, right) => left <= right,
                     ),
+                between: (between) =>
+                    check_value_and_execute(
+                        value,
+                        'number',
+                        find_input_values<number>(runtime_values, [between.min, between.max], 'number'),
+                        (left, [min, max]) => min <= left && left <= max,
+                    ),
+                not_between: (not_between) =>
+                    check_value_and_execute(
+                        value,
+                        'number',
+                        find_input_values<number>(runtime_values, [not_between.min, not_between.max], 'number'),
+                        (left, [min, max]) => min <= left && left <= max,
+                        false,
+                    ),
+                in_set: (in_set) =>
+                    check_value_and_execute(
+                        value,
+                        'number',
+                        find_input_values<number>(runtime_values, in_set, 'number'),
+                        (left, right) => right.some((r: number) => same(left, r)),
+                    ),
+                not_in_set: (not_in_set) =>
+                    check_value_and_execute(
+                        value,
+                        'number',
+                        find_input_values<number>(runtime_values, not_in_set, 'number'),
+                        (left, right) => right.some((r: number) => same(left, r)),
+                        false,
+                    ),
             }),
         array: (array) =>
             match_condition_array_compare(array, {
                 none: () => value === undefined,
-                not_null: () => value === undefined,
+                not_null: () => value !== undefined,
                 equal: (equal) =>
                     check_value_and_execute(value, output, runtime_values.find_input_value<any>(equal, output), same),
                 not_equal: (not_equal) =>
@@ -650,6 +755,26 @@ const assert_condition_item = (
                         runtime_values.find_input_value<number>(length_less_equal, 'integer'),
                         (left, right) => left.length <= right,
                     ),
+                any: (any) =>
+                    check_value_and_execute(value, output, true, (left: any[]) => {
+                        const subtype = (output as { array: LinkType }).array;
+                        for (const item of left) {
+                            const r = assert_condition_matches(any, subtype, item, runtime_values);
+                            if (r === undefined) return undefined;
+                            if (r) return true;
+                        }
+                        return false;
+                    }),
+                all: (all) =>
+                    check_value_and_execute(value, output, true, (left: any[]) => {
+                        const subtype = (output as { array: LinkType }).array;
+                        for (const item of left) {
+                            const r = assert_condition_matches(all, subtype, item, runtime_values);
+                            if (r === undefined) return undefined;
+                            if (!r) return false;
+                        }
+                        return true;
+                    }),
             }),
         object: (object) =>
             match_condition_object_compare(object, {
@@ -685,7 +810,7 @@ const assert_condition_item = (
                         output,
                         runtime_values.find_input_value<any>(contains_value),
                         (left, right) => {
-                            for (const item of (output as unknown as { output: ObjectSubitem[] }).output) {
+                            for (const item of (output as { object: ObjectSubitem[] }).object) {
                                 if (!link_type_is_match_js_value(item.ty, right)) continue;
                                 if (same(left[item.key], right)) {
                                     return true;
@@ -701,7 +826,7 @@ const assert_condition_item = (
                         runtime_values.find_input_value<any>(not_contains_value),
                         (left, right) => {
                             return !(() => {
-                                for (const item of (output as unknown as { output: ObjectSubitem[] }).output) {
+                                for (const item of (output as { object: ObjectSubitem[] }).object) {
                                     if (link_type_is_match_js_value(item.ty, right) && same(left[item.key], right)) {
                                         return true;
                                     }
@@ -850,6 +975,18 @@ const condition_item_get_expected_component = (self: ConditionItem): ComponentId
                 less_equal: () => {
                     /* do nothing */
                 },
+                between: () => {
+                    /* do nothing */
+                },
+                not_between: () => {
+                    /* do nothing */
+                },
+                in_set: () => {
+                    /* do nothing */
+                },
+                not_in_set: () => {
+                    /* do nothing */
+                },
             }),
         number: (number) =>
             match_condition_number_compare(number, {
@@ -875,6 +1012,18 @@ const condition_item_get_expected_component = (self: ConditionItem): ComponentId
                 less_equal: () => {
                     /* do nothing */
                 },
+                between: () => {
+                    /* do nothing */
+                },
+                not_between: () => {
+                    /* do nothing */
+                },
+                in_set: () => {
+                    /* do nothing */
+                },
+                not_in_set: () => {
+                    /* do nothing */
+                },
             }),
         array: (array) =>
             match_condition_array_compare(array, {
@@ -912,6 +1061,12 @@ const condition_item_get_expected_component = (self: ConditionItem): ComponentId
                 length_less_equal: () => {
                     /* do nothing */
                 },
+                any: () => {
+                    /* do nothing */
+                },
+                all: () => {
+                    /* do nothing */
+                },
             }),
         object: (object) =>
             match_condition_object_compare(object, {
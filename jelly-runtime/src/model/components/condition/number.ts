@@ -8,7 +8,17 @@ export type ConditionNumberCompare =
     | { greater: InputValue }
     | { greater_equal: InputValue }
     | { less: InputValue }
-    | { less_equal: InputValue };
+    | { less_equal: InputValue }
+    | { between: ConditionRange }
+    | { not_between: ConditionRange }
+    | { in_set: InputValue[] }
+    | { not_in_set: InputValue[] };
+
+// both ends are included
+export interface ConditionRange {
+    min: InputValue;
+    max: InputValue;
+}
 
 export const match_condition_number_compare = <T>(
     self: ConditionNumberCompare,
@@ -21,6 +31,10 @@ export const match_condition_number_compare = <T>(
         greater_equal,
         less,
         less_equal,
+        between,
+        not_between,
+        in_set,
+        not_in_set,
     }: {
         none: () => T;
         not_null: () => T;
@@ -30,6 +44,10 @@ export const match_condition_number_compare = <T>(
         greater_equal: (greater_equal: InputValue) => T;
         less: (less: InputValue) => T;
         less_equal: (less_equal: InputValue) => T;
+        between: (between: ConditionRange) => T;
+        not_between: (not_between: ConditionRange) => T;
+        in_set: (in_set: InputValue[]) => T;
+        not_in_set: (not_in_set: InputValue[]) => T;
     },
 ): T => {
     if (self === 'null') return none();
@@ -41,6 +59,10 @@ export const match_condition_number_compare = <T>(
         if ('greater_equal' in self) return greater_equal(self.greater_equal);
         if ('less' in self) return less(self.less);
         if ('less_equal' in self) return less_equal(self.less_equal);
+        if ('between' in self) return between(self.between);
+        if ('not_between' in self) return not_between(self.not_between);
+        if ('in_set' in self) return in_set(self.in_set);
+        if ('not_in_set' in self) return not_in_set(self.not_in_set);
     }
     throw new Error('Invalid condition number compare');
 };
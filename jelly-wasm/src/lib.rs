@@ -1,5 +1,6 @@
 #![doc = include_str!("../README.md")]
 
+use jelly_executor::{console::LogEntry, report::ExecutionReport};
 use jelly_model::model::types::check::CheckedCombined;
 use wasm_bindgen::prelude::*;
 
@@ -32,6 +33,29 @@ impl From<JellyResult> for String {
     }
 }
 
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct JellyReport {
+    result: JellyResult,
+    logs: Vec<LogEntry>,
+    duration: u64,
+}
+
+impl From<ExecutionReport> for JellyReport {
+    fn from(value: ExecutionReport) -> Self {
+        Self {
+            result: value.result.map_err(|err| format!("{:?}", err)).into(),
+            logs: value.logs,
+            duration: value.duration,
+        }
+    }
+}
+
+impl From<JellyReport> for String {
+    fn from(value: JellyReport) -> Self {
+        serde_json::to_string(&value).unwrap_or_default()
+    }
+}
+
 // ===================== Execute code =====================
 
 /// execute code
@@ -60,6 +84,86 @@ pub fn execute_validate_code(code: &str, value: &str) -> String {
     result.into()
 }
 
+/// execute code with limits
+///
+/// # Arguments
+///
+/// * `code` - Code
+/// * `args` - The string of parameter name and parameter. For example "[]" or "[[\"data\",\"{}\"]]"
+/// * `options` - Execute options. For example "{\"max_loop_iterations\":1000,\"deadline\":1700000000000}"
+#[wasm_bindgen]
+pub fn execute_code_with_options(code: &str, args: &str, options: &str) -> String {
+    fn inner(code: &str, args: &str, options: &str) -> Result<String, String> {
+        let options = serde_json::from_str(options).map_err(|err| format!("{:?}", err))?;
+        jelly_executor::execute_code_with_options(code, args, &options).map_err(|err| format!("{:?}", err))
+    }
+
+    let result = inner(code, args, options);
+    let result: JellyResult = result.into();
+    result.into()
+}
+
+/// execute validate code with limits
+///
+/// # Arguments
+///
+/// * `code` - Code
+/// * `value` - Verified value. For example "{\"text\":\"text\""}"
+/// * `options` - Execute options. For example "{\"max_loop_iterations\":1000,\"deadline\":1700000000000}"
+#[wasm_bindgen]
+pub fn execute_validate_code_with_options(code: &str, value: &str, options: &str) -> String {
+    fn inner(code: &str, value: &str, options: &str) -> Result<String, String> {
+        let options = serde_json::from_str(options).map_err(|err| format!("{:?}", err))?;
+        jelly_executor::execute_validate_code_with_options(code, value, &options).map_err(|err| format!("{:?}", err))
+    }
+
+    let result = inner(code, value, options);
+    let result: JellyResult = result.into();
+    result.into()
+}
+
+/// execute code, the logs of console and time are reported
+///
+/// # Arguments
+///
+/// * `code` - Code
+/// * `args` - The string of parameter name and parameter. For example "[]" or "[[\"data\",\"{}\"]]"
+/// * `options` - Execute options. For example "{\"max_loop_iterations\":1000,\"deadline\":1700000000000}"
+#[wasm_bindgen]
+pub fn execute_code_with_report(code: &str, args: &str, options: &str) -> String {
+    let report: ExecutionReport = match serde_json::from_str(options) {
+        Ok(options) => jelly_executor::execute_code_with_report(code, args, &options),
+        Err(err) => Err(jelly_executor::error::ExecuteCodeError::InvalidArgs(format!(
+            "{:?}",
+            err
+        )))
+        .into(),
+    };
+    let report: JellyReport = report.into();
+    report.into()
+}
+
+/// execute validate code, the logs of console and time are reported
+///
+/// # Arguments
+///
+/// * `code` - Code
+/// * `value` - Verified value. For example "{\"text\":\"text\""}"
+/// * `options` - Execute options. For example "{\"max_loop_iterations\":1000,\"deadline\":1700000000000}"
+#[wasm_bindgen]
+pub fn execute_validate_code_with_report(code: &str, value: &str, options: &str) -> String {
+    let report: ExecutionReport = match serde_json::from_str(options) {
+        Ok(options) => jelly_executor::execute_validate_code_with_report(code, value, &options),
+        Err(err) => Err(jelly_executor::error::ExecuteCodeError::InvalidArgs(format!(
+            "{:?}",
+            err
+        )))
+        .into(),
+    };
+    let report: JellyReport = report.into();
+    report.into()
+}
+
 // ===================== parse candid =====================
 
 /// parse candid
@@ -233,6 +337,135 @@ pub fn check(components: &str, fetch: &str) -> String {
     result.into()
 }
 
+/// Checking with all problems
+///
+/// # Arguments
+///
+/// * `components` - components
+/// * `fetch` - check function
+#[wasm_bindgen]
+pub fn check_diagnostics(components: &str, fetch: &str) -> String {
+    use jelly_model::model::LinkComponent;
+    use jelly_model::model::types::check::ApisCheckFunction;
+
+    fn inner(components: &str, fetch: &str) -> Result<String, String> {
+        let components: Vec<LinkComponent> =
+            serde_json::from_str(components).map_err(|e| format!("parse components failed: {}", e))?;
+        let fetch: ApisCheckFunction =
+            serde_json::from_str(fetch).map_err(|e| format!("parse ApisCheckFunction failed: {}", e))?;
+        let diagnostics = jelly_model::model::check::check_diagnostics(&components, &fetch);
+        serde_json::to_string(&diagnostics).map_err(|e| format!("stringify diagnostics failed: {}", e))
+    }
+
+    let result = inner(components, fetch);
+    let result: JellyResult = result.into();
+    result.into()
+}
+
+/// Lint the components
+///
+/// # Arguments
+///
+/// * `components` - components
+/// * `options` - lint options
+#[wasm_bindgen]
+pub fn lint(components: &str, options: &str) -> String {
+    use jelly_model::model::LinkComponent;
+    use jelly_model::model::lint::LintOptions;
+
+    fn inner(components: &str, options: &str) -> Result<String, String> {
+        let components: Vec<LinkComponent> =
+            serde_json::from_str(components).map_err(|e| format!("parse components failed: {}", e))?;
+        let options: LintOptions =
+            serde_json::from_str(options).map_err(|e| format!("parse lint options failed: {}", e))?;
+        let warnings = jelly_model::model::lint::lint(&components, &options);
+        serde_json::to_string(&warnings).map_err(|e| format!("stringify warnings failed: {}", e))
+    }
+
+    let result = inner(components, options);
+    let result: JellyResult = result.into();
+    result.into()
+}
+
+/// Diff the components of two versions
+///
+/// # Arguments
+///
+/// * `old` - old components
+/// * `new` - new components
+#[wasm_bindgen]
+pub fn diff_components(old: &str, new: &str) -> String {
+    use jelly_model::model::LinkComponent;
+
+    fn inner(old: &str, new: &str) -> Result<String, String> {
+        let old: Vec<LinkComponent> =
+            serde_json::from_str(old).map_err(|e| format!("parse old components failed: {}", e))?;
+        let new: Vec<LinkComponent> =
+            serde_json::from_str(new).map_err(|e| format!("parse new components failed: {}", e))?;
+        let diff = jelly_model::model::diff::diff(&old, &new);
+        serde_json::to_string(&diff).map_err(|e| format!("stringify diff failed: {}", e))
+    }
+
+    let result = inner(old, new);
+    let result: JellyResult = result.into();
+    result.into()
+}
+
+/// Apply the diff to the components
+///
+/// # Arguments
+///
+/// * `components` - old components
+/// * `diff` - the diff of components
+#[wasm_bindgen]
+pub fn patch_components(components: &str, diff: &str) -> String {
+    use jelly_model::model::LinkComponent;
+    use jelly_model::model::diff::ComponentsDiff;
+
+    fn inner(components: &str, diff: &str) -> Result<String, String> {
+        let components: Vec<LinkComponent> =
+            serde_json::from_str(components).map_err(|e| format!("parse components failed: {}", e))?;
+        let diff: ComponentsDiff = serde_json::from_str(diff).map_err(|e| format!("parse diff failed: {}", e))?;
+        let patched = jelly_model::model::diff::patch(&components, &diff).map_err(|e| e.to_string())?;
+        serde_json::to_string(&patched).map_err(|e| format!("stringify components failed: {}", e))
+    }
+
+    let result = inner(components, diff);
+    let result: JellyResult = result.into();
+    result.into()
+}
+
+/// Upgrade the combined json of any previous version to current
+///
+/// # Arguments
+///
+/// * `json` - combined json
+#[wasm_bindgen]
+pub fn migrate_combined_json(json: &str) -> String {
+    fn inner(json: &str) -> Result<String, String> {
+        let combined = jelly_model::model::migration::migrate_combined_json(json).map_err(|e| e.to_string())?;
+        serde_json::to_string(&combined).map_err(|e| format!("stringify combined json failed: {}", e))
+    }
+
+    let result = inner(json);
+    let result: JellyResult = result.into();
+    result.into()
+}
+
+/// The messages of link errors, keyed by stable code
+/// The placeholders such as {from} are the fields of link error
+#[wasm_bindgen]
+pub fn error_catalog() -> String {
+    fn inner() -> Result<String, String> {
+        serde_json::to_string(jelly_model::model::common::error::message::CATALOG)
+            .map_err(|e| format!("stringify catalog failed: {}", e))
+    }
+
+    let result = inner();
+    let result: JellyResult = result.into();
+    result.into()
+}
+
 /// Final checking
 ///
 /// # Arguments
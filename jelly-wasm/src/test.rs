@@ -16,7 +16,7 @@ fn test() {
 
 #[cfg(test)]
 mod tests {
-    use jelly_executor::error::ExecuteCodeError;
+    use jelly_executor::error::{ExecuteCodeError, ExecuteErrorDetail};
 
     #[test]
     fn test() {
@@ -24,7 +24,11 @@ mod tests {
         println!("Error: {:?}", ExecuteCodeError::InvalidOutput("123".into()));
         println!("Error: {:?}", ExecuteCodeError::Undefined);
         println!("Error: {:?}", ExecuteCodeError::WrongOutput("123".into()));
-        println!("Error: {:?}", ExecuteCodeError::ExecuteError("123".into()));
+        println!(
+            "Error: {:?}",
+            ExecuteCodeError::ExecuteError(ExecuteErrorDetail::new("Error", "123"))
+        );
+        println!("Error: {:?}", ExecuteCodeError::LimitExceeded("123".into()));
     }
 
     #[test]
@@ -39,4 +43,71 @@ mod tests {
         );
         println!("Result: {:?}", result1);
     }
+
+    #[test]
+    fn test_options() {
+        let result = crate::execute_code_with_options(r#"while (true) {}"#, "[]", r#"{"max_loop_iterations":1000}"#);
+        println!("Result: {:?}", result);
+        assert!(result.contains("LimitExceeded"));
+    }
+
+    #[test]
+    fn test_report() {
+        let result = crate::execute_code_with_report(r#"console.warn("warning"); result = 1;"#, "[]", "{}");
+        println!("Result: {:?}", result);
+        assert!(
+            result.starts_with(r#"{"result":{"ok":"1"},"logs":[{"level":"warn","message":"warning"}],"duration":"#)
+        );
+    }
+
+    #[test]
+    fn test_check_diagnostics() {
+        let components = r#"[
+            {"param":{"id":1,"metadata":{"name":"a"}}},
+            {"param":{"id":2,"metadata":{"name":"a"}}}
+        ]"#;
+        let fetch = r#"{"canister_id":"aaaaa-aa","codes":{},"apis":{},"combines":{},"origin_apis":{"hash_origins":{},"key_hashes":{}},"compiled":[]}"#;
+        let result = crate::check_diagnostics(components, fetch);
+        println!("Result: {:?}", result);
+        assert!(result.contains(r#"\"severity\":\"error\",\"component\":2"#));
+        assert!(result.contains("DuplicateParamName"));
+    }
+
+    #[test]
+    fn test_lint() {
+        let components = r#"[{"param":{"id":1,"metadata":{"name":"a"}}}]"#;
+        let result = crate::lint(components, "{}");
+        println!("Result: {:?}", result);
+        assert!(result.contains(r#"\"rule\":\"unused_param\""#));
+        let result = crate::lint(components, r#"{"allow":["unused_param"]}"#);
+        assert_eq!(result, r#"{"ok":"[]"}"#);
+    }
+
+    #[test]
+    fn test_diff_components() {
+        let old = r#"[{"param":{"id":1,"metadata":{"name":"a"}}}]"#;
+        let new = r#"[{"param":{"id":1,"metadata":{"name":"b"}}}]"#;
+        let result: serde_json::Value = serde_json::from_str(&crate::diff_components(old, new)).unwrap_or_default();
+        let diff = result["ok"].as_str().unwrap_or_default();
+        assert!(diff.contains(r#""removed_param""#));
+        let result = crate::patch_components(old, diff);
+        assert_eq!(result, serde_json::json!({ "ok": new }).to_string());
+    }
+
+    #[test]
+    fn test_migrate_combined_json() {
+        let result = crate::migrate_combined_json(r#"{"components":[],"nodes":[]}"#);
+        assert!(result.contains(&format!(
+            r#"\"version\":\"{}\""#,
+            jelly_model::model::migration::CURRENT_VERSION
+        )));
+        let result = crate::migrate_combined_json(r#"{"version":"99.0.0","components":[],"nodes":[]}"#);
+        assert!(result.starts_with(r#"{"err":"version 99.0.0 is newer"#));
+    }
+
+    #[test]
+    fn test_error_catalog() {
+        let result = crate::error_catalog();
+        assert!(result.contains(r#"\"code\":\"E0215\",\"name\":\"MismatchedInlets\""#));
+    }
 }